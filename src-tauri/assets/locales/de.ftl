@@ -0,0 +1,165 @@
+# Rapid Reporter report texts (German). Missing messages fall back to `en.ftl`.
+
+## Headings and labels
+
+label-report-title = Rapid-Reporter-Sitzung
+label-tester = Tester
+label-charter = Charter
+label-library-charter = Charter aus der Bibliothek
+label-started = Beginn
+label-duration = Dauer
+label-paused = Pausiert
+label-environment = Umgebung
+label-summary = Zusammenfassung
+label-task-breakdown = Aufgabenverteilung
+label-referenced-tickets = Referenzierte Tickets
+label-tag-index = Tag-Index
+label-notes = Notizen
+label-note = Notiz
+label-note-link = Notiz
+label-screenshot = Screenshot
+label-recording = Aufzeichnung
+label-attachment = Anhang
+label-copy-failed = Kopieren fehlgeschlagen
+label-bug-report-draft = Entwurf des Fehlerberichts
+label-figure = Abbildung
+label-generated-by = Erstellt mit Rapid Reporter
+label-note-type = Notiztyp
+label-count = Anzahl
+
+## Notes table (CSV / XLSX) columns
+
+label-index = Nr.
+label-timestamp = Zeitpunkt
+label-type = Typ
+label-text = Text
+label-path = Pfad
+
+## Environment
+
+label-os = Betriebssystem
+label-screens = Bildschirme
+label-locale = Gebietsschema
+label-app-version = App-Version
+
+## Task breakdown
+
+label-task = Aufgabe
+label-focus = Fokus
+label-time = Zeit
+label-share = Anteil
+label-session-setup = Sitzungsvorbereitung
+label-test-design = Testentwurf und -durchführung
+label-bug-investigation = Fehleranalyse und -meldung
+label-opportunity = Gelegenheit
+
+## Durations
+
+minutes =
+    { $count ->
+        [one] { $count } Minute
+       *[other] { $count } Minuten
+    }
+short-minutes = { $count } Min.
+short-hours = { $count } Std.
+duration-planned-actual = { $planned } geplant, { $actual } tatsächlich
+duration-actual = { $actual } tatsächlich
+duration-over = { $over } überzogen
+
+## Links to notes
+
+note-links =
+    { $count ->
+        [one] Notiz
+       *[other] Notizen
+    }
+
+## Note types
+
+type-bug = Fehler
+count-bug = { $count } Fehler
+group-bug = Fehler
+type-idea = Idee
+count-idea =
+    { $count ->
+        [one] { $count } Idee
+       *[other] { $count } Ideen
+    }
+group-idea = Ideen
+type-observation = Beobachtung
+count-observation =
+    { $count ->
+        [one] { $count } Beobachtung
+       *[other] { $count } Beobachtungen
+    }
+group-observation = Beobachtungen
+type-question = Frage
+count-question =
+    { $count ->
+        [one] { $count } Frage
+       *[other] { $count } Fragen
+    }
+group-question = Fragen
+type-warning = Warnung
+count-warning =
+    { $count ->
+        [one] { $count } Warnung
+       *[other] { $count } Warnungen
+    }
+group-warning = Warnungen
+
+## PDF, Word and OpenDocument copies
+
+document-title = Rapid-Reporter-Sitzung – { $charter }
+page-footer = Seite { $page } von { $total }
+
+## Bug report drafts
+
+bug-draft-title = Fehler: { $title }
+label-reported-by = Gemeldet von
+label-description = Beschreibung
+label-steps-to-reproduce = Schritte zum Reproduzieren
+label-evidence = Belege
+label-not-exported = nicht exportiert
+bug-draft-no-steps = Vor diesem Fehler wurden keine Testnotizen erfasst.
+
+## Debriefs
+
+debrief-title = Rapid-Reporter-Debriefing
+label-sessions = Sitzungen
+label-testers = Tester
+label-period = Zeitraum
+label-tester-time = Testzeit
+label-planned = Geplant
+label-actual = Tatsächlich
+label-notes-by-type = Notizen nach Typ
+count-session =
+    { $count ->
+        [one] { $count } Sitzung
+       *[other] { $count } Sitzungen
+    }
+debrief-time-planned = { $planned } geplant
+debrief-time-actual = { $actual } tatsächlich von { $planned } geplant
+debrief-untimed =
+    keine Zeitmessung für { $count ->
+        [one] Sitzung
+       *[other] Sitzungen
+    } { $sessions } ({ $planned } geplant)
+debrief-session = Sitzung { $number }
+debrief-session-tester = Sitzung { $number }, { $tester }
+
+## Dates, e.g. "1. März 2025"
+
+date = { $day }. { $month } { $year }
+month-1 = Januar
+month-2 = Februar
+month-3 = März
+month-4 = April
+month-5 = Mai
+month-6 = Juni
+month-7 = Juli
+month-8 = August
+month-9 = September
+month-10 = Oktober
+month-11 = November
+month-12 = Dezember
@@ -0,0 +1,173 @@
+# Rapid Reporter report texts (English). Every other catalog falls back to this one for
+# messages it does not translate.
+#
+# Messages starting with `label-` are also available to report templates as
+# `labels.<name>` in camelCase, e.g. `label-report-title` is `{{labels.reportTitle}}`.
+
+## Headings and labels
+
+label-report-title = Rapid Reporter Session
+label-tester = Tester
+label-charter = Charter
+label-library-charter = Library charter
+label-started = Started
+label-duration = Duration
+label-paused = Paused
+label-environment = Environment
+label-summary = Summary
+label-task-breakdown = Task Breakdown
+label-referenced-tickets = Referenced Tickets
+label-tag-index = Tag Index
+label-notes = Notes
+label-note = Note
+label-note-link = note
+label-screenshot = Screenshot
+label-recording = Recording
+label-attachment = Attachment
+label-copy-failed = copy failed
+label-bug-report-draft = bug report draft
+label-figure = Figure
+label-generated-by = Generated by Rapid Reporter
+label-note-type = Note type
+label-count = Count
+
+## Notes table (CSV / XLSX) columns
+
+label-index = Index
+label-timestamp = Timestamp
+label-type = Type
+label-text = Text
+label-path = Path
+
+## Environment
+
+label-os = OS
+label-screens = Screens
+label-locale = Locale
+label-app-version = App version
+
+## Task breakdown
+
+label-task = Task
+label-focus = Focus
+label-time = Time
+label-share = Share
+label-session-setup = Session setup
+label-test-design = Test design and execution
+label-bug-investigation = Bug investigation and reporting
+label-opportunity = Opportunity
+
+## Durations
+
+minutes =
+    { $count ->
+        [one] { $count } minute
+       *[other] { $count } minutes
+    }
+short-minutes = { $count } min
+short-hours = { $count } h
+duration-planned-actual = { $planned } planned, { $actual } actual
+duration-actual = { $actual } actual
+duration-over = { $over } over
+
+## Links to notes, e.g. "notes 1, 5"
+
+note-links =
+    { $count ->
+        [one] note
+       *[other] notes
+    }
+
+## Note types: name, count (Summary) and section heading (grouped layout)
+
+type-bug = Bug
+count-bug =
+    { $count ->
+        [one] { $count } Bug
+       *[other] { $count } Bugs
+    }
+group-bug = Bugs
+type-idea = Idea
+count-idea =
+    { $count ->
+        [one] { $count } Idea
+       *[other] { $count } Ideas
+    }
+group-idea = Ideas
+type-observation = Observation
+count-observation =
+    { $count ->
+        [one] { $count } Observation
+       *[other] { $count } Observations
+    }
+group-observation = Observations
+type-question = Question
+count-question =
+    { $count ->
+        [one] { $count } Question
+       *[other] { $count } Questions
+    }
+group-question = Questions
+type-warning = Warning
+count-warning =
+    { $count ->
+        [one] { $count } Warning
+       *[other] { $count } Warnings
+    }
+group-warning = Warnings
+
+## PDF, Word and OpenDocument copies
+
+document-title = Rapid Reporter Session – { $charter }
+page-footer = Page { $page } of { $total }
+
+## Bug report drafts
+
+bug-draft-title = Bug: { $title }
+label-reported-by = Reported by
+label-description = Description
+label-steps-to-reproduce = Steps to Reproduce
+label-evidence = Evidence
+label-not-exported = not exported
+bug-draft-no-steps = No test notes were recorded before this bug.
+
+## Debriefs
+
+debrief-title = Rapid Reporter Debrief
+label-sessions = Sessions
+label-testers = Testers
+label-period = Period
+label-tester-time = Tester time
+label-planned = Planned
+label-actual = Actual
+label-notes-by-type = Notes by Type
+count-session =
+    { $count ->
+        [one] { $count } session
+       *[other] { $count } sessions
+    }
+debrief-time-planned = { $planned } planned
+debrief-time-actual = { $actual } actual of { $planned } planned
+debrief-untimed =
+    no clock data for { $count ->
+        [one] session
+       *[other] sessions
+    } { $sessions } ({ $planned } planned)
+debrief-session = session { $number }
+debrief-session-tester = session { $number }, { $tester }
+
+## Dates, e.g. "1 March 2025"
+
+date = { $day } { $month } { $year }
+month-1 = January
+month-2 = February
+month-3 = March
+month-4 = April
+month-5 = May
+month-6 = June
+month-7 = July
+month-8 = August
+month-9 = September
+month-10 = October
+month-11 = November
+month-12 = December
@@ -0,0 +1,174 @@
+# Rapid Reporter report texts (French). Missing messages fall back to `en.ftl`.
+# French uses the singular for 0 and 1 ("0 minute", "1 minute", "2 minutes").
+
+## Headings and labels
+
+label-report-title = Session Rapid Reporter
+label-tester = Testeur
+label-charter = Charte
+label-library-charter = Charte de la bibliothèque
+label-started = Début
+label-duration = Durée
+label-paused = En pause
+label-environment = Environnement
+label-summary = Résumé
+label-task-breakdown = Répartition des tâches
+label-referenced-tickets = Tickets référencés
+label-tag-index = Index des tags
+label-notes = Notes
+label-note = Note
+label-note-link = note
+label-screenshot = Capture d’écran
+label-recording = Enregistrement
+label-attachment = Pièce jointe
+label-copy-failed = échec de la copie
+label-bug-report-draft = brouillon de rapport de bogue
+label-figure = Figure
+label-generated-by = Généré par Rapid Reporter
+label-note-type = Type de note
+label-count = Nombre
+
+## Notes table (CSV / XLSX) columns
+
+label-index = N°
+label-timestamp = Horodatage
+label-type = Type
+label-text = Texte
+label-path = Chemin
+
+## Environment
+
+label-os = Système d’exploitation
+label-screens = Écrans
+label-locale = Paramètres régionaux
+label-app-version = Version de l’application
+
+## Task breakdown
+
+label-task = Tâche
+label-focus = Orientation
+label-time = Temps
+label-share = Part
+label-session-setup = Préparation de la session
+label-test-design = Conception et exécution des tests
+label-bug-investigation = Analyse et signalement des bogues
+label-opportunity = Opportunité
+
+## Durations
+
+minutes =
+    { $count ->
+        [one] { $count } minute
+       *[other] { $count } minutes
+    }
+short-minutes = { $count } min
+short-hours = { $count } h
+duration-planned-actual = prévu : { $planned }, réel : { $actual }
+duration-actual = réel : { $actual }
+duration-over = { $over } de dépassement
+
+## Links to notes
+
+note-links =
+    { $count ->
+        [one] note
+       *[other] notes
+    }
+
+## Note types
+
+type-bug = Bogue
+count-bug =
+    { $count ->
+        [one] { $count } bogue
+       *[other] { $count } bogues
+    }
+group-bug = Bogues
+type-idea = Idée
+count-idea =
+    { $count ->
+        [one] { $count } idée
+       *[other] { $count } idées
+    }
+group-idea = Idées
+type-observation = Observation
+count-observation =
+    { $count ->
+        [one] { $count } observation
+       *[other] { $count } observations
+    }
+group-observation = Observations
+type-question = Question
+count-question =
+    { $count ->
+        [one] { $count } question
+       *[other] { $count } questions
+    }
+group-question = Questions
+type-warning = Avertissement
+count-warning =
+    { $count ->
+        [one] { $count } avertissement
+       *[other] { $count } avertissements
+    }
+group-warning = Avertissements
+
+## PDF, Word and OpenDocument copies
+
+document-title = Session Rapid Reporter – { $charter }
+page-footer = Page { $page } sur { $total }
+
+## Bug report drafts
+
+bug-draft-title = Bogue : { $title }
+label-reported-by = Signalé par
+label-description = Description
+label-steps-to-reproduce = Étapes pour reproduire
+label-evidence = Preuves
+label-not-exported = non exporté
+bug-draft-no-steps = Aucune note de test n’a été saisie avant ce bogue.
+
+## Debriefs
+
+debrief-title = Débriefing Rapid Reporter
+label-sessions = Sessions
+label-testers = Testeurs
+label-period = Période
+label-tester-time = Temps de test
+label-planned = Prévu
+label-actual = Réel
+label-notes-by-type = Notes par type
+count-session =
+    { $count ->
+        [one] { $count } session
+       *[other] { $count } sessions
+    }
+debrief-time-planned = prévu : { $planned }
+debrief-time-actual = réel : { $actual } sur { $planned } prévues
+debrief-untimed =
+    pas de chronométrage pour { $count ->
+        [one] la session
+       *[other] les sessions
+    } { $sessions } (prévu : { $planned })
+debrief-session = session { $number }
+debrief-session-tester = session { $number }, { $tester }
+
+## Dates, e.g. "1er mars 2025"
+
+date =
+    { $day ->
+        [1] 1er
+       *[other] { $day }
+    } { $month } { $year }
+month-1 = janvier
+month-2 = février
+month-3 = mars
+month-4 = avril
+month-5 = mai
+month-6 = juin
+month-7 = juillet
+month-8 = août
+month-9 = septembre
+month-10 = octobre
+month-11 = novembre
+month-12 = décembre
@@ -0,0 +1,183 @@
+use crate::models::{ActivityStatus, PausedInterval, SessionActivity, TaskBreakdown};
+use chrono::Local;
+use std::sync::Mutex;
+
+/// A stretch of time spent on one activity (epoch ms; `ended_at` is open for the current one).
+#[derive(Debug, Clone)]
+struct ActivitySegment {
+    activity: SessionActivity,
+    on_charter: bool,
+    started_at: i64,
+    ended_at: Option<i64>,
+}
+
+/// Timestamped activity changes for one session.
+#[derive(Debug)]
+pub(crate) struct ActivityLog {
+    session_started_at: i64,
+    segments: Vec<ActivitySegment>,
+}
+
+impl ActivityLog {
+    pub(crate) fn new(session_started_at: i64) -> Self {
+        ActivityLog {
+            session_started_at,
+            segments: Vec::new(),
+        }
+    }
+
+    /// Switches to `activity` at `now`, closing the current segment. Repeating the current
+    /// activity keeps the running segment.
+    pub(crate) fn switch(&mut self, activity: SessionActivity, on_charter: bool, now: i64) {
+        if let Some(current) = self.segments.last_mut() {
+            if current.activity == activity && current.on_charter == on_charter {
+                return;
+            }
+            current.ended_at = Some(now);
+        }
+
+        self.segments.push(ActivitySegment {
+            activity,
+            on_charter,
+            started_at: now,
+            ended_at: None,
+        });
+    }
+
+    fn current(&self) -> Option<ActivityStatus> {
+        self.segments.last().map(|segment| ActivityStatus {
+            activity: segment.activity,
+            on_charter: segment.on_charter,
+            since: segment.started_at,
+        })
+    }
+
+    /// Totals time per activity up to `now`, leaving out time the session clock was paused.
+    pub(crate) fn breakdown(&self, now: i64, paused: &[PausedInterval]) -> TaskBreakdown {
+        let mut breakdown = TaskBreakdown::default();
+
+        for segment in &self.segments {
+            let start = segment.started_at;
+            let end = segment.ended_at.unwrap_or(now).max(start);
+
+            let paused_ms: i64 = paused
+                .iter()
+                .map(|p| (end.min(p.ended_at) - start.max(p.started_at)).max(0))
+                .sum();
+            let seconds = ((end - start - paused_ms).max(0) / 1000) as u64;
+
+            match segment.activity {
+                SessionActivity::Setup => breakdown.setup_seconds += seconds,
+                SessionActivity::Test => breakdown.test_seconds += seconds,
+                SessionActivity::Bug => breakdown.bug_seconds += seconds,
+            }
+            if segment.on_charter {
+                breakdown.charter_seconds += seconds;
+            } else {
+                breakdown.opportunity_seconds += seconds;
+            }
+        }
+
+        breakdown
+    }
+}
+
+/// Managed state tracking the current SBTM activity of the running session.
+#[derive(Default)]
+pub(crate) struct ActivityTracker(Mutex<Option<ActivityLog>>);
+
+impl ActivityTracker {
+    /// Task breakdown for the session that started at `started_at`, if anything was tracked.
+    pub(crate) fn breakdown_for(
+        &self,
+        started_at: i64,
+        paused: &[PausedInterval],
+    ) -> Option<TaskBreakdown> {
+        let guard = self.0.lock().ok()?;
+        guard
+            .as_ref()
+            .filter(|log| log.session_started_at == started_at && !log.segments.is_empty())
+            .map(|log| log.breakdown(Local::now().timestamp_millis(), paused))
+    }
+}
+
+/// Records that the tester switched to a different SBTM activity.
+///
+/// `started_at` identifies the session; switching within a new session starts a fresh log.
+/// `on_charter` defaults to `true` (work on the charter rather than an opportunity).
+#[tauri::command]
+pub(crate) fn set_session_activity(
+    tracker: tauri::State<'_, ActivityTracker>,
+    started_at: i64,
+    activity: SessionActivity,
+    on_charter: Option<bool>,
+) -> Result<ActivityStatus, String> {
+    let mut guard = tracker.0.lock().map_err(|e| e.to_string())?;
+
+    if guard
+        .as_ref()
+        .is_none_or(|log| log.session_started_at != started_at)
+    {
+        *guard = Some(ActivityLog::new(started_at));
+    }
+
+    let log = guard
+        .as_mut()
+        .ok_or_else(|| "Activity log is unavailable.".to_string())?;
+    log.switch(
+        activity,
+        on_charter.unwrap_or(true),
+        Local::now().timestamp_millis(),
+    );
+
+    log.current()
+        .ok_or_else(|| "Activity log is unavailable.".to_string())
+}
+
+/// Returns the activity currently being tracked, if any.
+#[tauri::command]
+pub(crate) fn session_activity(
+    tracker: tauri::State<'_, ActivityTracker>,
+) -> Result<Option<ActivityStatus>, String> {
+    let guard = tracker.0.lock().map_err(|e| e.to_string())?;
+    Ok(guard.as_ref().and_then(ActivityLog::current))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: i64 = 60_000;
+
+    #[test]
+    fn breakdown_totals_time_per_activity_and_charter() {
+        let mut log = ActivityLog::new(0);
+        log.switch(SessionActivity::Setup, true, 0);
+        log.switch(SessionActivity::Test, true, 5 * MIN);
+        log.switch(SessionActivity::Test, true, 10 * MIN);
+        log.switch(SessionActivity::Bug, false, 40 * MIN);
+
+        let breakdown = log.breakdown(50 * MIN, &[]);
+
+        assert_eq!(breakdown.setup_seconds, 5 * 60);
+        assert_eq!(breakdown.test_seconds, 35 * 60);
+        assert_eq!(breakdown.bug_seconds, 10 * 60);
+        assert_eq!(breakdown.charter_seconds, 40 * 60);
+        assert_eq!(breakdown.opportunity_seconds, 10 * 60);
+    }
+
+    #[test]
+    fn paused_time_is_not_counted() {
+        let mut log = ActivityLog::new(0);
+        log.switch(SessionActivity::Test, true, 0);
+
+        let paused = [PausedInterval {
+            started_at: 10 * MIN,
+            ended_at: 25 * MIN,
+            started_at_iso: None,
+            ended_at_iso: None,
+        }];
+
+        assert_eq!(log.breakdown(30 * MIN, &paused).test_seconds, 15 * 60);
+    }
+}
@@ -16,6 +16,79 @@ pub(crate) fn platform_os() -> String {
     std::env::consts::OS.to_string()
 }
 
+/// Collects the environment details shown in the report header.
+///
+/// Covers OS name/version/architecture, connected screens (resolution and device pixel
+/// ratio), system locale and app version. User-supplied key/values are added by the frontend.
+#[tauri::command]
+pub(crate) fn collect_environment(app: tauri::AppHandle) -> crate::models::Environment {
+    use crate::models::{Environment, ScreenInfo};
+    use tauri::Manager;
+
+    let screens = app
+        .get_webview_window("main")
+        .and_then(|main| main.available_monitors().ok())
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| ScreenInfo {
+            name: monitor.name().cloned(),
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+        })
+        .collect();
+
+    Environment {
+        os: Some(platform_os()),
+        os_version: os_version(),
+        arch: Some(std::env::consts::ARCH.to_string()),
+        screens,
+        locale: sys_locale::get_locale(),
+        app_version: Some(app_version()),
+        custom: Vec::new(),
+    }
+}
+
+/// Best-effort OS release string (e.g. `14.5` on macOS, `Ubuntu 24.04 LTS` on Linux).
+fn os_version() -> Option<String> {
+    use std::process::Command;
+
+    let run = |program: &str, args: &[&str]| {
+        let mut command = Command::new(program);
+        // Keep `cmd` from flashing a console window (CREATE_NO_WINDOW).
+        #[cfg(windows)]
+        std::os::windows::process::CommandExt::creation_flags(&mut command, 0x0800_0000);
+        command
+            .args(args)
+            .output()
+            .ok()
+            .filter(|out| out.status.success())
+            .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    match std::env::consts::OS {
+        "macos" => run("sw_vers", &["-productVersion"]),
+        // `ver` prints e.g. "Microsoft Windows [Version 10.0.22631.3593]".
+        "windows" => run("cmd", &["/C", "ver"]).map(|v| {
+            v.split_once("[Version ")
+                .and_then(|(_, rest)| rest.strip_suffix(']'))
+                .map(str::to_string)
+                .unwrap_or(v)
+        }),
+        "linux" => std::fs::read_to_string("/etc/os-release")
+            .ok()
+            .and_then(|release| {
+                release.lines().find_map(|line| {
+                    line.strip_prefix("PRETTY_NAME=")
+                        .map(|v| v.trim_matches('"').to_string())
+                })
+            })
+            .or_else(|| run("uname", &["-r"])),
+        _ => None,
+    }
+}
+
 /// Creates a unique copy of an existing screenshot file next to the original.
 ///
 /// This avoids filename collisions when the screenshot plugin reuses file names.
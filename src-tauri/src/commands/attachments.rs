@@ -0,0 +1,111 @@
+use crate::models::AttachmentInfo;
+use chrono::Local;
+use std::path::{Path, PathBuf};
+
+/// Snapshots an arbitrary evidence file (log, HAR, JSON payload…) into the session staging area.
+///
+/// The file is copied at capture time so later edits or log rotation do not change what gets
+/// exported. The returned `path` is what the frontend stores as an `attachment` note.
+#[tauri::command]
+pub(crate) fn attach_file(path: String) -> Result<AttachmentInfo, String> {
+    let src = PathBuf::from(&path);
+    if !src.is_file() {
+        return Err(format!("Attachment file does not exist: {}", path));
+    }
+
+    let file_name = src
+        .file_name()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| "Could not determine attachment filename".to_string())?
+        .to_string();
+
+    // One folder per snapshot keeps the original filename intact for the export.
+    let millis = Local::now().timestamp_millis();
+    let staging_root = staging_root();
+    let mut staging_dir = staging_root.join(millis.to_string());
+    let mut counter = 1u32;
+    while staging_dir.exists() {
+        staging_dir = staging_root.join(format!("{}-{}", millis, counter));
+        counter += 1;
+    }
+    std::fs::create_dir_all(&staging_dir).map_err(|e| e.to_string())?;
+
+    let dst = staging_dir.join(&file_name);
+    std::fs::copy(&src, &dst).map_err(|e| e.to_string())?;
+
+    let (size_bytes, sha256) = file_digest(&dst)?;
+
+    Ok(AttachmentInfo {
+        path: dst.to_string_lossy().to_string(),
+        file_name,
+        size_bytes,
+        sha256,
+    })
+}
+
+/// Folder holding one sub-folder per attachment snapshot.
+fn staging_root() -> PathBuf {
+    std::env::temp_dir()
+        .join("rapid-reporter")
+        .join("attachments")
+}
+
+/// Deletes the snapshot folder of a staged attachment once the export has its own copy.
+/// Paths outside the staging area are left alone.
+pub(crate) fn remove_staged(path: &Path) {
+    if let Some(dir) = path.parent() {
+        if dir.parent() == Some(staging_root().as_path()) {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Deletes every staged attachment. Sessions only live in memory, so at startup anything left
+/// here belongs to a session that was discarded without being exported.
+pub(crate) fn clear_staging() {
+    let _ = std::fs::remove_dir_all(staging_root());
+}
+
+/// Returns the size in bytes and lowercase hex SHA-256 of a file.
+pub(crate) fn file_digest(path: &Path) -> Result<(u64, String), String> {
+    use sha2::{Digest, Sha256};
+
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let size = std::io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
+
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::test_support::TempDir;
+
+    #[test]
+    fn attached_files_are_snapshotted_with_size_and_hash() {
+        let dir = TempDir::new("attach");
+        let src = dir.join("gateway.log");
+        std::fs::write(&src, "hello").unwrap();
+
+        let info = attach_file(src.to_string_lossy().to_string()).unwrap();
+        // Later changes to the original do not reach the snapshot.
+        std::fs::write(&src, "changed").unwrap();
+        let staged = PathBuf::from(&info.path);
+        let snapshot = std::fs::read_to_string(&staged);
+        remove_staged(&staged);
+        // Files outside the staging area are never removed.
+        remove_staged(&src);
+
+        assert_eq!(info.file_name, "gateway.log");
+        assert_eq!(info.size_bytes, 5);
+        assert_eq!(
+            info.sha256,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(snapshot.unwrap(), "hello");
+        assert!(!staged.parent().unwrap().exists());
+        assert!(src.exists());
+        assert!(attach_file(dir.join("missing.log").to_string_lossy().to_string()).is_err());
+    }
+}
@@ -0,0 +1,386 @@
+use crate::models::{CharterDraft, CharterRef, CharterSelection, LibraryCharter};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const LIBRARY_FILE: &str = "charters.json";
+
+/// Serialises read-modify-write cycles on the library file across concurrent commands.
+static LIBRARY_LOCK: Mutex<()> = Mutex::new(());
+
+/// On-disk layout of the charter library.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct CharterLibrary {
+    #[serde(default)]
+    charters: Vec<LibraryCharter>,
+}
+
+impl CharterLibrary {
+    /// The charters carrying `tag` (all when unset), most used first.
+    fn list(mut self, tag: Option<&str>) -> Vec<LibraryCharter> {
+        if let Some(tag) = tag.map(str::trim).filter(|t| !t.is_empty()) {
+            let tag = tag.trim_start_matches('#');
+            self.charters
+                .retain(|c| c.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+        }
+
+        self.charters.sort_by(|a, b| {
+            b.usage_count
+                .cmp(&a.usage_count)
+                .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+        });
+
+        self.charters
+    }
+
+    fn find_mut(&mut self, id: &str) -> Result<&mut LibraryCharter, String> {
+        self.charters
+            .iter_mut()
+            .find(|c| c.id == id)
+            .ok_or_else(|| format!("Library charter not found: {}", id))
+    }
+
+    /// Adds a validated draft, created at `now` (epoch ms).
+    fn create(&mut self, draft: CharterDraft, now: i64) -> LibraryCharter {
+        let mut id = format!("charter-{}", now);
+        let mut counter = 1u32;
+        while self.charters.iter().any(|c| c.id == id) {
+            id = format!("charter-{}-{}", now, counter);
+            counter += 1;
+        }
+
+        let charter = LibraryCharter {
+            id,
+            title: draft.title.trim().to_string(),
+            explore: draft.explore.trim().to_string(),
+            with: draft.with.trim().to_string(),
+            to_discover: draft.to_discover.trim().to_string(),
+            tags: normalise_tags(draft.tags),
+            usage_count: 0,
+            created_at: now,
+            updated_at: now,
+            last_used_at: None,
+        };
+
+        self.charters.push(charter.clone());
+        charter
+    }
+
+    fn update(
+        &mut self,
+        id: &str,
+        draft: CharterDraft,
+        now: i64,
+    ) -> Result<LibraryCharter, String> {
+        let charter = self.find_mut(id)?;
+
+        charter.title = draft.title.trim().to_string();
+        charter.explore = draft.explore.trim().to_string();
+        charter.with = draft.with.trim().to_string();
+        charter.to_discover = draft.to_discover.trim().to_string();
+        charter.tags = normalise_tags(draft.tags);
+        charter.updated_at = now;
+
+        Ok(charter.clone())
+    }
+
+    fn delete(&mut self, id: &str) -> Result<(), String> {
+        let before = self.charters.len();
+        self.charters.retain(|c| c.id != id);
+
+        if self.charters.len() == before {
+            return Err(format!("Library charter not found: {}", id));
+        }
+        Ok(())
+    }
+
+    fn use_charter(&mut self, id: &str, now: i64) -> Result<CharterSelection, String> {
+        let charter = self.find_mut(id)?;
+
+        charter.usage_count += 1;
+        charter.last_used_at = Some(now);
+
+        Ok(CharterSelection {
+            charter: CharterRef {
+                id: charter.id.clone(),
+                title: charter.title.clone(),
+            },
+            text: charter_text(charter),
+        })
+    }
+}
+
+fn library_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    use tauri::Manager;
+
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(LIBRARY_FILE))
+}
+
+fn load_library(path: &Path) -> Result<CharterLibrary, String> {
+    if !path.exists() {
+        return Ok(CharterLibrary::default());
+    }
+
+    let raw = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&raw)
+        .map_err(|e| format!("Charter library {} is invalid: {}", path.display(), e))
+}
+
+fn save_library(path: &Path, library: &CharterLibrary) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let json = serde_json::to_string_pretty(library).map_err(|e| e.to_string())?;
+
+    // Write then rename so a crash mid-write never leaves a truncated library behind.
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+/// Loads the library, applies `f`, and saves it back under the library lock.
+fn modify_library<T>(
+    app: &tauri::AppHandle,
+    f: impl FnOnce(&mut CharterLibrary) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = LIBRARY_LOCK.lock().map_err(|e| e.to_string())?;
+
+    let path = library_path(app)?;
+    let mut library = load_library(&path)?;
+    let result = f(&mut library)?;
+    save_library(&path, &library)?;
+
+    Ok(result)
+}
+
+/// Renders a library entry using the Explore / With / To discover charter template.
+pub(crate) fn charter_text(charter: &LibraryCharter) -> String {
+    let mut lines = vec![format!("Explore {}", charter.explore.trim())];
+    if !charter.with.trim().is_empty() {
+        lines.push(format!("With {}", charter.with.trim()));
+    }
+    if !charter.to_discover.trim().is_empty() {
+        lines.push(format!("To discover {}", charter.to_discover.trim()));
+    }
+
+    lines.join("\n")
+}
+
+/// Trims tags and drops empty or case-insensitive duplicates, keeping first-seen order.
+fn normalise_tags(tags: Vec<String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').to_string();
+        if !tag.is_empty() && !out.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            out.push(tag);
+        }
+    }
+    out
+}
+
+fn validate(draft: &CharterDraft) -> Result<(), String> {
+    if draft.title.trim().is_empty() {
+        return Err("A library charter needs a title.".to_string());
+    }
+    if draft.explore.trim().is_empty() {
+        return Err("A library charter needs an \"Explore\" target.".to_string());
+    }
+    Ok(())
+}
+
+/// Lists library charters, most used first, optionally limited to those carrying `tag`.
+#[tauri::command]
+pub(crate) fn list_charters(
+    app: tauri::AppHandle,
+    tag: Option<String>,
+) -> Result<Vec<LibraryCharter>, String> {
+    let _guard = LIBRARY_LOCK.lock().map_err(|e| e.to_string())?;
+    Ok(load_library(&library_path(&app)?)?.list(tag.as_deref()))
+}
+
+/// Adds a charter to the library.
+#[tauri::command]
+pub(crate) fn create_charter(
+    app: tauri::AppHandle,
+    draft: CharterDraft,
+) -> Result<LibraryCharter, String> {
+    validate(&draft)?;
+    modify_library(&app, |library| {
+        Ok(library.create(draft, Local::now().timestamp_millis()))
+    })
+}
+
+/// Replaces the editable fields of a library charter, keeping its usage statistics.
+#[tauri::command]
+pub(crate) fn update_charter(
+    app: tauri::AppHandle,
+    id: String,
+    draft: CharterDraft,
+) -> Result<LibraryCharter, String> {
+    validate(&draft)?;
+    modify_library(&app, |library| {
+        library.update(&id, draft, Local::now().timestamp_millis())
+    })
+}
+
+/// Removes a charter from the library.
+#[tauri::command]
+pub(crate) fn delete_charter(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    modify_library(&app, |library| library.delete(&id))
+}
+
+/// Picks a library charter for a new session: bumps its usage count and returns the
+/// rendered charter text plus the reference to send back with the session on export.
+#[tauri::command]
+pub(crate) fn use_charter(app: tauri::AppHandle, id: String) -> Result<CharterSelection, String> {
+    modify_library(&app, |library| {
+        library.use_charter(&id, Local::now().timestamp_millis())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::test_support::TempDir;
+
+    fn charter(explore: &str, with: &str, to_discover: &str) -> LibraryCharter {
+        LibraryCharter {
+            id: "charter-1".to_string(),
+            title: "Checkout".to_string(),
+            explore: explore.to_string(),
+            with: with.to_string(),
+            to_discover: to_discover.to_string(),
+            tags: Vec::new(),
+            usage_count: 0,
+            created_at: 0,
+            updated_at: 0,
+            last_used_at: None,
+        }
+    }
+
+    #[test]
+    fn charter_text_follows_template_and_skips_blank_parts() {
+        assert_eq!(
+            charter_text(&charter(
+                "the checkout flow",
+                "expired cards",
+                "payment error handling"
+            )),
+            "Explore the checkout flow\nWith expired cards\nTo discover payment error handling"
+        );
+        assert_eq!(
+            charter_text(&charter("the login page", " ", "")),
+            "Explore the login page"
+        );
+    }
+
+    #[test]
+    fn tags_are_trimmed_and_deduplicated() {
+        assert_eq!(
+            normalise_tags(vec![
+                " payments ".to_string(),
+                "#Payments".to_string(),
+                "".to_string(),
+                "mobile".to_string(),
+            ]),
+            vec!["payments", "mobile"]
+        );
+    }
+
+    fn draft(title: &str, tags: &[&str]) -> CharterDraft {
+        CharterDraft {
+            title: title.to_string(),
+            explore: " the checkout flow ".to_string(),
+            with: "expired cards".to_string(),
+            to_discover: String::new(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn charters_are_created_updated_and_deleted() {
+        let mut library = CharterLibrary::default();
+        let first = library.create(draft("Checkout", &["#payments"]), 100);
+        let second = library.create(draft("Refunds", &[]), 100);
+
+        assert_eq!(first.id, "charter-100");
+        assert_eq!(second.id, "charter-100-1");
+        assert_eq!(first.explore, "the checkout flow");
+        assert_eq!(first.tags, vec!["payments"]);
+
+        let updated = library
+            .update(&first.id, draft("Checkout v2", &["mobile"]), 200)
+            .unwrap();
+        assert_eq!(updated.title, "Checkout v2");
+        assert_eq!(updated.tags, vec!["mobile"]);
+        assert_eq!((updated.created_at, updated.updated_at), (100, 200));
+
+        library.delete(&second.id).unwrap();
+        assert_eq!(library.charters.len(), 1);
+        assert_eq!(
+            library.delete(&second.id).unwrap_err(),
+            "Library charter not found: charter-100-1"
+        );
+        assert!(library.update("nope", draft("x", &[]), 300).is_err());
+    }
+
+    #[test]
+    fn using_a_charter_counts_it_and_lists_it_first() {
+        let mut library = CharterLibrary::default();
+        library.create(draft("Alpha", &["payments"]), 100);
+        let beta = library.create(draft("Beta", &[]), 100);
+
+        let selection = library.use_charter(&beta.id, 500).unwrap();
+        assert_eq!(selection.charter.title, "Beta");
+        assert_eq!(
+            selection.text,
+            "Explore the checkout flow\nWith expired cards"
+        );
+
+        let listed = CharterLibrary {
+            charters: library.charters.clone(),
+        }
+        .list(None);
+        assert_eq!(listed[0].title, "Beta");
+        assert_eq!(listed[0].usage_count, 1);
+        assert_eq!(listed[0].last_used_at, Some(500));
+
+        let tagged = CharterLibrary {
+            charters: library.charters.clone(),
+        }
+        .list(Some("#Payments"));
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].title, "Alpha");
+        assert!(library.use_charter("nope", 600).is_err());
+    }
+
+    #[test]
+    fn library_is_saved_as_json_and_loaded_back() {
+        let dir = TempDir::new("charters");
+        let path = dir.join("config").join(LIBRARY_FILE);
+        assert!(load_library(&path).unwrap().charters.is_empty());
+
+        let mut library = CharterLibrary::default();
+        let charter = library.create(draft("Checkout", &["payments"]), 100);
+        save_library(&path, &library).unwrap();
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(raw.contains("\"toDiscover\": \"\""));
+        assert!(raw.contains("\"usageCount\": 0"));
+        assert!(!path.with_extension("json.tmp").exists());
+        let loaded = load_library(&path).unwrap().charters;
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].id, charter.id);
+        assert_eq!(loaded[0].tags, vec!["payments"]);
+
+        std::fs::write(&path, "{").unwrap();
+        assert!(load_library(&path)
+            .err()
+            .unwrap()
+            .starts_with("Charter library"));
+    }
+}
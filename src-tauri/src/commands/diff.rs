@@ -0,0 +1,373 @@
+use crate::commands::{emit_to_main, NOTE_CAPTURED_EVENT};
+use crate::models::{DiffRegion, Note, ScreenshotDiff};
+use chrono::Local;
+use image::{Rgba, RgbaImage};
+use std::collections::VecDeque;
+
+/// Default per-channel difference (0-255) below which pixels count as unchanged.
+///
+/// Keeps anti-aliasing and compression noise from showing up as changes.
+const DEFAULT_THRESHOLD: u8 = 16;
+
+/// Changed pixels are grouped on a grid of this many pixels before regions are traced.
+const CELL_SIZE: u32 = 16;
+
+const CHANGED_COLOUR: Rgba<u8> = Rgba([255, 0, 64, 255]);
+const REGION_COLOUR: Rgba<u8> = Rgba([0, 120, 255, 255]);
+
+/// Pixel-level comparison of two images, including the rendered diff image.
+pub(crate) struct DiffOutcome {
+    pub(crate) image: RgbaImage,
+    pub(crate) similarity: f64,
+    pub(crate) changed_pixels: u64,
+    pub(crate) size_mismatch: bool,
+    pub(crate) regions: Vec<DiffRegion>,
+}
+
+/// Compares a "before" and "after" screenshot and writes a diff image beside the "after" file.
+///
+/// The diff and a short summary are also pushed to the main window as session notes.
+/// Images of different sizes are compared on a shared canvas, with the non-overlapping
+/// area reported as changed.
+#[tauri::command]
+pub(crate) async fn diff_screenshots(
+    app: tauri::AppHandle,
+    before: String,
+    after: String,
+    threshold: Option<u8>,
+) -> Result<ScreenshotDiff, String> {
+    let threshold = threshold.unwrap_or(DEFAULT_THRESHOLD);
+
+    let (diff, summary) = tauri::async_runtime::spawn_blocking(move || {
+        let before_img = image::open(&before).map_err(|e| e.to_string())?.to_rgba8();
+        let after_img = image::open(&after).map_err(|e| e.to_string())?.to_rgba8();
+
+        let outcome = diff_images(&before_img, &after_img, threshold);
+
+        let src = std::path::Path::new(&after);
+        let parent = src
+            .parent()
+            .ok_or("Could not determine screenshot directory")?;
+        let stem = src
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("screenshot");
+        let millis = Local::now().timestamp_millis();
+
+        let out_path = parent.join(format!("{}-diff-{}.png", stem, millis));
+        outcome.image.save(&out_path).map_err(|e| e.to_string())?;
+
+        let summary = describe(&outcome, &before, &after);
+
+        Ok::<_, String>((
+            ScreenshotDiff {
+                path: out_path.to_string_lossy().to_string(),
+                similarity: outcome.similarity,
+                changed_pixels: outcome.changed_pixels,
+                size_mismatch: outcome.size_mismatch,
+                regions: outcome.regions,
+            },
+            summary,
+        ))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let note = |note_type: &str, text: String| Note {
+        note_type: note_type.to_string(),
+        text,
+        timestamp: Some(Local::now().timestamp_millis()),
+        timestamp_iso: None,
+    };
+    let emitted =
+        emit_to_main(&app, NOTE_CAPTURED_EVENT, note("observation", summary)).and_then(|_| {
+            emit_to_main(
+                &app,
+                NOTE_CAPTURED_EVENT,
+                note("screenshot", diff.path.clone()),
+            )
+        });
+    if let Err(err) = emitted {
+        // No session note points at the diff image, so don't leave it behind.
+        let _ = std::fs::remove_file(&diff.path);
+        return Err(err);
+    }
+
+    Ok(diff)
+}
+
+/// One-line, human readable summary of a diff for the session log.
+fn describe(outcome: &DiffOutcome, before: &str, after: &str) -> String {
+    let file_name = |p: &str| {
+        std::path::Path::new(p)
+            .file_name()
+            .and_then(|s| s.to_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| p.to_string())
+    };
+
+    let areas = match outcome.regions.len() {
+        1 => "1 changed area".to_string(),
+        n => format!("{} changed areas", n),
+    };
+
+    let mut text = format!(
+        "Screenshot diff ({} → {}): {:.1}% similar, {}",
+        file_name(before),
+        file_name(after),
+        outcome.similarity * 100.0,
+        areas
+    );
+    if outcome.size_mismatch {
+        text.push_str(" (image sizes differ)");
+    }
+
+    text
+}
+
+/// Compares two images pixel by pixel on a canvas large enough to hold both.
+///
+/// A pixel counts as changed when any channel differs by more than `threshold`, or when it
+/// only exists in one of the images.
+pub(crate) fn diff_images(before: &RgbaImage, after: &RgbaImage, threshold: u8) -> DiffOutcome {
+    let width = before.width().max(after.width());
+    let height = before.height().max(after.height());
+    let size_mismatch = before.dimensions() != after.dimensions();
+
+    let cols = width.div_ceil(CELL_SIZE) as usize;
+    let rows = height.div_ceil(CELL_SIZE) as usize;
+    // Tight pixel bounds (min_x, min_y, max_x, max_y) of the changes inside each grid cell.
+    let mut cells: Vec<Option<(u32, u32, u32, u32)>> = vec![None; cols * rows];
+
+    let mut image = RgbaImage::new(width, height);
+    let mut changed_pixels = 0u64;
+
+    for y in 0..height {
+        for x in 0..width {
+            let b = pixel_at(before, x, y);
+            let a = pixel_at(after, x, y);
+
+            let changed = match (b, a) {
+                (Some(b), Some(a)) => {
+                    b.0.iter()
+                        .zip(a.0.iter())
+                        .any(|(l, r)| l.abs_diff(*r) > threshold)
+                }
+                _ => true,
+            };
+
+            if changed {
+                changed_pixels += 1;
+                image.put_pixel(x, y, CHANGED_COLOUR);
+
+                let idx = (y / CELL_SIZE) as usize * cols + (x / CELL_SIZE) as usize;
+                cells[idx] = Some(match cells[idx] {
+                    Some((x1, y1, x2, y2)) => (x1.min(x), y1.min(y), x2.max(x), y2.max(y)),
+                    None => (x, y, x, y),
+                });
+            } else {
+                let base = a.or(b).copied().unwrap_or(Rgba([255, 255, 255, 255]));
+                image.put_pixel(x, y, faded(base));
+            }
+        }
+    }
+
+    let regions = trace_regions(&cells, cols, rows);
+    for region in &regions {
+        outline(&mut image, region);
+    }
+
+    let total = width as u64 * height as u64;
+    let similarity = if total == 0 {
+        1.0
+    } else {
+        1.0 - changed_pixels as f64 / total as f64
+    };
+
+    DiffOutcome {
+        image,
+        similarity,
+        changed_pixels,
+        size_mismatch,
+        regions,
+    }
+}
+
+fn pixel_at(img: &RgbaImage, x: u32, y: u32) -> Option<&Rgba<u8>> {
+    if x < img.width() && y < img.height() {
+        Some(img.get_pixel(x, y))
+    } else {
+        None
+    }
+}
+
+/// Washes an unchanged pixel out to a light grey so highlighted changes stand out.
+fn faded(p: Rgba<u8>) -> Rgba<u8> {
+    let [r, g, b, _] = p.0;
+    let luma = (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64) as u32;
+    let v = (luma * 35 / 100 + 255 * 65 / 100).min(255) as u8;
+    Rgba([v, v, v, 255])
+}
+
+/// Merges touching changed cells (8-connected) into bounding boxes, sorted top-left first.
+fn trace_regions(
+    cells: &[Option<(u32, u32, u32, u32)>],
+    cols: usize,
+    rows: usize,
+) -> Vec<DiffRegion> {
+    let mut seen = vec![false; cells.len()];
+    let mut regions = Vec::new();
+
+    for start in 0..cells.len() {
+        if seen[start] || cells[start].is_none() {
+            continue;
+        }
+
+        let mut bounds = cells[start].unwrap();
+        let mut queue = VecDeque::from([start]);
+        seen[start] = true;
+
+        while let Some(idx) = queue.pop_front() {
+            let (x1, y1, x2, y2) = cells[idx].unwrap();
+            bounds = (
+                bounds.0.min(x1),
+                bounds.1.min(y1),
+                bounds.2.max(x2),
+                bounds.3.max(y2),
+            );
+
+            let (col, row) = ((idx % cols) as i64, (idx / cols) as i64);
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    let (c, r) = (col + dx, row + dy);
+                    if c < 0 || r < 0 || c >= cols as i64 || r >= rows as i64 {
+                        continue;
+                    }
+                    let next = r as usize * cols + c as usize;
+                    if !seen[next] && cells[next].is_some() {
+                        seen[next] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+
+        regions.push(DiffRegion {
+            x: bounds.0,
+            y: bounds.1,
+            width: bounds.2 - bounds.0 + 1,
+            height: bounds.3 - bounds.1 + 1,
+        });
+    }
+
+    regions.sort_by_key(|r| (r.y, r.x));
+    regions
+}
+
+/// Draws a 2px frame just outside a changed region.
+fn outline(image: &mut RgbaImage, region: &DiffRegion) {
+    let (w, h) = image.dimensions();
+    let x1 = region.x.saturating_sub(2);
+    let y1 = region.y.saturating_sub(2);
+    let x2 = (region.x + region.width + 1).min(w - 1);
+    let y2 = (region.y + region.height + 1).min(h - 1);
+
+    for x in x1..=x2 {
+        for y in [y1, y1 + 1, y2.saturating_sub(1), y2] {
+            image.put_pixel(x, y.min(h - 1), REGION_COLOUR);
+        }
+    }
+    for y in y1..=y2 {
+        for x in [x1, x1 + 1, x2.saturating_sub(1), x2] {
+            image.put_pixel(x.min(w - 1), y, REGION_COLOUR);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba([200, 200, 200, 255]))
+    }
+
+    fn paint(img: &mut RgbaImage, x: u32, y: u32, w: u32, h: u32) {
+        for py in y..y + h {
+            for px in x..x + w {
+                img.put_pixel(px, py, Rgba([0, 0, 0, 255]));
+            }
+        }
+    }
+
+    #[test]
+    fn identical_images_are_fully_similar() {
+        let img = canvas(64, 48);
+        let outcome = diff_images(&img, &img, DEFAULT_THRESHOLD);
+
+        assert_eq!(outcome.changed_pixels, 0);
+        assert_eq!(outcome.similarity, 1.0);
+        assert!(outcome.regions.is_empty());
+        assert!(!outcome.size_mismatch);
+    }
+
+    #[test]
+    fn small_differences_below_threshold_are_ignored() {
+        let before = canvas(32, 32);
+        let after = RgbaImage::from_pixel(32, 32, Rgba([210, 195, 200, 255]));
+
+        assert_eq!(diff_images(&before, &after, 16).changed_pixels, 0);
+        assert_eq!(diff_images(&before, &after, 4).changed_pixels, 32 * 32);
+    }
+
+    #[test]
+    fn separate_changes_become_separate_regions() {
+        let before = canvas(200, 200);
+        let mut after = before.clone();
+        paint(&mut after, 10, 12, 5, 4);
+        paint(&mut after, 150, 160, 20, 10);
+
+        let outcome = diff_images(&before, &after, DEFAULT_THRESHOLD);
+
+        assert_eq!(outcome.changed_pixels, 5 * 4 + 20 * 10);
+        assert_eq!(
+            outcome.regions,
+            vec![
+                DiffRegion {
+                    x: 10,
+                    y: 12,
+                    width: 5,
+                    height: 4
+                },
+                DiffRegion {
+                    x: 150,
+                    y: 160,
+                    width: 20,
+                    height: 10
+                },
+            ]
+        );
+        assert!(outcome.similarity < 1.0 && outcome.similarity > 0.99);
+    }
+
+    #[test]
+    fn size_mismatch_marks_extra_area_as_changed() {
+        let before = canvas(40, 40);
+        let after = canvas(40, 50);
+
+        let outcome = diff_images(&before, &after, DEFAULT_THRESHOLD);
+
+        assert!(outcome.size_mismatch);
+        assert_eq!(outcome.image.dimensions(), (40, 50));
+        assert_eq!(outcome.changed_pixels, 40 * 10);
+        assert_eq!(
+            outcome.regions,
+            vec![DiffRegion {
+                x: 0,
+                y: 40,
+                width: 40,
+                height: 10
+            }]
+        );
+    }
+}
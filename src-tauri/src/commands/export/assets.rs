@@ -42,3 +42,91 @@ pub(crate) fn copy_screenshot_asset(
 
     Ok(format!("assets/screenshots/{}", filename))
 }
+
+/// An attachment copied into the export folder, with the details shown in the report.
+pub(crate) struct AttachmentAsset {
+    pub(crate) rel_path: String,
+    pub(crate) file_name: String,
+    pub(crate) size_bytes: u64,
+    pub(crate) sha256: String,
+}
+
+/// Copies a staged attachment into `assets/attachments/` and returns its size and hash.
+///
+/// Existing files are never overwritten; clashing names get a numeric suffix.
+pub(crate) fn copy_attachment_asset(
+    export_dir: &std::path::Path,
+    absolute_path: &str,
+) -> Result<AttachmentAsset, String> {
+    let src = std::path::Path::new(absolute_path);
+    if !src.is_file() {
+        return Err(format!("Attachment file does not exist: {}", absolute_path));
+    }
+
+    let stem = src
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| "Could not determine attachment filename".to_string())?;
+    let ext = src.extension().and_then(|e| e.to_str());
+
+    let dest_dir = export_dir.join("assets/attachments");
+    std::fs::create_dir_all(&dest_dir).map_err(|e| e.to_string())?;
+
+    let name_for = |suffix: Option<u32>| {
+        let stem = match suffix {
+            Some(n) => format!("{}-{}", stem, n),
+            None => stem.to_string(),
+        };
+        match ext {
+            Some(ext) => format!("{}.{}", stem, ext),
+            None => stem,
+        }
+    };
+
+    let mut filename = name_for(None);
+    let mut counter = 1u32;
+    while dest_dir.join(&filename).exists() {
+        filename = name_for(Some(counter));
+        counter += 1;
+    }
+
+    let dest_path = dest_dir.join(&filename);
+    std::fs::copy(src, &dest_path).map_err(|e| e.to_string())?;
+
+    let (size_bytes, sha256) = crate::commands::attachments::file_digest(&dest_path)?;
+
+    Ok(AttachmentAsset {
+        rel_path: format!("assets/attachments/{}", filename),
+        file_name: filename,
+        size_bytes,
+        sha256,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::test_support::TempDir;
+
+    #[test]
+    fn attachments_are_copied_without_overwriting() {
+        let staged = TempDir::new("staged-attachment");
+        let src = staged.join("gateway.log");
+        std::fs::write(&src, "hello").unwrap();
+        let export_dir = TempDir::new("attachment-export");
+
+        let first = copy_attachment_asset(&export_dir, src.to_str().unwrap()).unwrap();
+        let second = copy_attachment_asset(&export_dir, src.to_str().unwrap()).unwrap();
+
+        assert_eq!(first.rel_path, "assets/attachments/gateway.log");
+        assert_eq!(second.rel_path, "assets/attachments/gateway-1.log");
+        assert_eq!(second.file_name, "gateway-1.log");
+        assert_eq!(first.size_bytes, 5);
+        assert_eq!(first.sha256, second.sha256);
+        assert_eq!(
+            std::fs::read_to_string(export_dir.join(&second.rel_path)).unwrap(),
+            "hello"
+        );
+        assert!(copy_attachment_asset(&export_dir, "/nonexistent/trace.har").is_err());
+    }
+}
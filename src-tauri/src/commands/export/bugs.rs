@@ -0,0 +1,462 @@
+use super::locale::Catalog;
+use super::template::url_path;
+use super::{assets, image_source, link_issue_in_report, markdown, tracker_label};
+use crate::commands::links::LinkRules;
+use crate::models::{IssueLink, Note, Session};
+use fluent_bundle::FluentValue;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// At most this many test notes before a bug become its reproduction steps.
+const MAX_STEPS: usize = 10;
+/// Screenshots/recordings attached to a draft, nearest to the bug note first.
+const MAX_SCREENSHOTS: usize = 3;
+const MAX_SNIPPETS: usize = 2;
+const MAX_TITLE_CHARS: usize = 80;
+
+/// A bug note expanded into a ticket-ready draft.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BugDraft {
+    pub(crate) number: usize,
+    /// Index of the bug note in the chronological note list.
+    pub(crate) note_index: usize,
+    pub(crate) title: String,
+    pub(crate) description: String,
+    pub(crate) steps: Vec<String>,
+    /// Indexes of the screenshot, recording and snippet notes used as evidence, chronological.
+    pub(crate) evidence: Vec<usize>,
+    /// File name under `bugs/` in the export folder.
+    pub(crate) file_name: String,
+}
+
+fn is_bug(note: &Note) -> bool {
+    note.note_type.eq_ignore_ascii_case("bug")
+}
+
+fn is_snippet(note: &Note) -> bool {
+    note.note_type.eq_ignore_ascii_case("snippet")
+}
+
+/// First line of the bug note, shortened on a word boundary for use as a ticket title.
+pub(crate) fn draft_title(text: &str) -> String {
+    let first_line = text.trim().lines().next().unwrap_or_default().trim();
+    if first_line.chars().count() <= MAX_TITLE_CHARS {
+        return first_line.to_string();
+    }
+
+    let cut: String = first_line.chars().take(MAX_TITLE_CHARS - 1).collect();
+    let cut = match cut.rfind(' ') {
+        Some(pos) if pos > MAX_TITLE_CHARS / 2 => &cut[..pos],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end())
+}
+
+fn slug(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.chars().count() >= 40 {
+            break;
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Builds one draft per bug note. `notes` must be in chronological order.
+///
+/// Reproduction steps are the test notes recorded since the previous bug. Evidence is picked
+/// from the screenshots and snippets around the bug (back to the previous bug, forward until
+/// testing carries on), nearest first.
+pub(crate) fn build_bug_drafts(notes: &[&Note]) -> Vec<BugDraft> {
+    let mut drafts = Vec::new();
+    let mut previous_bug: Option<usize> = None;
+
+    for (index, note) in notes.iter().enumerate() {
+        if !is_bug(note) {
+            continue;
+        }
+
+        let window_start = previous_bug.map(|i| i + 1).unwrap_or(0);
+        previous_bug = Some(index);
+
+        let mut steps: Vec<String> = notes[window_start..index]
+            .iter()
+            .filter(|n| n.note_type.eq_ignore_ascii_case("test"))
+            .map(|n| n.text.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|s| !s.is_empty())
+            .collect();
+        if steps.len() > MAX_STEPS {
+            steps.drain(..steps.len() - MAX_STEPS);
+        }
+
+        let window_end = notes[index + 1..]
+            .iter()
+            .position(|n| is_bug(n) || n.note_type.eq_ignore_ascii_case("test"))
+            .map(|offset| index + 1 + offset)
+            .unwrap_or(notes.len());
+
+        let mut candidates: Vec<usize> = (window_start..window_end)
+            .filter(|&i| image_source(notes[i]).is_some() || is_snippet(notes[i]))
+            .collect();
+        candidates.sort_by_key(|&i| (i.abs_diff(index), i));
+
+        let screenshots = candidates
+            .iter()
+            .filter(|&&i| image_source(notes[i]).is_some())
+            .take(MAX_SCREENSHOTS);
+        let snippets = candidates
+            .iter()
+            .filter(|&&i| is_snippet(notes[i]))
+            .take(MAX_SNIPPETS);
+        let mut evidence: Vec<usize> = screenshots.chain(snippets).copied().collect();
+        evidence.sort_unstable();
+
+        let number = drafts.len() + 1;
+        let title = draft_title(&note.text);
+        let file_name = match slug(&title).as_str() {
+            "" => format!("BUG-{:02}.md", number),
+            slug => format!("BUG-{:02}-{}.md", number, slug),
+        };
+
+        drafts.push(BugDraft {
+            number,
+            note_index: index,
+            title,
+            description: note.text.trim().to_string(),
+            steps,
+            evidence,
+            file_name,
+        });
+    }
+
+    drafts
+}
+
+/// Renders a draft as a standalone markdown file that lives in `bugs/` of the export folder.
+///
+/// `image_paths` maps screenshot source paths to their copied `assets/...` paths; ticket keys in
+/// the description and steps are linked with `rules`.
+pub(crate) fn render_bug_draft(
+    draft: &BugDraft,
+    session: &Session,
+    notes: &[&Note],
+    rules: &LinkRules,
+    image_paths: &HashMap<String, String>,
+    catalog: &Catalog,
+) -> String {
+    let title = catalog.message(
+        "bug-draft-title",
+        &[("title", FluentValue::from(draft.title.as_str()))],
+    );
+    let mut md = format!("# {}\n\n", title);
+
+    md.push_str(&format!(
+        "- **{}**: {}\n",
+        catalog.label("charter"),
+        session.charter.trim().lines().next().unwrap_or_default()
+    ));
+    if let Some(tester) = session
+        .tester_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        md.push_str(&format!(
+            "- **{}**: {}\n",
+            catalog.label("reported-by"),
+            tester
+        ));
+    }
+    md.push('\n');
+
+    md.push_str(&format!("## {}\n\n", catalog.label("description")));
+    md.push_str(&rules.linkify(&draft.description));
+    md.push_str("\n\n");
+
+    md.push_str(&format!("## {}\n\n", catalog.label("steps-to-reproduce")));
+    if draft.steps.is_empty() {
+        md.push_str(&format!(
+            "_{}_\n\n",
+            catalog.message("bug-draft-no-steps", &[])
+        ));
+    } else {
+        for (i, step) in draft.steps.iter().enumerate() {
+            md.push_str(&format!("{}. {}\n", i + 1, rules.linkify(step)));
+        }
+        md.push('\n');
+    }
+
+    if let Some(env_md) = session
+        .environment
+        .as_ref()
+        .and_then(|env| markdown::build_environment_section(env, catalog))
+    {
+        md.push_str(&env_md);
+    }
+
+    if !draft.evidence.is_empty() {
+        md.push_str(&format!("## {}\n\n", catalog.label("evidence")));
+        for &i in &draft.evidence {
+            let note = notes[i];
+            if let Some(source) = image_source(note) {
+                match image_paths.get(&source) {
+                    Some(rel_path) => md.push_str(&format!(
+                        "<img src=\"../{}\" width=\"900\" alt=\"{}\">\n\n",
+                        url_path(rel_path),
+                        catalog.label("screenshot")
+                    )),
+                    None => md.push_str(&format!(
+                        "{} ({}): {}\n\n",
+                        catalog.label("screenshot"),
+                        catalog.label("not-exported"),
+                        source
+                    )),
+                }
+            } else {
+                md.push_str("```\n");
+                md.push_str(note.text.trim());
+                md.push_str("\n```\n\n");
+            }
+        }
+    }
+
+    md
+}
+
+/// Writes every draft to `bugs/` in the export folder, copying evidence screenshots that the
+/// main report did not already export.
+pub(crate) fn write_bug_drafts(
+    export_dir: &Path,
+    session: &Session,
+    notes: &[&Note],
+    drafts: &[BugDraft],
+    rules: &LinkRules,
+    image_paths: &mut HashMap<String, String>,
+    catalog: &Catalog,
+) -> Result<(), String> {
+    if drafts.is_empty() {
+        return Ok(());
+    }
+
+    let bugs_dir = export_dir.join("bugs");
+    std::fs::create_dir_all(&bugs_dir).map_err(|e| e.to_string())?;
+
+    for draft in drafts {
+        for &i in &draft.evidence {
+            if let Some(source) = image_source(notes[i]) {
+                if let Entry::Vacant(slot) = image_paths.entry(source) {
+                    if let Ok(rel_path) = assets::copy_screenshot_asset(export_dir, slot.key()) {
+                        slot.insert(rel_path);
+                    }
+                }
+            }
+        }
+
+        let md = render_bug_draft(draft, session, notes, rules, image_paths, catalog);
+        std::fs::write(bugs_dir.join(&draft.file_name), md).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Whether the export wrote a draft file for `draft`. Bugs left out of the report by its type
+/// filters have none, and are not filed with the trackers.
+pub(crate) fn draft_written(export_dir: &Path, draft: &BugDraft) -> bool {
+    export_dir.join("bugs").join(&draft.file_name).is_file()
+}
+
+/// Screenshot and recording files backing a draft, preferring the copies in the export folder.
+pub(crate) fn evidence_files(draft: &BugDraft, notes: &[&Note], export_dir: &Path) -> Vec<PathBuf> {
+    draft
+        .evidence
+        .iter()
+        .filter_map(|&i| image_source(notes[i]))
+        .filter_map(|source| {
+            let source = PathBuf::from(source);
+            let exported = source
+                .file_name()
+                .map(|name| export_dir.join("assets/screenshots").join(name));
+            exported
+                .filter(|p| p.is_file())
+                .or_else(|| source.is_file().then_some(source))
+        })
+        .collect()
+}
+
+/// Text of the snippet notes backing a draft.
+pub(crate) fn evidence_snippets<'a>(draft: &BugDraft, notes: &[&'a Note]) -> Vec<&'a str> {
+    draft
+        .evidence
+        .iter()
+        .map(|&i| notes[i])
+        .filter(|n| is_snippet(n))
+        .map(|n| n.text.trim())
+        .collect()
+}
+
+/// End of the `- **Label**: value` list under the `# ` title of a draft, where issue links go.
+fn draft_metadata_end(md: &str) -> Option<usize> {
+    let title = if md.starts_with("# ") {
+        0
+    } else {
+        md.find("\n# ")? + 1
+    };
+    let mut position = md[title..].find('\n').map(|i| title + i + 1)?;
+    while md[position..].starts_with('\n') {
+        position += 1;
+    }
+
+    let mut end = None;
+    while md[position..].starts_with("- **") {
+        position = md[position..]
+            .find('\n')
+            .map_or(md.len(), |i| position + i + 1);
+        end = Some(position);
+    }
+    end
+}
+
+/// Writes a filed issue back into the export: the bug line in the main report and the draft
+/// file both gain a link to it. The caller records it in `session.json` first, so a failure
+/// here never leads to the bug being filed twice.
+pub(crate) fn link_issue(
+    export_dir: &Path,
+    draft: &BugDraft,
+    issue: &IssueLink,
+) -> Result<(), String> {
+    let draft_path = export_dir.join("bugs").join(&draft.file_name);
+    let mut draft_md = std::fs::read_to_string(&draft_path).map_err(|e| e.to_string())?;
+    let Some(end) = draft_metadata_end(&draft_md) else {
+        return Err(format!(
+            "bugs/{} has no metadata list to link {} {} from",
+            draft.file_name,
+            tracker_label(&issue.tracker),
+            issue.key
+        ));
+    };
+    let line = format!(
+        "- **{}**: [{}]({})\n",
+        tracker_label(&issue.tracker),
+        issue.key,
+        issue.url
+    );
+    if draft_md[..end].ends_with('\n') {
+        draft_md.insert_str(end, &line);
+    } else {
+        draft_md.insert_str(end, &format!("\n{}", line.trim_end()));
+    }
+    std::fs::write(&draft_path, draft_md).map_err(|e| e.to_string())?;
+
+    link_issue_in_report(export_dir, draft.note_index, Some(&draft.file_name), issue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LinkRule;
+
+    fn note(note_type: &str, text: &str) -> Note {
+        Note {
+            note_type: note_type.to_string(),
+            text: text.to_string(),
+            timestamp: None,
+            timestamp_iso: None,
+        }
+    }
+
+    #[test]
+    fn drafts_use_tests_since_previous_bug_and_nearest_evidence() {
+        let notes = [
+            note("test", "Open basket"),
+            note("bug", "Basket badge is stale"),
+            note("test", "Add two items"),
+            note("screenshot", "/tmp/far.png"),
+            note("test", "Go to checkout"),
+            note("snippet", "TypeError: total is undefined"),
+            note("bug", "Checkout total is blank\nSeen on every reload"),
+            note("screenshot", "/tmp/after.png"),
+            note("test", "Retry payment"),
+            note("screenshot", "/tmp/unrelated.png"),
+        ];
+        let refs: Vec<&Note> = notes.iter().collect();
+
+        let drafts = build_bug_drafts(&refs);
+
+        assert_eq!(drafts.len(), 2);
+        assert_eq!(drafts[0].steps, vec!["Open basket"]);
+        assert!(drafts[0].evidence.is_empty());
+
+        let second = &drafts[1];
+        assert_eq!(second.number, 2);
+        assert_eq!(second.note_index, 6);
+        assert_eq!(second.title, "Checkout total is blank");
+        assert_eq!(second.steps, vec!["Add two items", "Go to checkout"]);
+        assert_eq!(second.evidence, vec![3, 5, 7]);
+        assert_eq!(second.file_name, "BUG-02-checkout-total-is-blank.md");
+    }
+
+    #[test]
+    fn long_titles_are_cut_on_a_word_boundary() {
+        let title = draft_title(&"word ".repeat(30));
+
+        assert!(title.ends_with("word…"));
+        assert!(title.chars().count() <= MAX_TITLE_CHARS);
+    }
+
+    #[test]
+    fn rendered_draft_links_evidence_from_the_bugs_folder() {
+        let notes = [
+            note("test", "Pay with Visa"),
+            note("screenshot", "/tmp/pay now.png"),
+            note("bug", "Payment spinner never stops, like PAY-12"),
+        ];
+        let refs: Vec<&Note> = notes.iter().collect();
+        let session = Session {
+            tester_name: Some("Ada".to_string()),
+            charter: "Explore checkout".to_string(),
+            duration_minutes: None,
+            started_at: 0,
+            notes: Vec::new(),
+            environment: None,
+            library_charter: None,
+        };
+        let image_paths = HashMap::from([(
+            "/tmp/pay now.png".to_string(),
+            "assets/screenshots/pay now.png".to_string(),
+        )]);
+
+        let draft = &build_bug_drafts(&refs)[0];
+        let rules = LinkRules::compile(&[LinkRule {
+            name: None,
+            pattern: r"PAY-\d+".to_string(),
+            url: "https://jira.example.com/browse/$0".to_string(),
+        }])
+        .unwrap();
+        let md = render_bug_draft(
+            draft,
+            &session,
+            &refs,
+            &rules,
+            &image_paths,
+            Catalog::english(),
+        );
+
+        assert!(md.starts_with("# Bug: Payment spinner never stops, like PAY-12\n"));
+        assert!(md.contains("like [PAY-12](https://jira.example.com/browse/PAY-12)\n"));
+        assert!(md.contains("- **Reported by**: Ada\n"));
+        assert!(md.contains("## Steps to Reproduce\n\n1. Pay with Visa\n"));
+        assert!(md.contains("<img src=\"../assets/screenshots/pay%20now.png\""));
+
+        let german = Catalog::load(Some("de")).unwrap();
+        let md = render_bug_draft(draft, &session, &refs, &rules, &image_paths, &german);
+        assert!(md.starts_with("# Fehler: Payment spinner never stops, like PAY-12\n"));
+        assert!(md.contains("- **Gemeldet von**: Ada\n"));
+        assert!(md.contains("## Schritte zum Reproduzieren\n\n1. Pay with Visa\n"));
+    }
+}
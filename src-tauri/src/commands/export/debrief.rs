@@ -0,0 +1,473 @@
+use super::locale::Catalog;
+use super::zone::{self, ReportZone};
+use super::{assets, markdown, read_session_record};
+use crate::commands::links::LinkRules;
+use crate::models::SessionRecord;
+use chrono::{DateTime, FixedOffset, Utc};
+use fluent_bundle::FluentValue;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// Note types listed in full in the debrief, under their grouped-layout headings.
+const GROUPED_TYPES: [&str; 2] = ["bug", "question"];
+
+/// Loads a session from an export folder (via its `session.json`) or from a JSON file.
+fn load_record(source: &str) -> Result<SessionRecord, String> {
+    let path = Path::new(source.trim());
+
+    if path.is_dir() {
+        return read_session_record(path);
+    }
+    if !path.is_file() {
+        return Err(format!("Session source does not exist: {}", source));
+    }
+
+    let raw = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&raw)
+        .map_err(|e| format!("{} is not a session file: {}", path.display(), e))
+}
+
+/// Start of a session in the time zone its report was written in (the system zone for
+/// exports made before the zone was recorded).
+fn session_start(record: &SessionRecord) -> Option<DateTime<FixedOffset>> {
+    record
+        .started_at_iso
+        .as_deref()
+        .and_then(|iso| DateTime::parse_from_rfc3339(iso).ok())
+        .or_else(|| ReportZone::system().at(record.session.started_at))
+}
+
+/// Formats minutes as `45 min`, `2 h` or `2 h 40 min` (`2 Std. 40 Min.` in German).
+fn format_hours(minutes: i64, catalog: &Catalog) -> String {
+    let hours = |h: i64| catalog.count("short-hours", h as usize);
+    let mins = |m: i64| catalog.count("short-minutes", m as usize);
+    match (minutes / 60, minutes % 60) {
+        (0, m) => mins(m),
+        (h, 0) => hours(h),
+        (h, m) => format!("{} {}", hours(h), mins(m)),
+    }
+}
+
+/// Flattens text so it fits on one markdown line or table cell.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn planned_minutes(record: &SessionRecord) -> Option<i64> {
+    record
+        .session
+        .duration_minutes
+        .or_else(|| record.timing.as_ref().and_then(|t| t.planned_minutes))
+}
+
+fn actual_minutes(record: &SessionRecord) -> Option<i64> {
+    record
+        .timing
+        .as_ref()
+        .map(|t| ((t.actual_seconds + 30) / 60) as i64)
+}
+
+fn tester(record: &SessionRecord) -> Option<&str> {
+    record
+        .session
+        .tester_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Builds the combined debrief report for `records` (expected in chronological order).
+///
+/// Ticket keys in the listed bugs and questions are linked with `rules`; headings, counts and
+/// dates are in the language of `catalog`.
+pub(crate) fn build_debrief(
+    records: &[SessionRecord],
+    rules: &LinkRules,
+    catalog: &Catalog,
+) -> String {
+    let field = |label: &str, value: &str| format!("- **{}**: {}\n", catalog.label(label), value);
+    let hours = |minutes: i64| format_hours(minutes, catalog);
+
+    let mut md = String::new();
+    md.push_str(&format!("# {}\n\n", catalog.message("debrief-title", &[])));
+
+    md.push_str(&field(
+        "sessions",
+        &catalog.count("count-session", records.len()),
+    ));
+
+    let testers: BTreeSet<&str> = records.iter().filter_map(tester).collect();
+    if !testers.is_empty() {
+        md.push_str(&field(
+            "testers",
+            &testers.into_iter().collect::<Vec<_>>().join(", "),
+        ));
+    }
+
+    let first = records.first().and_then(session_start);
+    let last = records.last().and_then(session_start);
+    if let (Some(first), Some(last)) = (first, last) {
+        let (from, to) = (catalog.date(&first), catalog.date(&last));
+        if from == to {
+            md.push_str(&field("period", &from));
+        } else {
+            md.push_str(&field("period", &format!("{} – {}", from, to)));
+        }
+    }
+
+    // Actual time is only known for clocked sessions, so it is compared with their plan alone;
+    // sessions without clock data are listed by number with their planned time.
+    let (timed, untimed): (Vec<_>, Vec<_>) = records
+        .iter()
+        .enumerate()
+        .partition(|(_, r)| r.timing.is_some());
+    let planned = |sessions: &[(usize, &SessionRecord)]| -> i64 {
+        sessions
+            .iter()
+            .filter_map(|(_, r)| planned_minutes(r))
+            .sum()
+    };
+
+    let time = if timed.is_empty() {
+        catalog.message(
+            "debrief-time-planned",
+            &[("planned", FluentValue::from(hours(planned(&untimed))))],
+        )
+    } else {
+        let actual: i64 = timed.iter().filter_map(|(_, r)| actual_minutes(r)).sum();
+        let mut time = catalog.message(
+            "debrief-time-actual",
+            &[
+                ("actual", FluentValue::from(hours(actual))),
+                ("planned", FluentValue::from(hours(planned(&timed)))),
+            ],
+        );
+        if !untimed.is_empty() {
+            let numbers: Vec<String> = untimed.iter().map(|(i, _)| (i + 1).to_string()).collect();
+            time.push_str("; ");
+            time.push_str(&catalog.message(
+                "debrief-untimed",
+                &[
+                    ("count", FluentValue::from(untimed.len())),
+                    ("sessions", FluentValue::from(numbers.join(", "))),
+                    ("planned", FluentValue::from(hours(planned(&untimed)))),
+                ],
+            ));
+        }
+        time
+    };
+    md.push_str(&field("tester-time", &time));
+    md.push('\n');
+
+    md.push_str(&format!("## {}\n\n", catalog.label("sessions")));
+    md.push_str(&format!(
+        "| # | {} | {} | {} | {} | {} | {} |\n",
+        catalog.label("started"),
+        catalog.label("tester"),
+        catalog.label("charter"),
+        catalog.label("planned"),
+        catalog.label("actual"),
+        catalog.label("notes")
+    ));
+    md.push_str("|---:|---|---|---|---:|---:|---:|\n");
+    for (i, record) in records.iter().enumerate() {
+        let started = session_start(record)
+            .map(|t| {
+                format!(
+                    "{} {} {}",
+                    catalog.date(&t),
+                    t.format("%H:%M"),
+                    zone::utc_offset(t.offset())
+                )
+            })
+            .unwrap_or_default();
+        let charter = record
+            .session
+            .library_charter
+            .as_ref()
+            .map(|c| c.title.clone())
+            .unwrap_or_else(|| record.session.charter.clone());
+        let minutes = |m: Option<i64>| m.map(hours).unwrap_or_else(|| "—".to_string());
+
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} |\n",
+            i + 1,
+            started,
+            tester(record).unwrap_or("—").replace('|', "\\|"),
+            single_line(&charter).replace('|', "\\|"),
+            minutes(planned_minutes(record)),
+            minutes(actual_minutes(record)),
+            record.session.notes.len()
+        ));
+    }
+    md.push('\n');
+
+    let per_session: Vec<markdown::NoteCounts> = records
+        .iter()
+        .map(|r| markdown::NoteCounts::from_notes(&r.session.notes))
+        .collect();
+    let mut totals = markdown::NoteCounts::default();
+    for counts in &per_session {
+        totals.merge(counts);
+    }
+
+    if let Some(summary_md) = markdown::build_counts_section(&totals, catalog) {
+        md.push_str(&summary_md);
+    }
+
+    if totals.iter().next().is_some() {
+        md.push_str(&format!("## {}\n\n", catalog.label("notes-by-type")));
+        md.push_str(&format!(
+            "| {} | {} | {} |\n|---|---:|---:|\n",
+            catalog.label("type"),
+            catalog.label("notes"),
+            catalog.label("sessions")
+        ));
+        for (note_type, count) in totals.iter() {
+            let sessions = per_session.iter().filter(|c| c.get(note_type) > 0).count();
+            md.push_str(&format!("| {} | {} | {} |\n", note_type, count, sessions));
+        }
+        md.push('\n');
+    }
+
+    for note_type in GROUPED_TYPES {
+        let Some((heading, _)) = markdown::group_heading(note_type, catalog) else {
+            continue;
+        };
+        if totals.get(note_type) == 0 {
+            continue;
+        }
+
+        md.push_str(&format!("## {}\n\n", heading));
+        for (i, record) in records.iter().enumerate() {
+            let number = ("number", FluentValue::from(i + 1));
+            let source = match tester(record) {
+                Some(name) => catalog.message(
+                    "debrief-session-tester",
+                    &[number, ("tester", FluentValue::from(name))],
+                ),
+                None => catalog.message("debrief-session", &[number]),
+            };
+
+            // Session notes are stored newest-first.
+            for note in record.session.notes.iter().rev() {
+                if note.note_type.eq_ignore_ascii_case(note_type) {
+                    md.push_str(&format!(
+                        "- {} *({})*\n",
+                        rules.linkify(&single_line(&note.text)),
+                        source
+                    ));
+                }
+            }
+        }
+        md.push('\n');
+    }
+
+    let version = env!("CARGO_PKG_VERSION");
+    md.push_str("---\n");
+    md.push_str(&format!("{} v{}\n", catalog.label("generated-by"), version));
+
+    md
+}
+
+/// Combines several sessions into one debrief report in the user's home directory.
+///
+/// Each source is either an export folder (read through its `session.json`) or a session
+/// JSON file. The report lists every session, aggregates note counts, gathers all bugs and
+/// questions, and totals tester time against the planned durations. The folder is named
+/// after the current time in `timezone` (an IANA name, `UTC` or a UTC offset; the system zone
+/// when unset) and the report is written in `locale` (`en`, `de` or `fr`; English when unset).
+///
+/// Returns a map with:
+/// - `markdownPath`: absolute path to the debrief report file
+/// - `exportDir`: absolute path to the debrief folder
+/// - `linkRulesError`: why ticket keys were left unlinked, when the link rules cannot be loaded
+#[tauri::command]
+pub(crate) fn export_debrief_markdown(
+    app: tauri::AppHandle,
+    sources: Vec<String>,
+    timezone: Option<String>,
+    locale: Option<String>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    use std::collections::HashMap;
+
+    if sources.is_empty() {
+        return Err("Pick at least one session to debrief.".to_string());
+    }
+
+    let mut records = sources
+        .iter()
+        .map(|source| load_record(source))
+        .collect::<Result<Vec<_>, _>>()?;
+    records.sort_by_key(|r| r.session.started_at);
+
+    let (rules, rules_error) = super::load_link_rules(&app);
+    let catalog = Catalog::load(locale.as_deref())?;
+
+    let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+    let stamp = ReportZone::load(timezone.as_deref())?
+        .at(Utc::now().timestamp_millis())
+        .ok_or("Could not read the system clock")?
+        .format("%Y-%m-%d-%H%M")
+        .to_string();
+
+    let export_dir = home.join(format!("RapidReporter-Debrief-{}", stamp));
+    fs::create_dir_all(&export_dir).map_err(|e| e.to_string())?;
+
+    assets::copy_icon_assets(&export_dir)?;
+
+    let md_path = export_dir.join(format!("RapidReporter-Debrief-{}.md", stamp));
+    fs::write(&md_path, build_debrief(&records, &rules, &catalog)).map_err(|e| e.to_string())?;
+
+    let mut result = HashMap::new();
+    result.insert(
+        "markdownPath".to_string(),
+        md_path.to_string_lossy().to_string(),
+    );
+    result.insert(
+        "exportDir".to_string(),
+        export_dir.to_string_lossy().to_string(),
+    );
+    if let Some(err) = rules_error {
+        result.insert("linkRulesError".to_string(), err);
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::test_support::TempDir;
+    use crate::models::{Note, Session, SessionTiming};
+
+    fn record(
+        tester: &str,
+        started_at: i64,
+        planned: i64,
+        actual_secs: Option<u64>,
+    ) -> SessionRecord {
+        SessionRecord {
+            session: Session {
+                tester_name: Some(tester.to_string()),
+                charter: "Explore checkout".to_string(),
+                duration_minutes: Some(planned),
+                started_at,
+                notes: Vec::new(),
+                environment: None,
+                library_charter: None,
+            },
+            timing: actual_secs.map(|actual_seconds| SessionTiming {
+                planned_minutes: Some(planned),
+                actual_seconds,
+                paused_intervals: Vec::new(),
+            }),
+            task_breakdown: None,
+            issues: Vec::new(),
+            tags: Vec::new(),
+            mentions: Vec::new(),
+            started_at_iso: None,
+            timezone: None,
+        }
+    }
+
+    fn note(note_type: &str, text: &str) -> Note {
+        Note {
+            note_type: note_type.to_string(),
+            text: text.to_string(),
+            timestamp: None,
+            timestamp_iso: None,
+        }
+    }
+
+    #[test]
+    fn debrief_aggregates_counts_and_groups_bugs() {
+        let mut first = record("Ada", 1_700_000_000_000, 60, Some(55 * 60));
+        first.session.notes = vec![note("bug", "Refund times out"), note("test", "Pay")];
+        first.started_at_iso = Some("2023-11-14T23:13:20+01:00".to_string());
+        let mut second = record("Grace", 1_700_100_000_000, 90, Some(100 * 60));
+        second.started_at_iso = Some("2023-11-15T21:00:00-05:00".to_string());
+        second.session.notes = vec![
+            note("bug", "Total is wrong"),
+            note("Bug", "Basket\nempties"),
+            note("question", "Is VAT included?"),
+        ];
+
+        let md = build_debrief(&[first, second], &LinkRules::default(), Catalog::english());
+
+        assert!(md.contains("- **Sessions**: 2 sessions"));
+        assert!(md.contains("- **Testers**: Ada, Grace"));
+        // Each session keeps the time zone it was exported in.
+        assert!(md.contains("- **Period**: 14 November 2023 – 15 November 2023\n"));
+        assert!(md.contains("| 1 | 14 November 2023 23:13 UTC+01:00 | Ada |"));
+        assert!(md.contains("| 2 | 15 November 2023 21:00 UTC-05:00 | Grace |"));
+        assert!(md.contains("- **Tester time**: 2 h 35 min actual of 2 h 30 min planned"));
+        assert!(md.contains("3 Bugs"));
+        assert!(md.contains("1 Question"));
+        assert!(md.contains("| bug | 3 | 2 |"));
+        assert!(md.contains(
+            "## Bugs\n\n- Refund times out *(session 1, Ada)*\n- Basket empties *(session 2, Grace)*\n- Total is wrong *(session 2, Grace)*\n"
+        ));
+        assert!(md.contains("## Questions\n\n- Is VAT included? *(session 2, Grace)*\n"));
+    }
+
+    #[test]
+    fn tester_time_notes_sessions_without_clock_data() {
+        let md = build_debrief(
+            &[
+                record("Ada", 1_700_000_000_000, 60, Some(45 * 60)),
+                record("Ada", 1_700_000_000_000, 30, None),
+                record("Ada", 1_700_000_000_000, 20, Some(25 * 60)),
+                record("Ada", 1_700_000_000_000, 15, None),
+            ],
+            &LinkRules::default(),
+            Catalog::english(),
+        );
+
+        assert!(md.contains(
+            "- **Tester time**: 1 h 10 min actual of 1 h 20 min planned; \
+             no clock data for sessions 2, 4 (45 min planned)\n"
+        ));
+        assert!(md.contains("| 30 min | — | 0 |"));
+        assert!(!md.contains("## Summary"));
+    }
+
+    #[test]
+    fn debriefs_are_written_in_the_requested_language() {
+        let mut first = record("Ada", 1_700_000_000_000, 90, Some(95 * 60));
+        first.started_at_iso = Some("2023-03-01T14:05:00+01:00".to_string());
+        first.session.notes = vec![note("bug", "Summe ist falsch")];
+        let german = Catalog::load(Some("de")).unwrap();
+
+        let md = build_debrief(&[first], &LinkRules::default(), &german);
+
+        assert!(md.starts_with("# Rapid-Reporter-Debriefing\n\n- **Sitzungen**: 1 Sitzung\n"));
+        assert!(md.contains("- **Zeitraum**: 1. März 2023\n"));
+        assert!(
+            md.contains("- **Testzeit**: 1 Std. 35 Min. tatsächlich von 1 Std. 30 Min. geplant")
+        );
+        assert!(md.contains("| 1 | 1. März 2023 14:05 UTC+01:00 | Ada |"));
+        assert!(md.contains("## Zusammenfassung\n\n<img src=\"assets/icons/bug.png\" width=\"50\" valign=\"middle\"> 1 Fehler\n"));
+        assert!(md.contains("## Fehler\n\n- Summe ist falsch *(Sitzung 1, Ada)*\n"));
+        assert!(md.contains("Erstellt mit Rapid Reporter v"));
+    }
+
+    #[test]
+    fn bare_session_json_loads_as_record() {
+        let dir = TempDir::new("debrief");
+        let path = dir.join("session.json");
+        fs::write(
+            &path,
+            r#"{"charter":"Explore login","durationMinutes":30,"startedAt":1,"notes":[{"type":"bug","text":"x"}]}"#,
+        )
+        .unwrap();
+
+        let from_file = load_record(path.to_str().unwrap()).unwrap();
+        let from_dir = load_record(dir.to_str().unwrap()).unwrap();
+
+        assert_eq!(from_file.session.charter, "Explore login");
+        assert!(from_file.timing.is_none());
+        assert_eq!(from_dir.session.notes.len(), 1);
+    }
+}
@@ -0,0 +1,326 @@
+use super::template::ReportData;
+use std::io::{Cursor, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// The report as a flat list of blocks, shared by the word-processor exporters (DOCX, ODT).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Block {
+    Title(String),
+    Heading(String),
+    /// A `Label: value` metadata line.
+    Field {
+        label: String,
+        value: String,
+    },
+    /// The summary table, with its column headings.
+    Summary {
+        headings: [String; 2],
+        rows: Vec<SummaryRow>,
+    },
+    /// A note, with its type icon (relative to the export folder) when it has one.
+    Note {
+        icon: Option<String>,
+        text: String,
+    },
+    /// A screenshot (relative to the export folder) with its caption.
+    Figure {
+        path: String,
+        caption: String,
+    },
+    Code(String),
+    Text(String),
+    /// Small print, such as the "Generated by" line.
+    Footnote(String),
+}
+
+/// A row of the summary table.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SummaryRow {
+    pub(crate) icon: String,
+    pub(crate) label: String,
+    pub(crate) count: usize,
+}
+
+/// Lays out the report data as blocks: metadata, environment, summary table, then the notes.
+pub(crate) fn report_blocks(data: &ReportData) -> Vec<Block> {
+    let field = |label: &str, value: &str| Block::Field {
+        label: label.to_string(),
+        value: value.to_string(),
+    };
+
+    let session = &data.session;
+    let mut blocks = vec![Block::Title(data.label("reportTitle").to_string())];
+    if let Some(tester) = session.tester.as_deref() {
+        blocks.push(field(data.label("tester"), tester));
+    }
+    blocks.push(field(data.label("charter"), &session.charter));
+    if let Some(charter) = session.library_charter.as_ref() {
+        blocks.push(field(
+            data.label("libraryCharter"),
+            &format!("{} ({})", charter.title, charter.id),
+        ));
+    }
+    blocks.push(field(data.label("started"), &session.started));
+    for entry in &session.timing {
+        blocks.push(field(&entry.label, &entry.value));
+    }
+
+    if !data.environment.is_empty() {
+        blocks.push(Block::Heading(data.label("environment").to_string()));
+        for entry in &data.environment {
+            blocks.push(field(&entry.label, &entry.value));
+        }
+    }
+
+    if !data.summary.is_empty() {
+        blocks.push(Block::Heading(data.label("summary").to_string()));
+        blocks.push(Block::Summary {
+            headings: [
+                data.label("noteType").to_string(),
+                data.label("count").to_string(),
+            ],
+            rows: data
+                .summary
+                .iter()
+                .map(|count| SummaryRow {
+                    icon: count.icon.clone(),
+                    label: count.name.clone(),
+                    count: count.count,
+                })
+                .collect(),
+        });
+    }
+
+    blocks.push(Block::Heading(data.label("notes").to_string()));
+    let mut figures = 0;
+    for note in &data.notes {
+        match note.kind {
+            "image" => {
+                let alt = note
+                    .image
+                    .as_ref()
+                    .map_or(data.label("screenshot"), |i| i.alt.as_str());
+                match note.image.as_ref().and_then(|i| i.path.as_deref()) {
+                    Some(path) => {
+                        figures += 1;
+                        let file_name = path.rsplit('/').next().unwrap_or(path);
+                        blocks.push(Block::Figure {
+                            path: path.to_string(),
+                            caption: format!("{} {}: {}", data.label("figure"), figures, file_name),
+                        });
+                    }
+                    None => blocks.push(Block::Text(format!(
+                        "{} ({}): {}",
+                        alt,
+                        data.label("copyFailed"),
+                        note.text
+                    ))),
+                }
+            }
+            "attachment" => blocks.push(Block::Text(match note.attachment.as_ref() {
+                Some(a) => format!(
+                    "{}: {} ({}, SHA-256 {})",
+                    data.label("attachment"),
+                    a.file_name,
+                    a.size,
+                    a.sha256
+                ),
+                None => format!(
+                    "{} ({}): {}",
+                    data.label("attachment"),
+                    data.label("copyFailed"),
+                    note.text
+                ),
+            })),
+            "snippet" => blocks.push(Block::Code(note.raw_text.clone())),
+            _ => {
+                let mut text = note.raw_text.clone();
+                if let Some(draft) = note.bug_draft.as_ref() {
+                    text.push_str(&format!(
+                        " ({}: bugs/{})",
+                        data.label("bugReportDraft"),
+                        draft.file_name
+                    ));
+                }
+                blocks.push(Block::Note {
+                    icon: note.icon.clone(),
+                    text,
+                });
+            }
+        }
+    }
+
+    blocks.push(Block::Footnote(format!(
+        "{} v{}",
+        data.label("generatedBy"),
+        data.app.version
+    )));
+
+    blocks
+}
+
+/// An image ready to be stored in a document package.
+pub(crate) struct Picture {
+    pub(crate) bytes: Vec<u8>,
+    /// File extension matching `bytes`: `png`, `jpeg` or `gif`.
+    pub(crate) extension: &'static str,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+impl Picture {
+    /// Reads an image from the export folder. Formats word processors cannot show
+    /// (WebP, BMP, ...) are converted to PNG; `None` when the file is not a readable image.
+    pub(crate) fn load(export_dir: &Path, rel_path: &str) -> Option<Picture> {
+        let bytes = std::fs::read(export_dir.join(rel_path)).ok()?;
+        let format = image::guess_format(&bytes).ok()?;
+        let (width, height) = image::ImageReader::with_format(Cursor::new(&bytes), format)
+            .into_dimensions()
+            .ok()?;
+
+        let extension = match format {
+            image::ImageFormat::Png => "png",
+            image::ImageFormat::Jpeg => "jpeg",
+            image::ImageFormat::Gif => "gif",
+            _ => {
+                let decoded = image::load_from_memory_with_format(&bytes, format).ok()?;
+                let mut png = Vec::new();
+                decoded
+                    .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+                    .ok()?;
+                return Some(Picture {
+                    bytes: png,
+                    extension: "png",
+                    width,
+                    height,
+                });
+            }
+        };
+
+        Some(Picture {
+            bytes,
+            extension,
+            width,
+            height,
+        })
+    }
+
+    pub(crate) fn mime_type(&self) -> &'static str {
+        match self.extension {
+            "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            _ => "image/png",
+        }
+    }
+
+    /// Display size in points at 96 dpi, shrunk (never enlarged) to fit the given box.
+    pub(crate) fn fit(&self, max_width: f32, max_height: f32) -> (f32, f32) {
+        let (width, height) = (self.width as f32 * 0.75, self.height as f32 * 0.75);
+        let scale = (max_width / width).min(max_height / height).min(1.0);
+        (width * scale, height * scale)
+    }
+}
+
+/// The zip container holding the parts of a DOCX or ODT document.
+pub(crate) struct Package {
+    zip: ZipWriter<Cursor<Vec<u8>>>,
+}
+
+impl Package {
+    pub(crate) fn new() -> Self {
+        Package {
+            zip: ZipWriter::new(Cursor::new(Vec::new())),
+        }
+    }
+
+    /// Adds a compressed part.
+    pub(crate) fn add(&mut self, name: &str, bytes: &[u8]) -> Result<(), String> {
+        self.write(name, bytes, CompressionMethod::Deflated)
+    }
+
+    /// Adds an uncompressed part, as required for the ODT `mimetype`.
+    pub(crate) fn add_stored(&mut self, name: &str, bytes: &[u8]) -> Result<(), String> {
+        self.write(name, bytes, CompressionMethod::Stored)
+    }
+
+    fn write(&mut self, name: &str, bytes: &[u8], method: CompressionMethod) -> Result<(), String> {
+        let options = SimpleFileOptions::default().compression_method(method);
+        self.zip
+            .start_file(name, options)
+            .map_err(|e| e.to_string())?;
+        self.zip.write_all(bytes).map_err(|e| e.to_string())
+    }
+
+    pub(crate) fn finish(self) -> Result<Vec<u8>, String> {
+        self.zip
+            .finish()
+            .map(Cursor::into_inner)
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Escapes text for XML content and attributes, dropping characters XML 1.0 cannot hold.
+pub(crate) fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 || c == '\u{fffe}' || c == '\u{ffff}' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::template::sample_data;
+
+    #[test]
+    fn report_blocks_cover_metadata_summary_and_every_note_kind() {
+        let blocks = report_blocks(&sample_data());
+
+        assert_eq!(
+            blocks[0],
+            Block::Title("Rapid Reporter Session".to_string())
+        );
+        assert!(blocks.contains(&Block::Summary {
+            headings: ["Note type".to_string(), "Count".to_string()],
+            rows: vec![SummaryRow {
+                icon: "assets/icons/bug.png".to_string(),
+                label: "Bug".to_string(),
+                count: 1,
+            }],
+        }));
+        assert!(blocks.contains(&Block::Figure {
+            path: "assets/screenshots/refund.png".to_string(),
+            caption: "Figure 1: refund.png".to_string(),
+        }));
+        assert!(blocks.contains(&Block::Text(
+            "Screenshot (copy failed): /tmp/missing.png".to_string()
+        )));
+        assert!(blocks.contains(&Block::Code("HTTP 504 Gateway Timeout".to_string())));
+        assert!(blocks.iter().any(|b| matches!(
+            b,
+            Block::Note { icon: Some(icon), text }
+                if icon == "assets/icons/bug.png"
+                    && text.ends_with("(bug report draft: bugs/BUG-01-refund-times-out.md)")
+        )));
+    }
+
+    #[test]
+    fn xml_escape_drops_invalid_characters() {
+        assert_eq!(
+            xml_escape("<a href=\"x\">Tom & 'Jerry'</a>\u{1b}[0m"),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &apos;Jerry&apos;&lt;/a&gt;[0m"
+        );
+    }
+}
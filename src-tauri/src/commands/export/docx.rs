@@ -0,0 +1,351 @@
+use super::document::{report_blocks, xml_escape, Block, Package, Picture, SummaryRow};
+use super::locale::Catalog;
+use super::template::ReportData;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A4 page with 2 cm margins, in twentieths of a point.
+const PAGE_WIDTH: u32 = 11906;
+const PAGE_HEIGHT: u32 = 16838;
+const MARGIN: u32 = 1134;
+/// Drawing sizes are in EMU: 12700 per point.
+const EMU_PER_POINT: f32 = 12700.0;
+const ICON_POINTS: f32 = 12.0;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Default Extension="png" ContentType="image/png"/><Default Extension="jpeg" ContentType="image/jpeg"/><Default Extension="gif" ContentType="image/gif"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/><Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/></Types>"#;
+
+const PACKAGE_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/></Relationships>"#;
+
+/// Word's built-in style names, so headings show up in the navigation pane and the table of
+/// contents, and captions can be numbered by Word later.
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Calibri" w:hAnsi="Calibri" w:eastAsia="Calibri" w:cs="Calibri"/><w:sz w:val="22"/><w:szCs w:val="22"/><w:lang w:val="en-GB"/></w:rPr></w:rPrDefault><w:pPrDefault><w:pPr><w:spacing w:after="120" w:line="264" w:lineRule="auto"/></w:pPr></w:pPrDefault></w:docDefaults><w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/><w:qFormat/></w:style><w:style w:type="paragraph" w:styleId="Title"><w:name w:val="Title"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:spacing w:after="240"/></w:pPr><w:rPr><w:b/><w:sz w:val="40"/><w:szCs w:val="40"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:keepNext/><w:keepLines/><w:spacing w:before="360" w:after="120"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:color w:val="1F3864"/><w:sz w:val="30"/><w:szCs w:val="30"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Caption"><w:name w:val="caption"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:qFormat/><w:pPr><w:spacing w:after="240"/></w:pPr><w:rPr><w:i/><w:color w:val="595959"/><w:sz w:val="18"/><w:szCs w:val="18"/></w:rPr></w:style><w:style w:type="paragraph" w:customStyle="1" w:styleId="Code"><w:name w:val="Code"/><w:basedOn w:val="Normal"/><w:qFormat/><w:pPr><w:pBdr><w:top w:val="single" w:sz="4" w:space="4" w:color="D9D9D9"/><w:left w:val="single" w:sz="4" w:space="4" w:color="D9D9D9"/><w:bottom w:val="single" w:sz="4" w:space="4" w:color="D9D9D9"/><w:right w:val="single" w:sz="4" w:space="4" w:color="D9D9D9"/></w:pBdr><w:shd w:val="clear" w:color="auto" w:fill="F2F2F2"/><w:spacing w:after="160" w:line="240" w:lineRule="auto"/><w:ind w:left="113" w:right="113"/></w:pPr><w:rPr><w:rFonts w:ascii="Consolas" w:hAnsi="Consolas" w:eastAsia="Consolas" w:cs="Consolas"/><w:sz w:val="18"/><w:szCs w:val="18"/></w:rPr></w:style><w:style w:type="paragraph" w:customStyle="1" w:styleId="Footnote"><w:name w:val="Report Footnote"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:before="360"/></w:pPr><w:rPr><w:color w:val="7F7F7F"/><w:sz w:val="16"/><w:szCs w:val="16"/></w:rPr></w:style><w:style w:type="table" w:default="1" w:styleId="TableNormal"><w:name w:val="Normal Table"/><w:tblPr><w:tblInd w:w="0" w:type="dxa"/><w:tblCellMar><w:top w:w="0" w:type="dxa"/><w:left w:w="108" w:type="dxa"/><w:bottom w:w="0" w:type="dxa"/><w:right w:w="108" w:type="dxa"/></w:tblCellMar></w:tblPr></w:style></w:styles>"#;
+
+/// Images stored in `word/media`, one relationship each, shared between the places using them.
+struct Media<'a> {
+    export_dir: &'a Path,
+    by_path: HashMap<String, Option<(String, f32, f32)>>,
+    files: Vec<(String, Picture)>,
+    drawings: u32,
+}
+
+impl Media<'_> {
+    /// Relationship id and natural size (points) of an image, adding it on first use.
+    fn get(&mut self, rel_path: &str) -> Option<(String, f32, f32)> {
+        if let Some(entry) = self.by_path.get(rel_path) {
+            return entry.clone();
+        }
+
+        let entry = Picture::load(self.export_dir, rel_path).map(|picture| {
+            // rId1 is the styles part.
+            let id = format!("rId{}", self.files.len() + 2);
+            let (width, height) = picture.fit(f32::MAX, f32::MAX);
+            let name = format!("image{}.{}", self.files.len() + 1, picture.extension);
+            self.files.push((name, picture));
+            (id, width, height)
+        });
+        self.by_path.insert(rel_path.to_string(), entry.clone());
+        entry
+    }
+
+    /// An inline picture run, `width` × `height` points.
+    fn drawing(&mut self, rel_id: &str, name: &str, width: f32, height: f32) -> String {
+        self.drawings += 1;
+        let (cx, cy) = (
+            (width * EMU_PER_POINT).round() as u64,
+            (height * EMU_PER_POINT).round() as u64,
+        );
+        format!(
+            concat!(
+                r#"<w:r><w:drawing><wp:inline distT="0" distB="0" distL="0" distR="0">"#,
+                r#"<wp:extent cx="{cx}" cy="{cy}"/><wp:docPr id="{id}" name="Picture {id}" descr="{name}"/>"#,
+                r#"<wp:cNvGraphicFramePr><a:graphicFrameLocks noChangeAspect="1"/></wp:cNvGraphicFramePr>"#,
+                r#"<a:graphic><a:graphicData uri="http://schemas.openxmlformats.org/drawingml/2006/picture">"#,
+                r#"<pic:pic><pic:nvPicPr><pic:cNvPr id="{id}" name="{name}"/><pic:cNvPicPr/></pic:nvPicPr>"#,
+                r#"<pic:blipFill><a:blip r:embed="{rel}"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>"#,
+                r#"<pic:spPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="{cx}" cy="{cy}"/></a:xfrm>"#,
+                r#"<a:prstGeom prst="rect"><a:avLst/></a:prstGeom></pic:spPr></pic:pic>"#,
+                r#"</a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"#
+            ),
+            cx = cx,
+            cy = cy,
+            id = self.drawings,
+            name = xml_escape(name),
+            rel = rel_id,
+        )
+    }
+
+    fn icon(&mut self, rel_path: &str) -> Option<String> {
+        let (id, _, _) = self.get(rel_path)?;
+        let name = rel_path.rsplit('/').next().unwrap_or(rel_path).to_string();
+        Some(self.drawing(&id, &name, ICON_POINTS, ICON_POINTS))
+    }
+}
+
+/// A text run; line breaks become `<w:br/>` and spacing is kept as typed.
+fn run(text: &str, props: &str) -> String {
+    let mut xml = String::from("<w:r>");
+    if !props.is_empty() {
+        xml.push_str(&format!("<w:rPr>{}</w:rPr>", props));
+    }
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            xml.push_str("<w:br/>");
+        }
+        xml.push_str(&format!(
+            "<w:t xml:space=\"preserve\">{}</w:t>",
+            xml_escape(line.trim_end_matches('\r'))
+        ));
+    }
+    xml.push_str("</w:r>");
+    xml
+}
+
+fn paragraph(style: Option<&str>, content: &str) -> String {
+    match style {
+        Some(style) => format!(
+            "<w:p><w:pPr><w:pStyle w:val=\"{}\"/></w:pPr>{}</w:p>",
+            style, content
+        ),
+        None => format!("<w:p>{}</w:p>", content),
+    }
+}
+
+fn summary_table(headings: &[String; 2], rows: &[SummaryRow], media: &mut Media) -> String {
+    let widths = [700, 3200, 1200];
+    let border = r#"w:val="single" w:sz="4" w:space="0" w:color="BFBFBF""#;
+    let cell = |width: u32, header: bool, content: &str| {
+        let shading = if header {
+            r#"<w:shd w:val="clear" w:color="auto" w:fill="F2F2F2"/>"#
+        } else {
+            ""
+        };
+        format!(
+            "<w:tc><w:tcPr><w:tcW w:w=\"{}\" w:type=\"dxa\"/>{}<w:vAlign w:val=\"center\"/></w:tcPr><w:p><w:pPr><w:spacing w:before=\"40\" w:after=\"40\"/></w:pPr>{}</w:p></w:tc>",
+            width, shading, content
+        )
+    };
+
+    let mut xml = format!(
+        "<w:tbl><w:tblPr><w:tblW w:w=\"{}\" w:type=\"dxa\"/><w:tblBorders><w:top {b}/><w:left {b}/><w:bottom {b}/><w:right {b}/><w:insideH {b}/><w:insideV {b}/></w:tblBorders><w:tblLayout w:type=\"fixed\"/><w:tblLook w:val=\"0420\" w:firstRow=\"1\" w:lastRow=\"0\" w:firstColumn=\"0\" w:lastColumn=\"0\" w:noHBand=\"1\" w:noVBand=\"1\"/></w:tblPr><w:tblGrid>",
+        widths.iter().sum::<u32>(),
+        b = border
+    );
+    for width in widths {
+        xml.push_str(&format!("<w:gridCol w:w=\"{}\"/>", width));
+    }
+    xml.push_str("</w:tblGrid>");
+
+    xml.push_str("<w:tr><w:trPr><w:tblHeader/></w:trPr>");
+    xml.push_str(&cell(widths[0], true, ""));
+    xml.push_str(&cell(widths[1], true, &run(&headings[0], "<w:b/>")));
+    xml.push_str(&cell(widths[2], true, &run(&headings[1], "<w:b/>")));
+    xml.push_str("</w:tr>");
+
+    for row in rows {
+        let icon = media.icon(&row.icon).unwrap_or_default();
+        xml.push_str("<w:tr>");
+        xml.push_str(&cell(widths[0], false, &icon));
+        xml.push_str(&cell(widths[1], false, &run(&row.label, "")));
+        xml.push_str(&cell(widths[2], false, &run(&row.count.to_string(), "")));
+        xml.push_str("</w:tr>");
+    }
+
+    xml.push_str("</w:tbl>");
+    // Keeps the next heading from sticking to the table.
+    xml.push_str("<w:p/>");
+    xml
+}
+
+fn document_xml(blocks: &[Block], media: &mut Media) -> String {
+    let max_width = (PAGE_WIDTH - 2 * MARGIN) as f32 / 20.0;
+    let max_height = (PAGE_HEIGHT - 2 * MARGIN) as f32 / 20.0 * 0.8;
+
+    let mut body = String::new();
+    for block in blocks {
+        let xml = match block {
+            Block::Title(text) => paragraph(Some("Title"), &run(text, "")),
+            Block::Heading(text) => paragraph(Some("Heading1"), &run(text, "")),
+            Block::Field { label, value } => paragraph(
+                None,
+                &format!(
+                    "{}{}",
+                    run(&format!("{}: ", label), "<w:b/>"),
+                    run(value, "")
+                ),
+            ),
+            Block::Summary { headings, rows } => summary_table(headings, rows, media),
+            Block::Note { icon, text } => {
+                let icon = icon
+                    .as_deref()
+                    .and_then(|icon| media.icon(icon))
+                    .map(|drawing| format!("{}{}", drawing, run(" ", "")))
+                    .unwrap_or_default();
+                paragraph(None, &format!("{}{}", icon, run(text, "")))
+            }
+            Block::Figure { path, caption } => match media.get(path) {
+                Some((id, width, height)) => {
+                    let name = path.rsplit('/').next().unwrap_or(path);
+                    let (scale_w, scale_h) = (max_width / width, max_height / height);
+                    let scale = scale_w.min(scale_h).min(1.0);
+                    let drawing = media.drawing(&id, name, width * scale, height * scale);
+                    format!(
+                        "<w:p><w:pPr><w:keepNext/><w:spacing w:after=\"60\"/></w:pPr>{}</w:p>{}",
+                        drawing,
+                        paragraph(Some("Caption"), &run(caption, ""))
+                    )
+                }
+                None => paragraph(None, &run(&format!("{} ({})", caption, path), "")),
+            },
+            Block::Code(text) => paragraph(Some("Code"), &run(text, "")),
+            Block::Text(text) => paragraph(None, &run(text, "")),
+            Block::Footnote(text) => paragraph(Some("Footnote"), &run(text, "")),
+        };
+        body.push_str(&xml);
+    }
+
+    format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            "\n",
+            r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" "#,
+            r#"xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" "#,
+            r#"xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing" "#,
+            r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" "#,
+            r#"xmlns:pic="http://schemas.openxmlformats.org/drawingml/2006/picture">"#,
+            "<w:body>{body}",
+            r#"<w:sectPr><w:pgSz w:w="{w}" w:h="{h}"/>"#,
+            r#"<w:pgMar w:top="{m}" w:right="{m}" w:bottom="{m}" w:left="{m}" w:header="567" w:footer="567" w:gutter="0"/>"#,
+            "</w:sectPr></w:body></w:document>"
+        ),
+        body = body,
+        w = PAGE_WIDTH,
+        h = PAGE_HEIGHT,
+        m = MARGIN,
+    )
+}
+
+/// Builds the report as a Word document (Office Open XML): styled headings, the summary
+/// table, icon-tagged notes, captioned screenshots and code-styled snippets. Images are read
+/// from `export_dir`, where the markdown export copied them, and embedded in the file. The
+/// document title comes from `catalog`, in the report language.
+pub(crate) fn render_report_docx(
+    data: &ReportData,
+    export_dir: &Path,
+    catalog: &Catalog,
+) -> Result<Vec<u8>, String> {
+    let mut media = Media {
+        export_dir,
+        by_path: HashMap::new(),
+        files: Vec::new(),
+        drawings: 0,
+    };
+    let document = document_xml(&report_blocks(data), &mut media);
+
+    let mut rels = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        "\n",
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        r#"<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>"#
+    ));
+    for (i, (name, _)) in media.files.iter().enumerate() {
+        rels.push_str(&format!(
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="media/{}"/>"#,
+            i + 2,
+            name
+        ));
+    }
+    rels.push_str("</Relationships>");
+
+    let core = format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            "\n",
+            r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" "#,
+            r#"xmlns:dc="http://purl.org/dc/elements/1.1/">"#,
+            "<dc:title>{}</dc:title><dc:creator>{}</dc:creator></cp:coreProperties>"
+        ),
+        xml_escape(&catalog.document_title(&data.session.charter)),
+        xml_escape(data.session.tester.as_deref().unwrap_or("Rapid Reporter")),
+    );
+
+    let mut package = Package::new();
+    package.add("[Content_Types].xml", CONTENT_TYPES.as_bytes())?;
+    package.add("_rels/.rels", PACKAGE_RELS.as_bytes())?;
+    package.add("docProps/core.xml", core.as_bytes())?;
+    package.add("word/document.xml", document.as_bytes())?;
+    package.add("word/styles.xml", STYLES.as_bytes())?;
+    package.add("word/_rels/document.xml.rels", rels.as_bytes())?;
+    for (name, picture) in &media.files {
+        package.add(&format!("word/media/{}", name), &picture.bytes)?;
+    }
+    package.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::template::sample_data;
+    use crate::commands::export::test_support::TempDir;
+    use std::io::Read;
+
+    fn part(archive: &mut zip::ZipArchive<std::io::Cursor<Vec<u8>>>, name: &str) -> String {
+        let mut xml = String::new();
+        archive
+            .by_name(name)
+            .unwrap_or_else(|_| panic!("missing {}", name))
+            .read_to_string(&mut xml)
+            .unwrap();
+        xml
+    }
+
+    #[test]
+    fn docx_package_has_styles_table_and_embedded_images() {
+        let dir = TempDir::new("docx");
+        std::fs::create_dir_all(dir.join("assets/screenshots")).unwrap();
+        std::fs::create_dir_all(dir.join("assets/icons")).unwrap();
+        image::RgbImage::from_pixel(1600, 900, image::Rgb([200, 30, 30]))
+            .save(dir.join("assets/screenshots/refund.png"))
+            .unwrap();
+        image::RgbaImage::from_pixel(50, 50, image::Rgba([0, 0, 0, 255]))
+            .save(dir.join("assets/icons/bug.png"))
+            .unwrap();
+
+        let bytes = render_report_docx(&sample_data(), &dir, Catalog::english()).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+
+        let document = part(&mut archive, "word/document.xml");
+        assert!(document.contains(
+            r#"<w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t xml:space="preserve">Summary</w:t>"#
+        ));
+        assert!(document.contains("<w:tbl>"));
+        assert!(document.contains(r#"<w:pStyle w:val="Code"/></w:pPr><w:r><w:t xml:space="preserve">HTTP 504 Gateway Timeout</w:t>"#));
+        assert!(document.contains("Figure 1: refund.png"));
+        // The bug icon is used in the summary table and next to the note, but stored once.
+        assert_eq!(document.matches(r#"r:embed="rId2""#).count(), 2);
+        // 1600px at 96 dpi is wider than the page and is scaled to the 17 cm text width.
+        assert!(document.contains(r#"<wp:extent cx="6120130" cy="3442573"/>"#));
+
+        let rels = part(&mut archive, "word/_rels/document.xml.rels");
+        assert!(rels.contains(r#"Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/image" Target="media/image1.png""#));
+        assert!(rels.contains(r#"Target="media/image2.png""#));
+        for name in [
+            "[Content_Types].xml",
+            "_rels/.rels",
+            "docProps/core.xml",
+            "word/styles.xml",
+            "word/media/image1.png",
+            "word/media/image2.png",
+        ] {
+            assert!(archive.by_name(name).is_ok(), "missing {}", name);
+        }
+
+        let german = Catalog::load(Some("de")).unwrap();
+        let bytes = render_report_docx(&sample_data(), &dir, &german).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        assert!(part(&mut archive, "docProps/core.xml")
+            .contains("<dc:title>Rapid-Reporter-Sitzung – Explore refunds</dc:title>"));
+    }
+}
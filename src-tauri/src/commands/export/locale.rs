@@ -0,0 +1,234 @@
+use chrono::Datelike;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use fluent_syntax::ast;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use unic_langid::LanguageIdentifier;
+
+/// Language of reports exported without a locale; its catalog backs every other one.
+pub(crate) const DEFAULT_LOCALE: &str = "en";
+/// Translation catalogs (Fluent `.ftl` files), by language.
+const CATALOGS: [(&str, &str); 3] = [
+    (
+        DEFAULT_LOCALE,
+        include_str!("../../../assets/locales/en.ftl"),
+    ),
+    ("de", include_str!("../../../assets/locales/de.ftl")),
+    ("fr", include_str!("../../../assets/locales/fr.ftl")),
+];
+/// Messages available to report templates as `labels.*`.
+const LABEL_PREFIX: &str = "label-";
+
+/// The report texts of one language, with its plural rules and date format.
+pub(crate) struct Catalog {
+    code: &'static str,
+    bundle: FluentBundle<FluentResource>,
+}
+
+fn parse(code: &str, source: &str) -> Result<FluentResource, String> {
+    FluentResource::try_new(source.to_string())
+        .map_err(|(_, errors)| format!("The {} report catalog is invalid: {:?}", code, errors))
+}
+
+impl Catalog {
+    /// Loads the catalog for `locale` (a language such as `de`, or a tag such as `fr-CA` or
+    /// `de_AT`); English when unset. Messages a catalog lacks fall back to English.
+    pub(crate) fn load(locale: Option<&str>) -> Result<Self, String> {
+        let requested = locale
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .unwrap_or(DEFAULT_LOCALE);
+        let language = requested
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        let Some(&(code, source)) = CATALOGS.iter().find(|(code, _)| *code == language) else {
+            return Err(format!(
+                "Reports are not available in \"{}\"; choose one of: {}",
+                requested,
+                CATALOGS.map(|(code, _)| code).join(", ")
+            ));
+        };
+
+        let language: LanguageIdentifier = code.parse().map_err(|e| format!("{:?}", e))?;
+        let mut bundle = FluentBundle::new_concurrent(vec![language]);
+        // Unicode isolation marks around placeables would end up in the markdown.
+        bundle.set_use_isolating(false);
+        bundle
+            .add_resource(parse(DEFAULT_LOCALE, CATALOGS[0].1)?)
+            .map_err(|e| format!("{:?}", e))?;
+        if code != DEFAULT_LOCALE {
+            bundle.add_resource_overriding(parse(code, source)?);
+        }
+
+        Ok(Catalog { code, bundle })
+    }
+
+    /// The English catalog, for texts that are not localised per export (tracker issue
+    /// descriptions).
+    pub(crate) fn english() -> &'static Catalog {
+        static ENGLISH: OnceLock<Catalog> = OnceLock::new();
+        ENGLISH.get_or_init(|| Catalog::load(None).expect("the English catalog is valid"))
+    }
+
+    /// Language code of the catalog, e.g. `de`.
+    pub(crate) fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Message `id` with `args` filled in; unknown ids come back unchanged.
+    pub(crate) fn message(&self, id: &str, args: &[(&str, FluentValue)]) -> String {
+        let Some(pattern) = self.bundle.get_message(id).and_then(|m| m.value()) else {
+            return id.to_string();
+        };
+
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        let mut errors = Vec::new();
+        self.bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut errors)
+            .into_owned()
+    }
+
+    /// Heading or label `name`, e.g. `notes` for the `label-notes` message.
+    pub(crate) fn label(&self, name: &str) -> String {
+        self.message(&format!("{}{}", LABEL_PREFIX, name), &[])
+    }
+
+    /// Message `id` for `count` items, using the language's plural rules (e.g. `2 Bugs`).
+    pub(crate) fn count(&self, id: &str, count: usize) -> String {
+        self.message(id, &[("count", FluentValue::from(count))])
+    }
+
+    /// e.g. `5 minutes`.
+    pub(crate) fn minutes(&self, minutes: i64) -> String {
+        self.message("minutes", &[("count", FluentValue::from(minutes))])
+    }
+
+    /// Long date, e.g. `1 March 2025`, `1. März 2025` or `1er mars 2025`.
+    pub(crate) fn date(&self, date: &impl Datelike) -> String {
+        let month = self.message(&format!("month-{}", date.month()), &[]);
+        self.message(
+            "date",
+            &[
+                ("day", FluentValue::from(date.day())),
+                ("month", FluentValue::from(month)),
+                ("year", FluentValue::from(date.year().to_string())),
+            ],
+        )
+    }
+
+    /// Title of the PDF, Word and OpenDocument copies, e.g. `Rapid Reporter Session – Explore
+    /// refunds`.
+    pub(crate) fn document_title(&self, charter: &str) -> String {
+        self.message("document-title", &[("charter", FluentValue::from(charter))])
+    }
+
+    /// PDF page footer, e.g. `Page 2 of 5`.
+    pub(crate) fn page_footer(&self, page: usize, total: usize) -> String {
+        self.message(
+            "page-footer",
+            &[
+                ("page", FluentValue::from(page)),
+                ("total", FluentValue::from(total)),
+            ],
+        )
+    }
+
+    /// Every `label-*` message keyed by its camelCase name (`label-report-title` is
+    /// `reportTitle`), for templates.
+    pub(crate) fn labels(&self) -> BTreeMap<String, String> {
+        let Ok(english) = parse(DEFAULT_LOCALE, CATALOGS[0].1) else {
+            return BTreeMap::new();
+        };
+
+        english
+            .entries()
+            .filter_map(|entry| match entry {
+                ast::Entry::Message(message) => message.id.name.strip_prefix(LABEL_PREFIX),
+                _ => None,
+            })
+            .map(|name| (camel_case(name), self.label(name)))
+            .collect()
+    }
+}
+
+/// `report-title` → `reportTitle`.
+fn camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '-' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn catalogs_apply_each_languages_plural_rules() {
+        let en = Catalog::load(None).unwrap();
+        let de = Catalog::load(Some("de-AT")).unwrap();
+        let fr = Catalog::load(Some("fr_CA")).unwrap();
+        assert_eq!((en.code(), de.code(), fr.code()), ("en", "de", "fr"));
+
+        assert_eq!(en.count("count-bug", 1), "1 Bug");
+        assert_eq!(en.count("count-bug", 0), "0 Bugs");
+        assert_eq!(de.count("count-question", 1), "1 Frage");
+        assert_eq!(de.count("count-question", 3), "3 Fragen");
+        // French treats 0 as singular.
+        assert_eq!(fr.minutes(0), "0 minute");
+        assert_eq!(fr.minutes(1), "1 minute");
+        assert_eq!(fr.minutes(2), "2 minutes");
+        assert_eq!(en.minutes(0), "0 minutes");
+        assert_eq!(de.count("short-minutes", 5), "5 Min.");
+        assert_eq!(fr.count("short-minutes", 5), "5 min");
+
+        let err = Catalog::load(Some("xx")).err().unwrap();
+        assert_eq!(
+            err,
+            "Reports are not available in \"xx\"; choose one of: en, de, fr"
+        );
+    }
+
+    #[test]
+    fn dates_and_labels_are_localised() {
+        let first = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let later = NaiveDate::from_ymd_opt(2025, 12, 24).unwrap();
+
+        let en = Catalog::load(Some("en")).unwrap();
+        let de = Catalog::load(Some("de")).unwrap();
+        let fr = Catalog::load(Some("fr")).unwrap();
+        assert_eq!(en.date(&first), "1 March 2025");
+        assert_eq!(de.date(&first), "1. März 2025");
+        assert_eq!(fr.date(&first), "1er mars 2025");
+        assert_eq!(fr.date(&later), "24 décembre 2025");
+
+        let labels = de.labels();
+        assert_eq!(labels["reportTitle"], "Rapid-Reporter-Sitzung");
+        assert_eq!(labels["notes"], "Notizen");
+        assert_eq!(labels.len(), en.labels().len());
+        assert_eq!(fr.label("summary"), "Résumé");
+        assert_eq!(fr.message("no-such-message", &[]), "no-such-message");
+        assert_eq!(de.page_footer(2, 5), "Seite 2 von 5");
+        assert_eq!(
+            fr.document_title("Explorer les remboursements"),
+            "Session Rapid Reporter – Explorer les remboursements"
+        );
+    }
+}
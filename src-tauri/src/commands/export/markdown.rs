@@ -1,97 +1,559 @@
-use crate::models::Note;
+use super::assets::AttachmentAsset;
+use super::locale::Catalog;
+use super::zone::ReportZone;
+use crate::models::{Environment, Note, SessionTiming, TagEntry, TaskBreakdown};
+use std::collections::BTreeMap;
 
-/// Counts icon-backed note types used in the summary section.
-///
-/// Returns counts in this order:
-/// `(bug, idea, observation, question, warning)`.
-fn summary_counts(notes: &[Note]) -> (usize, usize, usize, usize, usize) {
-    let mut bug_count = 0usize;
-    let mut idea_count = 0usize;
-    let mut observation_count = 0usize;
-    let mut question_count = 0usize;
-    let mut warning_count = 0usize;
-
-    for note in notes {
-        match note.note_type.to_lowercase().as_str() {
-            "bug" => bug_count += 1,
-            "idea" => idea_count += 1,
-            "observation" => observation_count += 1,
-            "question" => question_count += 1,
-            "warning" => warning_count += 1,
-            _ => {}
+/// Note types shown in the `## Summary` section, in display order. Their names are in the
+/// report catalogs (`type-*`, `count-*` and `group-*` messages).
+const SUMMARY_TYPES: [&str; 5] = ["bug", "idea", "observation", "question", "warning"];
+
+fn summary_type(note_type: &str) -> Option<&'static str> {
+    SUMMARY_TYPES
+        .iter()
+        .copied()
+        .find(|t| t.eq_ignore_ascii_case(note_type))
+}
+
+/// Number of notes per note type (lowercased). Counts from several sessions can be merged.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct NoteCounts(BTreeMap<String, usize>);
+
+impl NoteCounts {
+    pub(crate) fn from_notes(notes: &[Note]) -> Self {
+        let mut counts = NoteCounts::default();
+        for note in notes {
+            *counts.0.entry(note.note_type.to_lowercase()).or_default() += 1;
+        }
+        counts
+    }
+
+    pub(crate) fn merge(&mut self, other: &NoteCounts) {
+        for (note_type, count) in &other.0 {
+            *self.0.entry(note_type.clone()).or_default() += count;
         }
     }
 
-    (
-        bug_count,
-        idea_count,
-        observation_count,
-        question_count,
-        warning_count,
+    pub(crate) fn get(&self, note_type: &str) -> usize {
+        self.0.get(note_type).copied().unwrap_or(0)
+    }
+
+    /// All note types with their counts, alphabetically.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.0.iter().map(|(t, c)| (t.as_str(), *c))
+    }
+}
+
+/// Formats a byte count for humans (e.g. `512 B`, `12.3 KB`, `4.0 MB`).
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Builds the markdown line linking to an exported attachment, with its size and SHA-256.
+pub(crate) fn attachment_line(asset: &AttachmentAsset) -> String {
+    format!(
+        "📎 [{}]({}) ({}, SHA-256 `{}`)\n\n",
+        asset.file_name.replace('[', "\\[").replace(']', "\\]"),
+        super::template::url_path(&asset.rel_path),
+        format_size(asset.size_bytes),
+        asset.sha256
     )
 }
 
-/// Formats a count label with singular/plural forms.
-fn plural(count: usize, singular: &str, plural: &str) -> String {
-    if count == 1 {
-        format!("{} {}", count, singular)
-    } else {
-        format!("{} {}", count, plural)
+/// Builds the `Duration` metadata line (and a `Paused` line when the clock was paused).
+///
+/// Without backend timing only the planned duration is shown, as before.
+pub(crate) fn duration_lines(
+    planned_minutes: Option<i64>,
+    timing: Option<&SessionTiming>,
+    zone: &ReportZone,
+    catalog: &Catalog,
+) -> Vec<String> {
+    duration_entries(planned_minutes, timing, zone, catalog)
+        .into_iter()
+        .map(|(label, value)| format!("- **{}**: {}", label, value))
+        .collect()
+}
+
+/// The `Duration` (and `Paused`) metadata as `(label, value)` pairs. Shared by every export
+/// format.
+pub(crate) fn duration_entries(
+    planned_minutes: Option<i64>,
+    timing: Option<&SessionTiming>,
+    zone: &ReportZone,
+    catalog: &Catalog,
+) -> Vec<(String, String)> {
+    let Some(timing) = timing else {
+        return planned_minutes
+            .map(|mins| vec![(catalog.label("duration"), catalog.minutes(mins))])
+            .unwrap_or_default();
+    };
+
+    let planned = planned_minutes.or(timing.planned_minutes);
+    let actual = ((timing.actual_seconds + 30) / 60) as i64;
+
+    let mut duration = match planned {
+        Some(planned) => catalog.message(
+            "duration-planned-actual",
+            &[
+                ("planned", catalog.minutes(planned).into()),
+                ("actual", catalog.minutes(actual).into()),
+            ],
+        ),
+        None => catalog.message(
+            "duration-actual",
+            &[("actual", catalog.minutes(actual).into())],
+        ),
+    };
+    if let Some(over) = planned.map(|p| actual - p).filter(|over| *over > 0) {
+        let over = catalog.message("duration-over", &[("over", catalog.minutes(over).into())]);
+        duration.push_str(&format!(" ({})", over));
+    }
+
+    let mut entries = vec![(catalog.label("duration"), duration)];
+
+    if !timing.paused_intervals.is_empty() {
+        let clock_time = |millis: i64| {
+            zone.at(millis)
+                .map(|t| t.format("%H:%M").to_string())
+                .unwrap_or_else(|| "?".to_string())
+        };
+
+        let paused_ms: i64 = timing
+            .paused_intervals
+            .iter()
+            .map(|p| (p.ended_at - p.started_at).max(0))
+            .sum();
+        let spans: Vec<String> = timing
+            .paused_intervals
+            .iter()
+            .map(|p| format!("{}–{}", clock_time(p.started_at), clock_time(p.ended_at)))
+            .collect();
+
+        entries.push((
+            catalog.label("paused"),
+            format!(
+                "{} ({})",
+                catalog.minutes((paused_ms + 30_000) / 60_000),
+                spans.join(", ")
+            ),
+        ));
     }
+
+    entries
 }
 
-/// Builds the optional `## Summary` markdown section for icon-backed note types.
-pub(crate) fn build_summary_section(notes: &[Note]) -> Option<String> {
-    let (bug_count, idea_count, observation_count, question_count, warning_count) =
-        summary_counts(notes);
+/// Whole-number percentages of `parts` that always add up to 100 (largest remainder method).
+fn percentages(parts: &[u64]) -> Vec<u64> {
+    let total: u64 = parts.iter().sum();
+    if total == 0 {
+        return vec![0; parts.len()];
+    }
 
-    let has_summary = bug_count > 0
-        || idea_count > 0
-        || observation_count > 0
-        || question_count > 0
-        || warning_count > 0;
+    let mut shares: Vec<u64> = parts.iter().map(|p| p * 100 / total).collect();
+    let mut by_remainder: Vec<usize> = (0..parts.len()).collect();
+    by_remainder.sort_by_key(|&i| std::cmp::Reverse(parts[i] * 100 % total));
 
-    if !has_summary {
+    let missing = 100 - shares.iter().sum::<u64>();
+    for &i in by_remainder.iter().take(missing as usize) {
+        shares[i] += 1;
+    }
+
+    shares
+}
+
+/// One table of the task breakdown, with its heading and rows already localised.
+pub(crate) struct BreakdownTable {
+    pub(crate) heading: String,
+    pub(crate) rows: Vec<BreakdownRow>,
+}
+
+pub(crate) struct BreakdownRow {
+    pub(crate) label: String,
+    /// e.g. `12 min`.
+    pub(crate) time: String,
+    /// Whole-number percentage of the table's total.
+    pub(crate) share: u64,
+}
+
+/// The task (setup / test / bug) and focus (charter / opportunity) tables of the task
+/// breakdown, or `None` when no task time was recorded.
+pub(crate) fn task_breakdown_tables(
+    breakdown: &TaskBreakdown,
+    catalog: &Catalog,
+) -> Option<[BreakdownTable; 2]> {
+    let tasks = [
+        ("session-setup", breakdown.setup_seconds),
+        ("test-design", breakdown.test_seconds),
+        ("bug-investigation", breakdown.bug_seconds),
+    ];
+    let focus = [
+        ("charter", breakdown.charter_seconds),
+        ("opportunity", breakdown.opportunity_seconds),
+    ];
+
+    if tasks.iter().all(|(_, secs)| *secs == 0) {
         return None;
     }
 
-    let mut md = String::new();
-    md.push_str("## Summary\n\n");
+    let table = |heading: &str, rows: &[(&str, u64)]| {
+        let shares = percentages(&rows.iter().map(|(_, secs)| *secs).collect::<Vec<_>>());
+        BreakdownTable {
+            heading: catalog.label(heading),
+            rows: rows
+                .iter()
+                .zip(shares)
+                .map(|((label, secs), share)| BreakdownRow {
+                    label: catalog.label(label),
+                    time: catalog.count("short-minutes", ((secs + 30) / 60) as usize),
+                    share,
+                })
+                .collect(),
+        }
+    };
 
-    if bug_count > 0 {
+    Some([table("task", &tasks), table("focus", &focus)])
+}
+
+/// Builds the optional `## Task Breakdown` section (SBTM setup / test / bug split, plus
+/// charter vs opportunity).
+pub(crate) fn build_task_breakdown_section(
+    breakdown: &TaskBreakdown,
+    catalog: &Catalog,
+) -> Option<String> {
+    let tables = task_breakdown_tables(breakdown, catalog)?;
+
+    let mut md = String::new();
+    md.push_str(&format!("## {}\n\n", catalog.label("task-breakdown")));
+    for table in tables {
         md.push_str(&format!(
-            "<img src=\"assets/icons/bug.png\" width=\"50\" valign=\"middle\"> {}\n\n",
-            plural(bug_count, "Bug", "Bugs")
+            "| {} | {} | {} |\n|---|---:|---:|\n",
+            table.heading,
+            catalog.label("time"),
+            catalog.label("share")
         ));
+        for row in table.rows {
+            md.push_str(&format!(
+                "| {} | {} | {}% |\n",
+                row.label, row.time, row.share
+            ));
+        }
+        md.push('\n');
     }
 
-    if idea_count > 0 {
-        md.push_str(&format!(
-            "<img src=\"assets/icons/idea.png\" width=\"50\" valign=\"middle\"> {}\n\n",
-            plural(idea_count, "Idea", "Ideas")
-        ));
+    Some(md)
+}
+
+/// Human-friendly OS name for the short identifiers returned by `platform_os`.
+fn os_display_name(os: &str) -> &str {
+    match os {
+        "macos" => "macOS",
+        "windows" => "Windows",
+        "linux" => "Linux",
+        other => other,
+    }
+}
+
+/// Environment details as `(label, value)` pairs in display order (OS, screens, locale,
+/// app version, then custom details). Shared by every export format.
+pub(crate) fn environment_entries(env: &Environment, catalog: &Catalog) -> Vec<(String, String)> {
+    let mut entries = Vec::new();
+
+    if let Some(os) = env.os.as_deref() {
+        let mut os_line = os_display_name(os).to_string();
+        if let Some(version) = env.os_version.as_deref() {
+            os_line.push_str(&format!(" {}", version));
+        }
+        if let Some(arch) = env.arch.as_deref() {
+            os_line.push_str(&format!(" ({})", arch));
+        }
+        entries.push((catalog.label("os"), os_line));
+    }
+
+    if !env.screens.is_empty() {
+        let screens: Vec<String> = env
+            .screens
+            .iter()
+            .map(|screen| {
+                let size = format!(
+                    "{}×{} @{}x",
+                    screen.width,
+                    screen.height,
+                    (screen.scale_factor * 100.0).round() / 100.0
+                );
+                match screen.name.as_deref().filter(|n| !n.trim().is_empty()) {
+                    Some(name) => format!("{} {}", name.trim(), size),
+                    None => size,
+                }
+            })
+            .collect();
+        entries.push((catalog.label("screens"), screens.join("; ")));
+    }
+
+    if let Some(locale) = env.locale.as_deref() {
+        entries.push((catalog.label("locale"), locale.to_string()));
+    }
+
+    if let Some(version) = env.app_version.as_deref() {
+        entries.push((catalog.label("app-version"), version.to_string()));
+    }
+
+    for entry in &env.custom {
+        let key = entry.key.trim();
+        let value = entry.value.trim();
+        if !key.is_empty() && !value.is_empty() {
+            entries.push((key.to_string(), value.to_string()));
+        }
+    }
+
+    entries
+}
+
+/// Builds the optional `## Environment` markdown section (OS, screens, locale, custom details).
+pub(crate) fn build_environment_section(env: &Environment, catalog: &Catalog) -> Option<String> {
+    let lines: Vec<String> = environment_entries(env, catalog)
+        .into_iter()
+        .map(|(label, value)| format!("- **{}**: {}", label, value))
+        .collect();
+
+    if lines.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "## {}\n\n{}\n\n",
+        catalog.label("environment"),
+        lines.join("\n")
+    ))
+}
+
+/// Builds the optional `## Summary` markdown section for icon-backed note types.
+pub(crate) fn build_summary_section(notes: &[Note], catalog: &Catalog) -> Option<String> {
+    build_counts_section(&NoteCounts::from_notes(notes), catalog)
+}
+
+/// Builds the `## Summary` section of the grouped layout, each line linking to the section
+/// listing the notes of that type (see [`build_groups_section`]).
+pub(crate) fn build_grouped_summary_section(notes: &[Note], catalog: &Catalog) -> Option<String> {
+    counts_section(&NoteCounts::from_notes(notes), catalog, true)
+}
+
+/// The summary note types present in `counts`, in display order, as `(type, count, label)`
+/// with labels such as `2 Bugs`.
+pub(crate) fn summary_entries(
+    counts: &NoteCounts,
+    catalog: &Catalog,
+) -> Vec<(&'static str, usize, String)> {
+    SUMMARY_TYPES
+        .iter()
+        .map(|&note_type| (note_type, counts.get(note_type)))
+        .filter(|(_, count)| *count > 0)
+        .map(|(note_type, count)| {
+            let label = catalog.count(&format!("count-{}", note_type), count);
+            (note_type, count, label)
+        })
+        .collect()
+}
+
+/// Icon shown next to notes of the summary types (relative to the export folder), e.g.
+/// `assets/icons/bug.png`; other note types have none.
+pub(crate) fn icon_path(note_type: &str) -> Option<String> {
+    summary_type(note_type).map(|t| format!("assets/icons/{}.png", t))
+}
+
+/// Heading of a summary note type's section in the grouped layout and the id of its anchor,
+/// e.g. `("Bugs", "bugs")` for `bug`. Anchors are the same in every language.
+pub(crate) fn group_heading(note_type: &str, catalog: &Catalog) -> Option<(String, String)> {
+    summary_type(note_type).map(|t| {
+        (
+            catalog.message(&format!("group-{}", t), &[]),
+            format!("{}s", t),
+        )
+    })
+}
+
+/// Display name of a summary note type, e.g. `Bug` for `bug`.
+pub(crate) fn type_label(note_type: &str, catalog: &Catalog) -> Option<String> {
+    summary_type(note_type).map(|t| catalog.message(&format!("type-{}", t), &[]))
+}
+
+/// Builds the `## Summary` section from precomputed (possibly aggregated) note counts.
+pub(crate) fn build_counts_section(counts: &NoteCounts, catalog: &Catalog) -> Option<String> {
+    counts_section(counts, catalog, false)
+}
+
+fn counts_section(counts: &NoteCounts, catalog: &Catalog, linked: bool) -> Option<String> {
+    let entries = summary_entries(counts, catalog);
+    if entries.is_empty() {
+        return None;
     }
 
-    if observation_count > 0 {
+    let mut md = String::new();
+    md.push_str(&format!("## {}\n\n", catalog.label("summary")));
+
+    for (note_type, _, label) in entries {
+        let label = match group_heading(note_type, catalog) {
+            Some((_, anchor)) if linked => format!("[{}](#{})", label, anchor),
+            _ => label,
+        };
         md.push_str(&format!(
-            "<img src=\"assets/icons/observation.png\" width=\"50\" valign=\"middle\"> {}\n\n",
-            plural(observation_count, "Observation", "Observations")
+            "<img src=\"assets/icons/{}.png\" width=\"50\" valign=\"middle\"> {}\n\n",
+            note_type, label
         ));
     }
 
-    if question_count > 0 {
+    Some(md)
+}
+
+/// The notes of one summary type, listed in their own section of the grouped layout.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NoteGroup {
+    pub(crate) note_type: &'static str,
+    /// Chronological indices of the notes.
+    pub(crate) notes: Vec<usize>,
+}
+
+/// Groups chronological notes by summary type, in summary order; other types are left out.
+pub(crate) fn group_notes(notes: &[&Note]) -> Vec<NoteGroup> {
+    SUMMARY_TYPES
+        .iter()
+        .map(|&note_type| NoteGroup {
+            note_type,
+            notes: notes
+                .iter()
+                .enumerate()
+                .filter(|(_, n)| n.note_type.eq_ignore_ascii_case(note_type))
+                .map(|(i, _)| i)
+                .collect(),
+        })
+        .filter(|group| !group.notes.is_empty())
+        .collect()
+}
+
+/// Builds the per-type sections of the grouped layout (`## Bugs`, `## Questions`, ...), each
+/// behind a stable `<a id>` anchor and listing its notes with links back to the `note-N`
+/// anchors of the chronological log. `texts` holds the markdown text of every note.
+pub(crate) fn build_groups_section(
+    groups: &[NoteGroup],
+    texts: &[String],
+    catalog: &Catalog,
+) -> Option<String> {
+    if groups.is_empty() {
+        return None;
+    }
+
+    let note = catalog.label("note");
+    let mut md = String::new();
+    for group in groups {
+        let Some((heading, anchor)) = group_heading(group.note_type, catalog) else {
+            continue;
+        };
+        md.push_str(&format!("<a id=\"{}\"></a>\n## {}\n\n", anchor, heading));
+        for &i in &group.notes {
+            let text = texts.get(i).map(String::as_str).unwrap_or_default();
+            md.push_str(&format!(
+                "- [{} {}](#note-{}): {}\n",
+                note,
+                i + 1,
+                i + 1,
+                text.replace('\n', "\n  ")
+            ));
+        }
+        md.push('\n');
+    }
+
+    Some(md)
+}
+
+/// A ticket referenced from note text, with the (chronological) indices of the notes citing it.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ReferencedTicket {
+    pub(crate) key: String,
+    pub(crate) url: String,
+    pub(crate) notes: Vec<usize>,
+}
+
+/// Builds the optional `## Referenced Tickets` section, linking each ticket and the notes
+/// (by their `note-N` anchors) that mention it.
+pub(crate) fn build_tickets_section(
+    tickets: &[ReferencedTicket],
+    catalog: &Catalog,
+) -> Option<String> {
+    if tickets.is_empty() {
+        return None;
+    }
+
+    let mut md = String::new();
+    md.push_str(&format!("## {}\n\n", catalog.label("referenced-tickets")));
+
+    for ticket in tickets {
         md.push_str(&format!(
-            "<img src=\"assets/icons/question.png\" width=\"50\" valign=\"middle\"> {}\n\n",
-            plural(question_count, "Question", "Questions")
+            "- [{}]({}) — {}\n",
+            ticket.key,
+            ticket.url,
+            note_links(&ticket.notes, catalog)
         ));
     }
+    md.push('\n');
+
+    Some(md)
+}
 
-    if warning_count > 0 {
+/// Links to the `note-N` anchors of the given (chronological) note indices, e.g.
+/// `notes [1](#note-1), [5](#note-5)`.
+fn note_links(notes: &[usize], catalog: &Catalog) -> String {
+    let links: Vec<String> = notes
+        .iter()
+        .map(|i| format!("[{}](#note-{})", i + 1, i + 1))
+        .collect();
+    format!(
+        "{} {}",
+        catalog.count("note-links", notes.len()),
+        links.join(", ")
+    )
+}
+
+/// Builds the optional `## Tag Index` section: every `#tag`, then every `@mention`, with the
+/// number of notes carrying it and links to those notes.
+pub(crate) fn build_tag_index_section(
+    tags: &[TagEntry],
+    mentions: &[TagEntry],
+    catalog: &Catalog,
+) -> Option<String> {
+    if tags.is_empty() && mentions.is_empty() {
+        return None;
+    }
+
+    let mut md = String::new();
+    md.push_str(&format!("## {}\n\n", catalog.label("tag-index")));
+
+    let entries = tags
+        .iter()
+        .map(|t| ('#', t))
+        .chain(mentions.iter().map(|m| ('@', m)));
+    for (sigil, entry) in entries {
         md.push_str(&format!(
-            "<img src=\"assets/icons/warning.png\" width=\"50\" valign=\"middle\"> {}\n\n",
-            plural(warning_count, "Warning", "Warnings")
+            "- {}{} ({}) — {}\n",
+            sigil,
+            entry.name,
+            entry.notes.len(),
+            note_links(&entry.notes, catalog)
         ));
     }
+    md.push('\n');
 
     Some(md)
 }
@@ -104,11 +566,13 @@ mod tests {
         Note {
             note_type: note_type.to_string(),
             text: text.to_string(),
+            timestamp: None,
+            timestamp_iso: None,
         }
     }
 
     #[test]
-    fn summary_counts_only_icon_types() {
+    fn note_counts_are_per_type() {
         let notes = vec![
             note("bug", "b1"),
             note("bug", "b2"),
@@ -118,12 +582,12 @@ mod tests {
             note("warning", "w1"),
         ];
 
-        let (bug, idea, obs, q, warn) = summary_counts(&notes);
-        assert_eq!(bug, 2);
-        assert_eq!(idea, 1);
-        assert_eq!(obs, 0);
-        assert_eq!(q, 0);
-        assert_eq!(warn, 1);
+        let counts = NoteCounts::from_notes(&notes);
+        assert_eq!(counts.get("bug"), 2);
+        assert_eq!(counts.get("idea"), 1);
+        assert_eq!(counts.get("observation"), 0);
+        assert_eq!(counts.get("question"), 0);
+        assert_eq!(counts.get("warning"), 1);
     }
 
     #[test]
@@ -134,7 +598,7 @@ mod tests {
             note("screenshot", "/tmp/x.png"),
         ];
 
-        assert!(build_summary_section(&notes).is_none());
+        assert!(build_summary_section(&notes, Catalog::english()).is_none());
     }
 
     #[test]
@@ -149,7 +613,7 @@ mod tests {
             note("warning", "w3"),
         ];
 
-        let md = build_summary_section(&notes).expect("summary should exist");
+        let md = build_summary_section(&notes, Catalog::english()).expect("summary should exist");
         assert!(md.contains("## Summary"));
         assert!(md.contains("assets/icons/bug.png"));
         assert!(md.contains("2 Bugs"));
@@ -162,6 +626,198 @@ mod tests {
         assert!(!md.contains("assets/icons/observation.png"));
     }
 
+    #[test]
+    fn grouped_layout_links_summary_lines_to_per_type_sections() {
+        let notes = vec![
+            note("bug", "b1"),
+            note("test", "t1"),
+            note("question", "q1\nsecond line"),
+            note("Bug", "b2"),
+        ];
+        let chronological: Vec<&Note> = notes.iter().collect();
+
+        let summary = build_grouped_summary_section(&notes, Catalog::english()).unwrap();
+        assert!(summary.contains("valign=\"middle\"> [2 Bugs](#bugs)\n"));
+        assert!(summary.contains("valign=\"middle\"> [1 Question](#questions)\n"));
+
+        let groups = group_notes(&chronological);
+        assert_eq!(
+            groups,
+            vec![
+                NoteGroup {
+                    note_type: "bug",
+                    notes: vec![0, 3],
+                },
+                NoteGroup {
+                    note_type: "question",
+                    notes: vec![2],
+                },
+            ]
+        );
+
+        let texts: Vec<String> = notes.iter().map(|n| n.text.clone()).collect();
+        assert_eq!(
+            build_groups_section(&groups, &texts, Catalog::english()).unwrap(),
+            "<a id=\"bugs\"></a>\n## Bugs\n\n\
+             - [Note 1](#note-1): b1\n\
+             - [Note 4](#note-4): b2\n\n\
+             <a id=\"questions\"></a>\n## Questions\n\n\
+             - [Note 3](#note-3): q1\n  second line\n\n"
+        );
+        assert!(build_groups_section(&[], &texts, Catalog::english()).is_none());
+    }
+
+    #[test]
+    fn format_size_uses_binary_units() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    }
+
+    #[test]
+    fn attachment_line_links_relative_path_with_size_and_hash() {
+        let asset = AttachmentAsset {
+            rel_path: "assets/attachments/server log.txt".to_string(),
+            file_name: "server log.txt".to_string(),
+            size_bytes: 2048,
+            sha256: "abc123".to_string(),
+        };
+
+        assert_eq!(
+            attachment_line(&asset),
+            "📎 [server log.txt](assets/attachments/server%20log.txt) (2.0 KB, SHA-256 `abc123`)\n\n"
+        );
+
+        let asset = AttachmentAsset {
+            rel_path: "assets/attachments/trace (1)#2.har".to_string(),
+            file_name: "trace (1)#2.har".to_string(),
+            ..asset
+        };
+        assert!(attachment_line(&asset)
+            .starts_with("📎 [trace (1)#2.har](assets/attachments/trace%20%281%29%232.har) "));
+    }
+
+    #[test]
+    fn environment_section_lists_collected_and_custom_details() {
+        use crate::models::{EnvironmentEntry, ScreenInfo};
+
+        let env = Environment {
+            os: Some("macos".to_string()),
+            os_version: Some("14.5".to_string()),
+            arch: Some("aarch64".to_string()),
+            screens: vec![ScreenInfo {
+                name: Some("Built-in Display".to_string()),
+                width: 3024,
+                height: 1964,
+                scale_factor: 2.0,
+            }],
+            locale: Some("en-GB".to_string()),
+            app_version: Some("1.0.4".to_string()),
+            custom: vec![
+                EnvironmentEntry {
+                    key: "Build".to_string(),
+                    value: "2024.06.1".to_string(),
+                },
+                EnvironmentEntry {
+                    key: "Browser".to_string(),
+                    value: " ".to_string(),
+                },
+            ],
+        };
+
+        let md =
+            build_environment_section(&env, Catalog::english()).expect("environment should exist");
+        assert!(md.starts_with("## Environment\n\n"));
+        assert!(md.contains("- **OS**: macOS 14.5 (aarch64)\n"));
+        assert!(md.contains("- **Screens**: Built-in Display 3024×1964 @2x\n"));
+        assert!(md.contains("- **Locale**: en-GB\n"));
+        assert!(md.contains("- **Build**: 2024.06.1"));
+        assert!(!md.contains("Browser"));
+    }
+
+    #[test]
+    fn duration_lines_compare_planned_and_actual() {
+        let utc = ReportZone::load(Some("UTC")).unwrap();
+        assert_eq!(
+            duration_lines(Some(60), None, &utc, Catalog::english()),
+            vec!["- **Duration**: 60 minutes"]
+        );
+
+        let timing = SessionTiming {
+            planned_minutes: Some(60),
+            actual_seconds: 72 * 60 + 10,
+            paused_intervals: Vec::new(),
+        };
+        assert_eq!(
+            duration_lines(Some(60), Some(&timing), &utc, Catalog::english()),
+            vec!["- **Duration**: 60 minutes planned, 72 minutes actual (12 minutes over)"]
+        );
+
+        let unlimited = SessionTiming {
+            planned_minutes: None,
+            actual_seconds: 50,
+            paused_intervals: vec![crate::models::PausedInterval {
+                started_at: 0,
+                ended_at: 5 * 60_000,
+                started_at_iso: None,
+                ended_at_iso: None,
+            }],
+        };
+        let lines = duration_lines(None, Some(&unlimited), &utc, Catalog::english());
+        assert_eq!(lines[0], "- **Duration**: 1 minute actual");
+        assert_eq!(lines[1], "- **Paused**: 5 minutes (00:00–00:05)");
+
+        // Pauses are shown in the report's time zone, across a DST change (Berlin, 30 March
+        // 2025 at 01:00 UTC).
+        let berlin = ReportZone::load(Some("Europe/Berlin")).unwrap();
+        let dst_change = 1_743_296_400_000;
+        let across_dst = SessionTiming {
+            planned_minutes: None,
+            actual_seconds: 3600,
+            paused_intervals: vec![crate::models::PausedInterval {
+                started_at: dst_change - 10 * 60_000,
+                ended_at: dst_change + 10 * 60_000,
+                started_at_iso: None,
+                ended_at_iso: None,
+            }],
+        };
+        let lines = duration_lines(None, Some(&across_dst), &berlin, Catalog::english());
+        assert_eq!(lines[1], "- **Paused**: 20 minutes (01:50–03:10)");
+    }
+
+    #[test]
+    fn percentages_always_total_one_hundred() {
+        assert_eq!(percentages(&[1, 1, 1]), vec![34, 33, 33]);
+        assert_eq!(percentages(&[5, 40, 15]), vec![8, 67, 25]);
+        assert_eq!(percentages(&[0, 0]), vec![0, 0]);
+    }
+
+    #[test]
+    fn task_breakdown_section_renders_both_tables() {
+        let breakdown = TaskBreakdown {
+            setup_seconds: 5 * 60,
+            test_seconds: 40 * 60,
+            bug_seconds: 15 * 60,
+            charter_seconds: 50 * 60,
+            opportunity_seconds: 10 * 60,
+        };
+
+        let md = build_task_breakdown_section(&breakdown, Catalog::english())
+            .expect("breakdown should exist");
+        assert!(md.starts_with("## Task Breakdown\n\n| Task | Time | Share |"));
+        assert!(md.contains("| Test design and execution | 40 min | 67% |"));
+        assert!(md.contains("| Opportunity | 10 min | 17% |"));
+        assert!(
+            build_task_breakdown_section(&TaskBreakdown::default(), Catalog::english()).is_none()
+        );
+    }
+
+    #[test]
+    fn environment_section_is_none_when_empty() {
+        assert!(build_environment_section(&Environment::default(), Catalog::english()).is_none());
+    }
+
     #[test]
     fn summary_is_case_insensitive() {
         let notes = vec![
@@ -171,17 +827,70 @@ mod tests {
             note("WARNING", "w1"),
         ];
 
-        let (bug, idea, obs, q, warn) = summary_counts(&notes);
+        let counts = NoteCounts::from_notes(&notes);
 
-        assert_eq!(bug, 2);
-        assert_eq!(idea, 1);
-        assert_eq!(obs, 0);
-        assert_eq!(q, 0);
-        assert_eq!(warn, 1);
+        assert_eq!(counts.get("bug"), 2);
+        assert_eq!(counts.get("idea"), 1);
+        assert_eq!(counts.get("observation"), 0);
+        assert_eq!(counts.get("question"), 0);
+        assert_eq!(counts.get("warning"), 1);
 
-        let md = build_summary_section(&notes).expect("summary should exist");
+        let md = build_summary_section(&notes, Catalog::english()).expect("summary should exist");
         assert!(md.contains("2 Bugs"));
         assert!(md.contains("1 Idea"));
         assert!(md.contains("1 Warning"));
     }
+
+    #[test]
+    fn tickets_section_links_each_citing_note() {
+        assert!(build_tickets_section(&[], Catalog::english()).is_none());
+
+        let md = build_tickets_section(
+            &[
+                ReferencedTicket {
+                    key: "PROJ-123".to_string(),
+                    url: "https://jira.example.com/browse/PROJ-123".to_string(),
+                    notes: vec![0, 4],
+                },
+                ReferencedTicket {
+                    key: "#456".to_string(),
+                    url: "https://github.com/acme/shop/issues/456".to_string(),
+                    notes: vec![2],
+                },
+            ],
+            Catalog::english(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            md,
+            "## Referenced Tickets\n\n\
+             - [PROJ-123](https://jira.example.com/browse/PROJ-123) — notes [1](#note-1), [5](#note-5)\n\
+             - [#456](https://github.com/acme/shop/issues/456) — note [3](#note-3)\n\n"
+        );
+    }
+
+    #[test]
+    fn tag_index_lists_tags_then_mentions_with_counts() {
+        assert!(build_tag_index_section(&[], &[], Catalog::english()).is_none());
+
+        let entry = |name: &str, notes: Vec<usize>| TagEntry {
+            name: name.to_string(),
+            notes,
+        };
+        let md = build_tag_index_section(
+            &[entry("cart", vec![0, 3]), entry("checkout", vec![2])],
+            &[entry("Maria", vec![1])],
+            Catalog::english(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            md,
+            "## Tag Index\n\n\
+             - #cart (2) — notes [1](#note-1), [4](#note-4)\n\
+             - #checkout (1) — note [3](#note-3)\n\
+             - @Maria (1) — note [2](#note-2)\n\n"
+        );
+    }
 }
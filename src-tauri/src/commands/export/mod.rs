@@ -1,28 +1,407 @@
 mod assets;
-mod markdown;
+pub(crate) mod bugs;
+pub(crate) mod debrief;
+pub(crate) mod document;
+pub(crate) mod docx;
+pub(crate) mod locale;
+pub(crate) mod markdown;
+pub(crate) mod odt;
+pub(crate) mod pdf;
+pub(crate) mod table;
+pub(crate) mod template;
+pub(crate) mod wiki;
+pub(crate) mod zone;
 
-use crate::models::Session;
-use chrono::{Local, TimeZone};
+use crate::commands::activity::ActivityTracker;
+use crate::commands::links::{self, LinkRules};
+use crate::commands::timer::SessionClock;
+use crate::commands::{attachments, history, jira, tags};
+use crate::models::{
+    CharterRef, ExportOptions, IssueLink, Note, NoteOrder, Session, SessionRecord,
+};
+use locale::Catalog;
+use markdown::ReferencedTicket;
 use std::fs;
+use std::path::{Path, PathBuf};
+use template::{
+    AppData, AssetPaths, AttachmentData, BugDraftData, GroupData, ImageData, LabelValue, NoteData,
+    ReportData, ReportTemplate, Sections, SessionData, SummaryCount, TagData, TicketData,
+};
+use zone::ReportZone;
 
-/// Exports an in-memory test session to a portable markdown report in the user's home directory.
+/// Machine-readable copy of the session written into every export folder.
+pub(crate) const SESSION_RECORD_FILE: &str = "session.json";
+
+/// Reads the `session.json` saved in an export folder.
+pub(crate) fn read_session_record(export_dir: &Path) -> Result<SessionRecord, String> {
+    let path = export_dir.join(SESSION_RECORD_FILE);
+    if !path.is_file() {
+        return Err(format!(
+            "{} has no {}; re-export the session first",
+            export_dir.display(),
+            SESSION_RECORD_FILE
+        ));
+    }
+
+    let raw = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&raw)
+        .map_err(|e| format!("{} is not a session file: {}", path.display(), e))
+}
+
+pub(crate) fn write_session_record(
+    export_dir: &Path,
+    record: &SessionRecord,
+) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(record).map_err(|e| e.to_string())?;
+    fs::write(export_dir.join(SESSION_RECORD_FILE), json).map_err(|e| e.to_string())
+}
+
+/// Path of the main markdown report inside an export folder (named after the folder).
+pub(crate) fn report_path(export_dir: &Path) -> PathBuf {
+    let name = export_dir
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("RapidReporter");
+    export_dir.join(format!("{}.md", name))
+}
+
+/// HTML anchor placed in front of a note in the main report, e.g. `note-3` for the third note.
+pub(crate) fn note_anchor(index: usize) -> String {
+    format!("note-{}", index + 1)
+}
+
+/// Tickets mentioned in text notes, in order of first mention, with the notes mentioning them.
+pub(crate) fn referenced_tickets(notes: &[&Note], rules: &LinkRules) -> Vec<ReferencedTicket> {
+    let mut tickets: Vec<ReferencedTicket> = Vec::new();
+
+    for (index, note) in notes.iter().enumerate() {
+        if !is_text_note(note) {
+            continue;
+        }
+
+        for found in rules.find(note.text.trim()) {
+            match tickets
+                .iter_mut()
+                .find(|t| t.key == found.key && t.url == found.url)
+            {
+                Some(ticket) => {
+                    if ticket.notes.last() != Some(&index) {
+                        ticket.notes.push(index);
+                    }
+                }
+                None => tickets.push(ReferencedTicket {
+                    key: found.key,
+                    url: found.url,
+                    notes: vec![index],
+                }),
+            }
+        }
+    }
+
+    tickets
+}
+
+/// Whether a note is rendered as text (as opposed to an image, attachment or snippet).
+pub(crate) fn is_text_note(note: &Note) -> bool {
+    let note_type_lc = note.note_type.to_lowercase();
+    image_source(note).is_none() && note_type_lc != "attachment" && note_type_lc != "snippet"
+}
+
+/// Display name of an issue tracker id stored in [`IssueLink::tracker`].
+pub(crate) fn tracker_label(tracker: &str) -> &str {
+    match tracker {
+        "jira" => "Jira",
+        "github" => "GitHub",
+        "gitlab" => "GitLab",
+        other => other,
+    }
+}
+
+/// Byte range of the text of note `note_index` in the main report: the line starting with its
+/// `<a id>` anchor, or the line ending in its ` ^note-N` block id (`obsidian` template; the
+/// range stops in front of the block id).
+fn note_line(md: &str, note_index: usize) -> Option<(usize, usize)> {
+    let anchor = format!("<a id=\"{}\"></a>", note_anchor(note_index));
+    if let Some(start) = md.find(&anchor) {
+        let end = md[start..].find('\n').map_or(md.len(), |i| start + i);
+        return Some((start, end));
+    }
+
+    let block_id = format!(" ^{}", note_anchor(note_index));
+    let (end, _) = md
+        .match_indices(&block_id)
+        .find(|(i, _)| matches!(md[i + block_id.len()..].chars().next(), None | Some('\n')))?;
+    let start = md[..end].rfind('\n').map_or(0, |i| i + 1);
+    Some((start, end))
+}
+
+/// Adds an issue link to the line of note `note_index` in the main report. When the line links
+/// to the bug draft `draft_file`, the issue joins the draft link in its parentheses
+/// (`([bug report draft](bugs/…), Jira [QA-7](…))`); otherwise ` (Jira [QA-7](…))` is appended.
+///
+/// Fails when the report has no line for the note, e.g. with a custom template that leaves
+/// out anchors; the link is then only kept in `session.json`.
+pub(crate) fn link_issue_in_report(
+    export_dir: &Path,
+    note_index: usize,
+    draft_file: Option<&str>,
+    issue: &IssueLink,
+) -> Result<(), String> {
+    let report = report_path(export_dir);
+    let mut md = fs::read_to_string(&report).map_err(|e| e.to_string())?;
+
+    let Some((start, end)) = note_line(&md, note_index) else {
+        return Err(format!(
+            "The report has no line for note {}, so {} {} is only linked in {}",
+            note_index + 1,
+            tracker_label(&issue.tracker),
+            issue.key,
+            SESSION_RECORD_FILE
+        ));
+    };
+    let link = format!(
+        "{} [{}]({})",
+        tracker_label(&issue.tracker),
+        issue.key,
+        issue.url
+    );
+    let line = md[start..end].trim_end();
+    let in_draft_group = draft_file
+        .is_some_and(|file| line.contains(&format!("bugs/{}", file)) && line.ends_with(')'));
+    if in_draft_group {
+        md.insert_str(start + line.len() - 1, &format!(", {}", link));
+    } else {
+        md.insert_str(start + line.len(), &format!(" ({})", link));
+    }
+
+    fs::write(&report, md).map_err(|e| e.to_string())
+}
+
+/// Appends an issue link to a note line without a bug draft in the main report (see
+/// [`link_issue_in_report`]).
+pub(crate) fn link_note_in_report(
+    export_dir: &Path,
+    note_index: usize,
+    issue: &IssueLink,
+) -> Result<(), String> {
+    link_issue_in_report(export_dir, note_index, None, issue)
+}
+
+/// Source path of a note that is embedded as an image (screenshots and burst recordings).
+pub(crate) fn image_source(note: &Note) -> Option<String> {
+    let text = note.text.trim();
+    let note_type_lc = note.note_type.to_lowercase();
+
+    if note_type_lc == "screenshot" || note_type_lc == "recording" {
+        Some(text.to_string())
+    } else {
+        text.strip_prefix("Screenshot:")
+            .map(|rest| rest.trim().to_string())
+    }
+}
+
+/// Whether notes of `note_type` go into the report under the type filters of `options`.
+pub(crate) fn type_selected(options: &ExportOptions, note_type: &str) -> bool {
+    let listed = |types: &[String]| {
+        types
+            .iter()
+            .any(|t| t.trim().eq_ignore_ascii_case(note_type))
+    };
+    (options.include_types.is_empty() || listed(&options.include_types))
+        && !listed(&options.exclude_types)
+}
+
+/// The configured link rules, or none plus the error for `linkRulesError` when they cannot be
+/// loaded: broken link rules only cost the ticket links, never the report.
+pub(crate) fn load_link_rules(app: &tauri::AppHandle) -> (LinkRules, Option<String>) {
+    match links::load_rules(app) {
+        Ok(rules) => (rules, None),
+        Err(err) => (
+            LinkRules::default(),
+            Some(format!("Ticket references were not linked: {}", err)),
+        ),
+    }
+}
+
+/// Bug drafts for the bugs the type filters of `options` keep in the report; only these get a
+/// draft file under `bugs/` and are filed with the trackers.
+pub(crate) fn report_drafts(
+    drafts: &[bugs::BugDraft],
+    notes: &[&Note],
+    options: &ExportOptions,
+) -> Vec<bugs::BugDraft> {
+    drafts
+        .iter()
+        .filter(|d| type_selected(options, &notes[d.note_index].note_type))
+        .cloned()
+        .collect()
+}
+
+/// Puts the report notes (chronological on input) in the requested order.
+fn arrange_notes(mut notes: Vec<NoteData>, order: NoteOrder) -> Vec<NoteData> {
+    match order {
+        // The grouped layout keeps the chronological log and adds the per-type sections.
+        NoteOrder::Chronological | NoteOrder::Grouped => {}
+        NoteOrder::Reverse => notes.reverse(),
+    }
+    notes
+}
+
+/// Template data for the notes selected by the type filters of `options`, copying their
+/// screenshots and attachments into the export folder. Notes keep their session numbers.
 ///
-/// The export folder contains the markdown file plus copied icons/screenshots under `assets/`.
+/// `image_paths` records the copied screenshots (source path → relative path) for the drafts.
+fn build_note_data(
+    export_dir: &Path,
+    notes: &[&Note],
+    options: &ExportOptions,
+    bug_drafts: &[bugs::BugDraft],
+    rules: &LinkRules,
+    catalog: &Catalog,
+    image_paths: &mut std::collections::HashMap<String, String>,
+) -> Vec<NoteData> {
+    // Copy errors end up in an HTML comment next to the note.
+    let comment_safe = |err: String| err.replace("--", "- -");
+
+    notes
+        .iter()
+        .enumerate()
+        .filter(|(_, note)| type_selected(options, &note.note_type))
+        .map(|(index, note)| {
+            let text = note.text.trim();
+            let note_type_lc = note.note_type.to_lowercase();
+
+            let mut data = NoteData {
+                number: index + 1,
+                anchor: note_anchor(index),
+                linked: false,
+                note_type: note_type_lc.clone(),
+                timestamp: note.timestamp,
+                timestamp_iso: None,
+                kind: "text",
+                text: text.to_string(),
+                raw_text: text.to_string(),
+                icon: None,
+                type_label: None,
+                image: None,
+                attachment: None,
+                copy_error: None,
+                bug_draft: None,
+                tags: Vec::new(),
+                mentions: Vec::new(),
+            };
+
+            // Burst recordings (GIF/APNG) are embedded exactly like still screenshots.
+            if let Some(abs_path) = image_source(note) {
+                let alt = if note_type_lc == "recording" {
+                    catalog.label("recording")
+                } else {
+                    catalog.label("screenshot")
+                };
+
+                let path = match assets::copy_screenshot_asset(export_dir, &abs_path) {
+                    Ok(rel_path) => {
+                        image_paths.insert(abs_path.clone(), rel_path.clone());
+                        Some(rel_path)
+                    }
+                    Err(err) => {
+                        data.copy_error = Some(comment_safe(err));
+                        None
+                    }
+                };
+
+                data.kind = "image";
+                data.text = abs_path;
+                data.image = Some(ImageData {
+                    path,
+                    alt: alt.to_string(),
+                });
+                return data;
+            }
+
+            if note_type_lc == "attachment" {
+                data.kind = "attachment";
+                match assets::copy_attachment_asset(export_dir, text) {
+                    Ok(asset) => data.attachment = Some(AttachmentData::from(&asset)),
+                    Err(err) => data.copy_error = Some(comment_safe(err)),
+                }
+                return data;
+            }
+
+            if note_type_lc == "snippet" {
+                data.kind = "snippet";
+                return data;
+            }
+
+            data.icon = markdown::icon_path(&note_type_lc);
+            data.type_label = markdown::type_label(&note_type_lc, catalog);
+            data.bug_draft = bug_drafts
+                .iter()
+                .find(|d| d.note_index == index)
+                .map(BugDraftData::from);
+            data.text = rules.linkify(text);
+
+            let tokens = tags::parse_tokens(text);
+            data.tags = tokens.tags;
+            data.mentions = tokens.mentions;
+
+            data
+        })
+        .collect()
+}
+
+/// Saves another format of the report to `path` and records it in the export result as
+/// `<key>Path`, or the failure as `<key>Error`.
+fn write_report_copy(
+    result: &mut std::collections::HashMap<String, String>,
+    key: &str,
+    path: &Path,
+    bytes: Result<Vec<u8>, String>,
+) {
+    match bytes.and_then(|bytes| fs::write(path, bytes).map_err(|e| e.to_string())) {
+        Ok(()) => {
+            result.insert(format!("{}Path", key), path.to_string_lossy().to_string());
+        }
+        Err(err) => {
+            result.insert(format!("{}Error", key), err);
+        }
+    }
+}
+
+/// Exports an in-memory test session to a `RapidReporter-<start>` folder in the user's home
+/// directory: the markdown report with its assets, a draft per bug under `bugs/`, `session.json`
+/// for later debriefs, and whatever else [`ExportOptions`] asks for.
 ///
 /// Returns a map with:
 /// - `markdownPath`: absolute path to the markdown report file
 /// - `exportDir`: absolute path to the export folder
+/// - `pdfPath`, `docxPath`, `odtPath`, `confluencePath`, `jiraWikiPath`, `csvPath`,
+///   `xlsxPath`: absolute paths to the other report formats (when requested), or the matching
+///   `<format>Error` when one could not be written
+/// - `jiraIssues`: comma-separated keys of the Jira issues filed (when requested), or `jiraError`
+/// - `linkRulesError`: why ticket references were left unlinked (when the link rules are broken)
 #[tauri::command]
-pub(crate) fn export_session_markdown(
+pub(crate) async fn export_session_markdown(
+    app: tauri::AppHandle,
+    clock: tauri::State<'_, SessionClock>,
+    activity: tauri::State<'_, ActivityTracker>,
     session: Session,
+    options: Option<ExportOptions>,
 ) -> Result<std::collections::HashMap<String, String>, String> {
     use std::collections::HashMap;
 
     let home = dirs::home_dir().ok_or("Could not determine home directory")?;
+    let (rules, rules_error) = load_link_rules(&app);
+    let options = options.unwrap_or_default();
+    // Load and try the template up front so a broken one fails before anything is written.
+    let template =
+        ReportTemplate::load(&template::templates_dir(&app)?, options.template.as_deref())?;
+    template.check()?;
+    let catalog = Catalog::load(options.locale.as_deref())?;
+    let zone = ReportZone::load(options.timezone.as_deref())?;
 
-    let started = Local
-        .timestamp_millis_opt(session.started_at)
-        .single()
+    let started = zone
+        .at(session.started_at)
         .ok_or("Invalid session startedAt timestamp")?;
 
     let stamp = started.format("%Y-%m-%d-%H%M").to_string();
@@ -31,116 +410,531 @@ pub(crate) fn export_session_markdown(
     fs::create_dir_all(&export_dir).map_err(|e| e.to_string())?;
 
     assets::copy_icon_assets(&export_dir)?;
+    let template_assets = template.copy_assets(&export_dir)?;
 
     let md_path = export_dir.join(format!("RapidReporter-{}.md", stamp));
 
-    let mut md = String::new();
-    md.push_str("# Rapid Reporter Session\n\n");
+    let started_iso = zone::iso(&started);
+    let date_display = catalog.date(&started);
+    let time_display = started.format("%H:%M").to_string();
+    let started_line = format!("{} {} {}", date_display, time_display, zone.label(&started));
 
-    if let Some(tester_display) = session
-        .tester_name
-        .as_deref()
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-    {
-        md.push_str(&format!("- **Tester**: {}\n", tester_display));
+    let timing = clock.timing_for(session.started_at);
+    let paused = timing
+        .as_ref()
+        .map(|t| t.paused_intervals.as_slice())
+        .unwrap_or_default();
+    let breakdown = activity.breakdown_for(session.started_at, paused);
+
+    // The frontend keeps notes newest-first; reports read oldest-first.
+    let notes: Vec<&Note> = session.notes.iter().rev().collect();
+    let bug_drafts = bugs::build_bug_drafts(&notes);
+    let mut image_paths: HashMap<String, String> = HashMap::new();
+
+    let tickets = referenced_tickets(&notes, &rules);
+    let (tags, mentions) = tags::index_notes(&notes, is_text_note);
+    let mut note_data = build_note_data(
+        &export_dir,
+        &notes,
+        &options,
+        &bug_drafts,
+        &rules,
+        &catalog,
+        &mut image_paths,
+    );
+    for note in &mut note_data {
+        note.timestamp_iso = note
+            .timestamp
+            .and_then(|millis| zone.at(millis))
+            .map(|time| zone::iso(&time));
     }
 
-    md.push_str(&format!("- **Charter**: {}\n", session.charter.trim()));
+    // Type filters only shape the report; session.json and the history keep every note.
+    let selected = |index: &usize| type_selected(&options, &notes[*index].note_type);
+    let shown: Vec<Note> = notes
+        .iter()
+        .filter(|n| type_selected(&options, &n.note_type))
+        .map(|n| (*n).clone())
+        .collect();
+    let report_drafts = report_drafts(&bug_drafts, &notes, &options);
+    let mut report_tickets = tickets.clone();
+    for ticket in &mut report_tickets {
+        ticket.notes.retain(selected);
+    }
+    report_tickets.retain(|t| !t.notes.is_empty());
+    let [report_tags, report_mentions] = [&tags, &mentions].map(|entries| {
+        let mut entries = entries.clone();
+        for entry in &mut entries {
+            entry.notes.retain(selected);
+        }
+        entries.retain(|e| !e.notes.is_empty());
+        entries
+    });
 
-    let tz_abbrev = started.format("%Z").to_string();
-    let tz_display = if tz_abbrev == "+00:00" {
-        "GMT"
+    let counts = markdown::NoteCounts::from_notes(&shown);
+    let groups = if options.order == NoteOrder::Grouped {
+        markdown::group_notes(&notes)
+            .into_iter()
+            .filter(|g| type_selected(&options, g.note_type))
+            .collect()
     } else {
-        &tz_abbrev
+        Vec::new()
     };
-    let date_display = started.format("%-d %B %Y").to_string();
-    let time_display = started.format("%H:%M").to_string();
-    let started_line = format!("{} {} {}", date_display, time_display, tz_display);
-    md.push_str(&format!("- **Started**: {}\n", started_line));
+    // Only these notes get anchors in the default template. Tracker issues are linked onto the
+    // lines of bugs and questions, found through their anchors.
+    let links_to = |index: usize| {
+        report_tickets.iter().any(|t| t.notes.contains(&index))
+            || report_tags
+                .iter()
+                .chain(&report_mentions)
+                .any(|e| e.notes.contains(&index))
+            || groups.iter().any(|g| g.notes.contains(&index))
+    };
+    for note in &mut note_data {
+        note.linked =
+            matches!(note.note_type.as_str(), "bug" | "question") || links_to(note.number - 1);
+    }
+    let mut note_texts = vec![String::new(); notes.len()];
+    for note in &note_data {
+        note_texts[note.number - 1] = note.text.clone();
+    }
+    let data = ReportData {
+        app: AppData {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        },
+        locale: catalog.code().to_string(),
+        labels: catalog.labels(),
+        session: SessionData {
+            tester: session
+                .tester_name
+                .as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+            charter: session.charter.trim().to_string(),
+            library_charter: session.library_charter.as_ref().map(|c| CharterRef {
+                id: c.id.clone(),
+                title: c.title.trim().to_string(),
+            }),
+            started_at: session.started_at,
+            started: started_line,
+            started_iso: started_iso.clone(),
+            date: date_display,
+            time: time_display,
+            timezone: zone.name(&started),
+            utc_offset: started.format("%:z").to_string(),
+            planned_minutes: session
+                .duration_minutes
+                .or(timing.as_ref().and_then(|t| t.planned_minutes)),
+            actual_minutes: timing
+                .as_ref()
+                .map(|t| ((t.actual_seconds + 30) / 60) as i64),
+            timing: markdown::duration_entries(
+                session.duration_minutes,
+                timing.as_ref(),
+                &zone,
+                &catalog,
+            )
+            .into_iter()
+            .map(|(label, value)| LabelValue { label, value })
+            .collect(),
+            duration_lines: markdown::duration_lines(
+                session.duration_minutes,
+                timing.as_ref(),
+                &zone,
+                &catalog,
+            ),
+        },
+        environment: session
+            .environment
+            .as_ref()
+            .map(|env| markdown::environment_entries(env, &catalog))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(label, value)| LabelValue { label, value })
+            .collect(),
+        counts: counts
+            .iter()
+            .map(|(note_type, count)| (note_type.to_string(), count))
+            .collect(),
+        summary: markdown::summary_entries(&counts, &catalog)
+            .into_iter()
+            .map(|(note_type, count, label)| SummaryCount {
+                note_type: note_type.to_string(),
+                count,
+                label,
+                name: markdown::type_label(note_type, &catalog).unwrap_or_default(),
+                icon: markdown::icon_path(note_type).unwrap_or_default(),
+            })
+            .collect(),
+        task_breakdown: breakdown.clone(),
+        tickets: report_tickets.iter().map(TicketData::from).collect(),
+        tags: report_tags.iter().map(TagData::from).collect(),
+        mentions: report_mentions.iter().map(TagData::from).collect(),
+        groups: groups
+            .iter()
+            .map(|g| GroupData::new(g, &note_data, &catalog))
+            .collect(),
+        notes: arrange_notes(note_data, options.order),
+        bug_drafts: report_drafts.iter().map(BugDraftData::from).collect(),
+        assets: AssetPaths {
+            icons: "assets/icons".to_string(),
+            screenshots: "assets/screenshots".to_string(),
+            attachments: "assets/attachments".to_string(),
+            template: template_assets,
+        },
+        sections: Sections {
+            environment: Sections::section(
+                session
+                    .environment
+                    .as_ref()
+                    .and_then(|env| markdown::build_environment_section(env, &catalog)),
+            ),
+            summary: Sections::section(if options.order == NoteOrder::Grouped {
+                markdown::build_grouped_summary_section(&shown, &catalog)
+            } else {
+                markdown::build_summary_section(&shown, &catalog)
+            }),
+            task_breakdown: Sections::section(
+                breakdown
+                    .as_ref()
+                    .and_then(|b| markdown::build_task_breakdown_section(b, &catalog)),
+            ),
+            referenced_tickets: Sections::section(markdown::build_tickets_section(
+                &report_tickets,
+                &catalog,
+            )),
+            tag_index: Sections::section(markdown::build_tag_index_section(
+                &report_tags,
+                &report_mentions,
+                &catalog,
+            )),
+            groups: Sections::section(markdown::build_groups_section(
+                &groups,
+                &note_texts,
+                &catalog,
+            )),
+        },
+    };
+
+    let md = template.render(&data)?;
+    fs::write(&md_path, md).map_err(|e| e.to_string())?;
+
+    bugs::write_bug_drafts(
+        &export_dir,
+        &session,
+        &notes,
+        &report_drafts,
+        &rules,
+        &mut image_paths,
+        &catalog,
+    )?;
+
+    // The history index is a convenience for later searches; never fail the export over it.
+    let _ = history::record_export(&app, &session, &export_dir, &md_path, &zone);
 
-    if let Some(mins) = session.duration_minutes {
-        md.push_str(&format!("- **Duration**: {} minutes\n", mins));
+    // session.json keeps epoch ms, with ISO 8601 copies in the report's time zone next to them.
+    let iso_at = |millis: i64| zone.at(millis).map(|time| zone::iso(&time));
+    let mut session = session;
+    for note in &mut session.notes {
+        note.timestamp_iso = note.timestamp.and_then(iso_at);
+    }
+    let mut timing = timing;
+    for interval in timing
+        .iter_mut()
+        .flat_map(|t| t.paused_intervals.iter_mut())
+    {
+        interval.started_at_iso = iso_at(interval.started_at);
+        interval.ended_at_iso = iso_at(interval.ended_at);
     }
 
-    md.push_str("\n");
+    let record = SessionRecord {
+        session,
+        timing,
+        task_breakdown: breakdown,
+        issues: Vec::new(),
+        tags,
+        mentions,
+        started_at_iso: Some(started_iso),
+        timezone: Some(zone.name(&started)),
+    };
+    write_session_record(&export_dir, &record)?;
 
-    if let Some(summary_md) = markdown::build_summary_section(&session.notes) {
-        md.push_str(&summary_md);
+    // The export folder holds its own copies now, so the capture-time snapshots can go.
+    for note in &record.session.notes {
+        if note.note_type.trim().eq_ignore_ascii_case("attachment") {
+            attachments::remove_staged(Path::new(&note.text));
+        }
     }
 
-    md.push_str("## Notes\n\n");
+    let mut result = HashMap::new();
+    if let Some(err) = rules_error {
+        result.insert("linkRulesError".to_string(), err);
+    }
+    result.insert(
+        "markdownPath".to_string(),
+        md_path.to_string_lossy().to_string(),
+    );
+    result.insert(
+        "exportDir".to_string(),
+        export_dir.to_string_lossy().to_string(),
+    );
 
-    for note in session.notes.iter().rev() {
-        let text = note.text.trim();
-        let note_type_lc = note.note_type.to_lowercase();
+    if options.pdf {
+        let pdf = pdf::render_report_pdf(&data, &export_dir, &catalog);
+        write_report_copy(&mut result, "pdf", &md_path.with_extension("pdf"), Ok(pdf));
+    }
+    if options.docx {
+        let docx = docx::render_report_docx(&data, &export_dir, &catalog);
+        write_report_copy(&mut result, "docx", &md_path.with_extension("docx"), docx);
+    }
+    if options.odt {
+        let odt = odt::render_report_odt(&data, &export_dir, &catalog);
+        write_report_copy(&mut result, "odt", &md_path.with_extension("odt"), odt);
+    }
+    if options.csv || options.xlsx {
+        let headers = table::note_headers(&data);
+        let rows = table::note_rows(&data);
+        if options.csv {
+            let csv = table::render_notes_csv(&headers, &rows);
+            let path = md_path.with_extension("csv");
+            write_report_copy(&mut result, "csv", &path, Ok(csv.into_bytes()));
+        }
+        if options.xlsx {
+            let xlsx = table::render_notes_xlsx(&headers, &rows);
+            write_report_copy(&mut result, "xlsx", &md_path.with_extension("xlsx"), xlsx);
+        }
+    }
+    if options.confluence {
+        let page = wiki::render_confluence(&data, &rules, &catalog);
+        let path = md_path.with_extension("confluence.xhtml");
+        write_report_copy(&mut result, "confluence", &path, Ok(page.into_bytes()));
+    }
+    if options.jira_wiki {
+        let wiki = wiki::render_jira_wiki(&data, &rules, &catalog);
+        let path = md_path.with_extension("jira.txt");
+        write_report_copy(&mut result, "jiraWiki", &path, Ok(wiki.into_bytes()));
+    }
 
-        let abs_path_opt: Option<String> = if note_type_lc == "screenshot" {
-            Some(text.to_string())
-        } else if let Some(rest) = text.strip_prefix("Screenshot:") {
-            Some(rest.trim().to_string())
-        } else {
-            None
+    if options.create_jira_issues {
+        let filed = match jira::load_settings(&app) {
+            Ok(Some(settings)) => {
+                let dir = export_dir.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    jira::file_export_bugs(&settings, &dir)
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| r)
+            }
+            Ok(None) => Err("Jira is not configured yet.".to_string()),
+            Err(err) => Err(err),
         };
 
-        if let Some(abs_path) = abs_path_opt {
-            match assets::copy_screenshot_asset(&export_dir, &abs_path) {
-                Ok(rel_path) => {
-                    md.push_str(&format!(
-                        "<img src=\"{}\" width=\"900\" alt=\"Screenshot\">\n\n",
-                        rel_path
-                    ));
-                }
-                Err(err) => {
-                    md.push_str(&format!("Screenshot (copy failed): {}\n\n", abs_path));
-                    md.push_str(&format!("<!-- {} -->\n\n", err.replace("--", "- -")));
-                }
+        match filed {
+            Ok(issues) => {
+                let keys: Vec<&str> = issues.iter().map(|i| i.key.as_str()).collect();
+                result.insert("jiraIssues".to_string(), keys.join(", "));
+            }
+            Err(err) => {
+                result.insert("jiraError".to_string(), err);
             }
+        }
+    }
 
-            continue;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notes_are_filtered_by_type_and_arranged() {
+        let options: ExportOptions = serde_json::from_str(
+            r#"{"order": "grouped", "includeTypes": ["Bug", "screenshot"], "excludeTypes": [" Screenshot "]}"#,
+        )
+        .unwrap();
+        assert_eq!(options.order, NoteOrder::Grouped);
+        assert!(type_selected(&options, "bug"));
+        assert!(!type_selected(&options, "screenshot"));
+        assert!(!type_selected(&options, "test"));
+        assert!(type_selected(&ExportOptions::default(), "test"));
+
+        // test, screenshot, bug, screenshot, attachment, attachment, snippet
+        let notes = template::sample_data().notes;
+        let numbers = |order| -> Vec<usize> {
+            arrange_notes(notes.clone(), order)
+                .iter()
+                .map(|n| n.number)
+                .collect()
+        };
+        assert_eq!(numbers(NoteOrder::Chronological), vec![1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(numbers(NoteOrder::Reverse), vec![7, 6, 5, 4, 3, 2, 1]);
+        assert_eq!(numbers(NoteOrder::Grouped), vec![1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn frontend_notes_carry_their_timestamps() {
+        // The session as the frontend sends it to `export_session_markdown`.
+        let session: Session = serde_json::from_str(
+            r#"{
+                "testerName": "Ada",
+                "charter": "Explore checkout",
+                "startedAt": 1700000000000,
+                "notes": [
+                    {"id": "b", "timestamp": 1700000090000, "type": "bug", "text": "Total is blank"},
+                    {"id": "a", "timestamp": 1700000060000, "type": "test", "text": "Open basket"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let timestamps: Vec<Option<i64>> = session.notes.iter().map(|n| n.timestamp).collect();
+        assert_eq!(
+            timestamps,
+            vec![Some(1_700_000_090_000), Some(1_700_000_060_000)]
+        );
+    }
+}
+
+/// Export folders for exercising the tracker integrations in tests.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::{
+        bugs, note_anchor, report_drafts, report_path, type_selected, write_session_record,
+    };
+    use crate::models::{ExportOptions, Note, Session, SessionRecord};
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A new empty folder for one test, removed again when dropped. Names carry the process id
+    /// and a counter so parallel tests and test runs never share a folder.
+    pub(crate) struct TempDir(PathBuf);
+
+    impl TempDir {
+        pub(crate) fn new(name: &str) -> Self {
+            static COUNTER: AtomicUsize = AtomicUsize::new(0);
+            let dir = std::env::temp_dir()
+                .join("rapid-reporter-tests")
+                .join(format!(
+                    "{}-{}-{}",
+                    name,
+                    std::process::id(),
+                    COUNTER.fetch_add(1, Ordering::Relaxed)
+                ));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
         }
+    }
 
-        if note_type_lc == "snippet" {
-            md.push_str("```\n");
-            md.push_str(text);
-            md.push_str("\n```\n\n");
-            continue;
+    impl std::ops::Deref for TempDir {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
         }
+    }
 
-        let icon_filename = match note_type_lc.as_str() {
-            "bug" => Some("bug.png"),
-            "warning" => Some("warning.png"),
-            "observation" => Some("observation.png"),
-            "question" => Some("question.png"),
-            "idea" => Some("idea.png"),
-            _ => None,
-        };
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
 
-        if let Some(icon_file) = icon_filename {
-            md.push_str(&format!(
-                "<img src=\"assets/icons/{}\" width=\"50\" valign=\"middle\"> {}\n\n",
-                icon_file, text
-            ));
-        } else {
-            md.push_str(&format!("{}\n\n", text));
+    pub(crate) fn note(note_type: &str, text: &str) -> Note {
+        Note {
+            note_type: note_type.to_string(),
+            text: text.to_string(),
+            timestamp: None,
+            timestamp_iso: None,
         }
     }
 
-    let version = env!("CARGO_PKG_VERSION");
-    md.push_str("---\n");
-    md.push_str(&format!("Generated by Rapid Reporter v{}\n", version));
+    /// Writes a minimal export folder (report, bug drafts and `session.json`) for `notes`,
+    /// given newest-first like the frontend sends them.
+    pub(crate) fn export_folder(name: &str, notes: Vec<Note>) -> TempDir {
+        export_folder_with(name, notes, &ExportOptions::default())
+    }
+
+    /// Like [`export_folder`], with the report and bug drafts limited by the type filters of
+    /// `options` as in the export.
+    pub(crate) fn export_folder_with(
+        name: &str,
+        notes: Vec<Note>,
+        options: &ExportOptions,
+    ) -> TempDir {
+        let dir = TempDir::new(name);
+        std::fs::create_dir_all(dir.join("bugs")).unwrap();
 
-    fs::write(&md_path, md).map_err(|e| e.to_string())?;
+        let record = SessionRecord {
+            session: Session {
+                tester_name: Some("Ada".to_string()),
+                charter: "Explore checkout".to_string(),
+                duration_minutes: None,
+                started_at: 0,
+                notes,
+                environment: None,
+                library_charter: None,
+            },
+            timing: None,
+            task_breakdown: None,
+            issues: Vec::new(),
+            tags: Vec::new(),
+            mentions: Vec::new(),
+            started_at_iso: None,
+            timezone: None,
+        };
 
-    let mut result = HashMap::new();
-    result.insert(
-        "markdownPath".to_string(),
-        md_path.to_string_lossy().to_string(),
-    );
-    result.insert(
-        "exportDir".to_string(),
-        export_dir.to_string_lossy().to_string(),
-    );
+        let chronological: Vec<&Note> = record.session.notes.iter().rev().collect();
+        let drafts = report_drafts(
+            &bugs::build_bug_drafts(&chronological),
+            &chronological,
+            options,
+        );
+        let mut report = String::new();
+        for (index, note) in chronological.iter().enumerate() {
+            if !type_selected(options, &note.note_type) {
+                continue;
+            }
+            match drafts.iter().find(|d| d.note_index == index) {
+                Some(draft) => {
+                    let link = format!(" ([bug report draft](bugs/{}))", draft.file_name);
+                    report.push_str(&icon_note_line(index, "bug.png", note.text.trim(), &link));
+                    std::fs::write(
+                        dir.join("bugs").join(&draft.file_name),
+                        format!(
+                            "# Bug: {}\n\n- **Charter**: x\n\n## Description\n",
+                            draft.title
+                        ),
+                    )
+                    .unwrap();
+                }
+                None if note.note_type == "question" => {
+                    report.push_str(&icon_note_line(index, "question.png", note.text.trim(), ""));
+                }
+                None => report.push_str(&format!(
+                    "<a id=\"{}\"></a>{}\n\n",
+                    note_anchor(index),
+                    note.text.trim()
+                )),
+            }
+        }
+        std::fs::write(report_path(&dir), report).unwrap();
+        write_session_record(&dir, &record).unwrap();
 
-    Ok(result)
+        dir
+    }
+
+    /// Report line for an icon-backed note (bug, question, ...), followed by an optional suffix.
+    fn icon_note_line(index: usize, icon_file: &str, text: &str, suffix: &str) -> String {
+        format!(
+        "<a id=\"{}\"></a><img src=\"assets/icons/{}\" width=\"50\" valign=\"middle\"> {}{}\n\n",
+        note_anchor(index),
+        icon_file,
+        text,
+        suffix
+    )
+    }
 }
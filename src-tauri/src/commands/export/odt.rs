@@ -0,0 +1,341 @@
+use super::document::{report_blocks, xml_escape, Block, Package, Picture, SummaryRow};
+use super::locale::Catalog;
+use super::template::ReportData;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A4 text area with 2 cm margins, in points.
+const TEXT_WIDTH: f32 = 481.9;
+const TEXT_HEIGHT: f32 = 728.5;
+const ICON_POINTS: f32 = 12.0;
+
+const MIMETYPE: &str = "application/vnd.oasis.opendocument.text";
+
+const NAMESPACES: &str = concat!(
+    r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" "#,
+    r#"xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" "#,
+    r#"xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" "#,
+    r#"xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" "#,
+    r#"xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0" "#,
+    r#"xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" "#,
+    r#"xmlns:xlink="http://www.w3.org/1999/xlink" "#,
+    r#"xmlns:svg="urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0" "#,
+    r#"xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+    r#"xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0" "#,
+    r#"office:version="1.3""#
+);
+
+/// LibreOffice's built-in style names (`Heading 1`, `Caption`, ...) so the document behaves
+/// like one written by hand, plus the A4 page layout.
+const STYLES: &str = r##"<office:font-face-decls><style:font-face style:name="Liberation Sans" svg:font-family="'Liberation Sans'" style:font-family-generic="swiss"/><style:font-face style:name="Liberation Mono" svg:font-family="'Liberation Mono'" style:font-family-generic="modern" style:font-pitch="fixed"/></office:font-face-decls><office:styles><style:default-style style:family="paragraph"><style:paragraph-properties fo:margin-bottom="0.2cm"/><style:text-properties style:font-name="Liberation Sans" fo:font-size="11pt" fo:language="en" fo:country="GB"/></style:default-style><style:style style:name="Standard" style:family="paragraph" style:class="text"/><style:style style:name="Title" style:family="paragraph" style:parent-style-name="Standard" style:class="chapter"><style:paragraph-properties fo:margin-bottom="0.4cm"/><style:text-properties fo:font-size="20pt" fo:font-weight="bold"/></style:style><style:style style:name="Heading_20_1" style:display-name="Heading 1" style:family="paragraph" style:parent-style-name="Standard" style:next-style-name="Standard" style:default-outline-level="1" style:class="text"><style:paragraph-properties fo:margin-top="0.6cm" fo:margin-bottom="0.2cm" fo:keep-with-next="always"/><style:text-properties fo:font-size="15pt" fo:font-weight="bold" fo:color="#1f3864"/></style:style><style:style style:name="Caption" style:family="paragraph" style:parent-style-name="Standard" style:class="extra"><style:paragraph-properties fo:margin-top="0.1cm" fo:margin-bottom="0.4cm"/><style:text-properties fo:font-size="9pt" fo:font-style="italic" fo:color="#595959"/></style:style><style:style style:name="Preformatted_20_Text" style:display-name="Preformatted Text" style:family="paragraph" style:parent-style-name="Standard" style:class="html"><style:paragraph-properties fo:margin-bottom="0.3cm" fo:padding="0.15cm" fo:border="0.5pt solid #d9d9d9" fo:background-color="#f2f2f2"/><style:text-properties style:font-name="Liberation Mono" fo:font-size="9pt"/></style:style><style:style style:name="Report_20_Footnote" style:display-name="Report Footnote" style:family="paragraph" style:parent-style-name="Standard"><style:paragraph-properties fo:margin-top="0.6cm"/><style:text-properties fo:font-size="8pt" fo:color="#7f7f7f"/></style:style><style:style style:name="Strong_20_Emphasis" style:display-name="Strong Emphasis" style:family="text"><style:text-properties fo:font-weight="bold"/></style:style></office:styles><office:automatic-styles><style:page-layout style:name="A4"><style:page-layout-properties fo:page-width="21cm" fo:page-height="29.7cm" style:print-orientation="portrait" fo:margin-top="2cm" fo:margin-bottom="2cm" fo:margin-left="2cm" fo:margin-right="2cm"/></style:page-layout></office:automatic-styles><office:master-styles><style:master-page style:name="Standard" style:page-layout-name="A4"/></office:master-styles>"##;
+
+/// Styles for the summary table and inline pictures.
+const AUTOMATIC_STYLES: &str = r##"<office:automatic-styles><style:style style:name="Summary" style:family="table"><style:table-properties style:width="9cm" table:align="left" fo:margin-bottom="0.3cm"/></style:style><style:style style:name="Summary.Icon" style:family="table-column"><style:table-column-properties style:column-width="1.2cm"/></style:style><style:style style:name="Summary.Type" style:family="table-column"><style:table-column-properties style:column-width="5.6cm"/></style:style><style:style style:name="Summary.Count" style:family="table-column"><style:table-column-properties style:column-width="2.2cm"/></style:style><style:style style:name="Summary.Head" style:family="table-cell"><style:table-cell-properties fo:padding="0.08cm" fo:border="0.5pt solid #bfbfbf" fo:background-color="#f2f2f2" style:vertical-align="middle"/></style:style><style:style style:name="Summary.Cell" style:family="table-cell"><style:table-cell-properties fo:padding="0.08cm" fo:border="0.5pt solid #bfbfbf" style:vertical-align="middle"/></style:style><style:style style:name="Figure" style:family="paragraph" style:parent-style-name="Standard"><style:paragraph-properties fo:margin-bottom="0cm" fo:keep-with-next="always"/></style:style><style:style style:name="Picture" style:family="graphic"><style:graphic-properties style:vertical-pos="middle" style:vertical-rel="line" fo:border="none"/></style:style></office:automatic-styles>"##;
+
+/// Images stored under `Pictures/`, each added once.
+struct Pictures<'a> {
+    export_dir: &'a Path,
+    by_path: HashMap<String, Option<(String, f32, f32)>>,
+    files: Vec<(String, Picture)>,
+    frames: u32,
+}
+
+impl Pictures<'_> {
+    /// Package path and natural size (points) of an image, adding it on first use.
+    fn get(&mut self, rel_path: &str) -> Option<(String, f32, f32)> {
+        if let Some(entry) = self.by_path.get(rel_path) {
+            return entry.clone();
+        }
+
+        let entry = Picture::load(self.export_dir, rel_path).map(|picture| {
+            let (width, height) = picture.fit(f32::MAX, f32::MAX);
+            let name = format!(
+                "Pictures/image{}.{}",
+                self.files.len() + 1,
+                picture.extension
+            );
+            self.files.push((name.clone(), picture));
+            (name, width, height)
+        });
+        self.by_path.insert(rel_path.to_string(), entry.clone());
+        entry
+    }
+
+    /// An inline picture frame, `width` × `height` points.
+    fn frame(&mut self, href: &str, width: f32, height: f32) -> String {
+        self.frames += 1;
+        format!(
+            concat!(
+                r#"<draw:frame draw:style-name="Picture" draw:name="Image{}" text:anchor-type="as-char" "#,
+                r#"svg:width="{:.2}pt" svg:height="{:.2}pt" draw:z-index="0">"#,
+                r#"<draw:image xlink:href="{}" xlink:type="simple" xlink:show="embed" xlink:actuate="onLoad"/>"#,
+                "</draw:frame>"
+            ),
+            self.frames, width, height, href
+        )
+    }
+
+    fn icon(&mut self, rel_path: &str) -> Option<String> {
+        let (href, _, _) = self.get(rel_path)?;
+        Some(self.frame(&href, ICON_POINTS, ICON_POINTS))
+    }
+}
+
+/// Text for a paragraph: line breaks, tabs and runs of spaces become ODF elements so they
+/// survive (ODF collapses whitespace like HTML).
+fn text(value: &str) -> String {
+    let mut xml = String::new();
+    for (i, line) in value.split('\n').enumerate() {
+        if i > 0 {
+            xml.push_str("<text:line-break/>");
+        }
+
+        let mut spaces = 0;
+        let flush = |xml: &mut String, spaces: &mut usize, at_start: bool| {
+            match *spaces {
+                0 => {}
+                1 if !at_start => xml.push(' '),
+                1 => xml.push_str("<text:s/>"),
+                n if at_start => xml.push_str(&format!("<text:s text:c=\"{}\"/>", n)),
+                n => xml.push_str(&format!(" <text:s text:c=\"{}\"/>", n - 1)),
+            }
+            *spaces = 0;
+        };
+
+        let mut at_start = true;
+        for c in line.trim_end_matches('\r').chars() {
+            match c {
+                ' ' => spaces += 1,
+                '\t' => {
+                    flush(&mut xml, &mut spaces, at_start);
+                    xml.push_str("<text:tab/>");
+                    at_start = false;
+                }
+                c => {
+                    flush(&mut xml, &mut spaces, at_start);
+                    xml.push_str(&xml_escape(&c.to_string()));
+                    at_start = false;
+                }
+            }
+        }
+        // Trailing spaces are collapsed too.
+        if spaces > 0 {
+            xml.push_str(&format!("<text:s text:c=\"{}\"/>", spaces));
+        }
+    }
+    xml
+}
+
+fn paragraph(style: &str, content: &str) -> String {
+    format!("<text:p text:style-name=\"{}\">{}</text:p>", style, content)
+}
+
+fn summary_table(headings: &[String; 2], rows: &[SummaryRow], pictures: &mut Pictures) -> String {
+    let cell = |style: &str, content: &str| {
+        format!(
+            "<table:table-cell table:style-name=\"{}\" office:value-type=\"string\"><text:p>{}</text:p></table:table-cell>",
+            style, content
+        )
+    };
+    let strong = |value: &str| {
+        format!(
+            "<text:span text:style-name=\"Strong_20_Emphasis\">{}</text:span>",
+            text(value)
+        )
+    };
+
+    let mut xml = String::from(concat!(
+        r#"<table:table table:name="Summary" table:style-name="Summary">"#,
+        r#"<table:table-column table:style-name="Summary.Icon"/>"#,
+        r#"<table:table-column table:style-name="Summary.Type"/>"#,
+        r#"<table:table-column table:style-name="Summary.Count"/>"#,
+        "<table:table-header-rows><table:table-row>"
+    ));
+    xml.push_str(&cell("Summary.Head", ""));
+    xml.push_str(&cell("Summary.Head", &strong(&headings[0])));
+    xml.push_str(&cell("Summary.Head", &strong(&headings[1])));
+    xml.push_str("</table:table-row></table:table-header-rows>");
+
+    for row in rows {
+        let icon = pictures.icon(&row.icon).unwrap_or_default();
+        xml.push_str("<table:table-row>");
+        xml.push_str(&cell("Summary.Cell", &icon));
+        xml.push_str(&cell("Summary.Cell", &text(&row.label)));
+        xml.push_str(&cell("Summary.Cell", &row.count.to_string()));
+        xml.push_str("</table:table-row>");
+    }
+
+    xml.push_str("</table:table>");
+    xml
+}
+
+fn content_xml(blocks: &[Block], pictures: &mut Pictures) -> String {
+    let mut body = String::new();
+    for block in blocks {
+        let xml = match block {
+            Block::Title(value) => paragraph("Title", &text(value)),
+            Block::Heading(value) => format!(
+                "<text:h text:style-name=\"Heading_20_1\" text:outline-level=\"1\">{}</text:h>",
+                text(value)
+            ),
+            Block::Field { label, value } => paragraph(
+                "Standard",
+                &format!(
+                    "<text:span text:style-name=\"Strong_20_Emphasis\">{}: </text:span>{}",
+                    text(label),
+                    text(value)
+                ),
+            ),
+            Block::Summary { headings, rows } => summary_table(headings, rows, pictures),
+            Block::Note { icon, text: value } => {
+                let icon = icon
+                    .as_deref()
+                    .and_then(|icon| pictures.icon(icon))
+                    .map(|frame| format!("{} ", frame))
+                    .unwrap_or_default();
+                paragraph("Standard", &format!("{}{}", icon, text(value)))
+            }
+            Block::Figure { path, caption } => match pictures.get(path) {
+                Some((href, width, height)) => {
+                    let scale = (TEXT_WIDTH / width)
+                        .min(TEXT_HEIGHT * 0.8 / height)
+                        .min(1.0);
+                    let frame = pictures.frame(&href, width * scale, height * scale);
+                    format!(
+                        "{}{}",
+                        paragraph("Figure", &frame),
+                        paragraph("Caption", &text(caption))
+                    )
+                }
+                None => paragraph("Standard", &text(&format!("{} ({})", caption, path))),
+            },
+            Block::Code(value) => paragraph("Preformatted_20_Text", &text(value)),
+            Block::Text(value) => paragraph("Standard", &text(value)),
+            Block::Footnote(value) => paragraph("Report_20_Footnote", &text(value)),
+        };
+        body.push_str(&xml);
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<office:document-content {}>{}<office:body><office:text>{}</office:text></office:body></office:document-content>",
+        NAMESPACES, AUTOMATIC_STYLES, body
+    )
+}
+
+/// Builds the report as an OpenDocument text file with the same layout as the Word export.
+pub(crate) fn render_report_odt(
+    data: &ReportData,
+    export_dir: &Path,
+    catalog: &Catalog,
+) -> Result<Vec<u8>, String> {
+    let mut pictures = Pictures {
+        export_dir,
+        by_path: HashMap::new(),
+        files: Vec::new(),
+        frames: 0,
+    };
+    let content = content_xml(&report_blocks(data), &mut pictures);
+
+    let styles = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<office:document-styles {}>{}</office:document-styles>",
+        NAMESPACES, STYLES
+    );
+    let meta = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<office:document-meta {}><office:meta><dc:title>{}</dc:title>{}<meta:generator>Rapid Reporter/{}</meta:generator></office:meta></office:document-meta>",
+        NAMESPACES,
+        xml_escape(&catalog.document_title(&data.session.charter)),
+        data.session
+            .tester
+            .as_deref()
+            .map(|tester| format!("<meta:initial-creator>{}</meta:initial-creator>", xml_escape(tester)))
+            .unwrap_or_default(),
+        data.app.version,
+    );
+
+    let mut manifest = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        r#"<manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.3">"#,
+        r#"<manifest:file-entry manifest:full-path="/" manifest:version="1.3" manifest:media-type="application/vnd.oasis.opendocument.text"/>"#,
+        r#"<manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>"#,
+        r#"<manifest:file-entry manifest:full-path="styles.xml" manifest:media-type="text/xml"/>"#,
+        r#"<manifest:file-entry manifest:full-path="meta.xml" manifest:media-type="text/xml"/>"#
+    ));
+    for (name, picture) in &pictures.files {
+        manifest.push_str(&format!(
+            r#"<manifest:file-entry manifest:full-path="{}" manifest:media-type="{}"/>"#,
+            name,
+            picture.mime_type()
+        ));
+    }
+    manifest.push_str("</manifest:manifest>");
+
+    let mut package = Package::new();
+    // The mimetype must come first and uncompressed so the file type can be sniffed.
+    package.add_stored("mimetype", MIMETYPE.as_bytes())?;
+    package.add("META-INF/manifest.xml", manifest.as_bytes())?;
+    package.add("content.xml", content.as_bytes())?;
+    package.add("styles.xml", styles.as_bytes())?;
+    package.add("meta.xml", meta.as_bytes())?;
+    for (name, picture) in &pictures.files {
+        package.add(name, &picture.bytes)?;
+    }
+    package.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::template::sample_data;
+    use crate::commands::export::test_support::TempDir;
+    use std::io::Read;
+
+    #[test]
+    fn odt_starts_with_an_uncompressed_mimetype_and_lists_every_picture() {
+        let dir = TempDir::new("odt");
+        std::fs::create_dir_all(dir.join("assets/screenshots")).unwrap();
+        image::RgbImage::from_pixel(400, 300, image::Rgb([200, 30, 30]))
+            .save(dir.join("assets/screenshots/refund.png"))
+            .unwrap();
+
+        let bytes = render_report_odt(&sample_data(), &dir, Catalog::english()).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+
+        let mimetype = archive.by_index(0).unwrap();
+        assert_eq!(mimetype.name(), "mimetype");
+        assert_eq!(mimetype.compression(), zip::CompressionMethod::Stored);
+        drop(mimetype);
+
+        let mut content = String::new();
+        archive
+            .by_name("content.xml")
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        assert!(content.contains(r#"<table:table table:name="Summary""#));
+        // The missing bug icon is skipped; the screenshot keeps its 96 dpi size.
+        assert!(content.contains(r#"svg:width="300.00pt" svg:height="225.00pt""#));
+        assert!(content.contains("Figure 1: refund.png"));
+        assert!(content.contains(
+            r#"<text:p text:style-name="Preformatted_20_Text">HTTP 504 Gateway Timeout</text:p>"#
+        ));
+
+        let mut manifest = String::new();
+        archive
+            .by_name("META-INF/manifest.xml")
+            .unwrap()
+            .read_to_string(&mut manifest)
+            .unwrap();
+        assert!(manifest.contains(
+            r#"manifest:full-path="Pictures/image1.png" manifest:media-type="image/png""#
+        ));
+        assert!(archive.by_name("Pictures/image1.png").is_ok());
+    }
+
+    #[test]
+    fn repeated_spaces_tabs_and_line_breaks_are_kept() {
+        assert_eq!(
+            text("  a  b\tc \nd"),
+            r#"<text:s text:c="2"/>a <text:s text:c="1"/>b<text:tab/>c<text:s text:c="1"/><text:line-break/>d"#
+        );
+    }
+}
@@ -0,0 +1,713 @@
+use super::locale::Catalog;
+use super::template::ReportData;
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// A4 portrait, in points.
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const CONTENT_WIDTH: f32 = PAGE_WIDTH - 2.0 * MARGIN;
+/// Space kept free at the bottom of every page for the page number.
+const FOOTER_HEIGHT: f32 = 20.0;
+
+const BODY_SIZE: f32 = 10.5;
+const BODY_LEADING: f32 = 14.0;
+const MONO_SIZE: f32 = 8.5;
+const MONO_LEADING: f32 = 11.0;
+const ICON_SIZE: f32 = 13.0;
+const ICON_INDENT: f32 = 19.0;
+/// Screenshots are downscaled to at most this width before embedding.
+const MAX_IMAGE_PIXELS: u32 = 1600;
+
+/// The PDF standard fonts: always available to viewers, so nothing has to be embedded.
+#[derive(Clone, Copy, PartialEq)]
+enum Font {
+    Regular,
+    Bold,
+    Mono,
+}
+
+/// Helvetica glyph widths (per 1000 units of font size) for ASCII 32..=126.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Helvetica-Bold glyph widths for ASCII 32..=126.
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// ASCII look-alikes for the accented letters in WinAnsi 0xC0..=0xFF, used for their widths.
+const LATIN1_BASE: &[u8; 64] = b"AAAAAAACEEEEIIIIDNOOOOOxOUUUUYPsaaaaaaaceeeeiiiidnooooo/ouuuuypy";
+
+impl Font {
+    fn resource(self) -> Name<'static> {
+        match self {
+            Font::Regular => Name(b"F1"),
+            Font::Bold => Name(b"F2"),
+            Font::Mono => Name(b"F3"),
+        }
+    }
+
+    fn base_font(self) -> Name<'static> {
+        match self {
+            Font::Regular => Name(b"Helvetica"),
+            Font::Bold => Name(b"Helvetica-Bold"),
+            Font::Mono => Name(b"Courier"),
+        }
+    }
+
+    /// Width of a WinAnsi-encoded byte, per 1000 units of font size.
+    fn glyph_width(self, byte: u8) -> f32 {
+        let table = match self {
+            Font::Mono => return 600.0,
+            Font::Regular => &HELVETICA_WIDTHS,
+            Font::Bold => &HELVETICA_BOLD_WIDTHS,
+        };
+
+        let ascii = match byte {
+            0xC0..=0xFF => LATIN1_BASE[(byte - 0xC0) as usize],
+            other => other,
+        };
+        match ascii {
+            32..=126 => table[(ascii - 32) as usize] as f32,
+            _ => 556.0,
+        }
+    }
+
+    fn width(self, text: &[u8], size: f32) -> f32 {
+        text.iter().map(|b| self.glyph_width(*b)).sum::<f32>() * size / 1000.0
+    }
+}
+
+/// Encodes text for the standard fonts (WinAnsiEncoding). Characters outside it, such as
+/// emoji or CJK, become `?`.
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .filter_map(|c| match c {
+            '\n' => Some(b'\n'),
+            '\t' => Some(b' '),
+            ' '..='~' => Some(c as u8),
+            '\u{a0}'..='\u{ff}' => Some(c as u32 as u8),
+            '€' => Some(0x80),
+            '‚' => Some(0x82),
+            'ƒ' => Some(0x83),
+            '„' => Some(0x84),
+            '…' => Some(0x85),
+            '†' => Some(0x86),
+            '‡' => Some(0x87),
+            'ˆ' => Some(0x88),
+            '‰' => Some(0x89),
+            'Š' => Some(0x8A),
+            '‹' => Some(0x8B),
+            'Œ' => Some(0x8C),
+            'Ž' => Some(0x8E),
+            '‘' => Some(0x91),
+            '’' => Some(0x92),
+            '“' => Some(0x93),
+            '”' => Some(0x94),
+            '•' => Some(0x95),
+            '–' => Some(0x96),
+            '—' => Some(0x97),
+            '˜' => Some(0x98),
+            '™' => Some(0x99),
+            'š' => Some(0x9A),
+            '›' => Some(0x9B),
+            'œ' => Some(0x9C),
+            'ž' => Some(0x9E),
+            'Ÿ' => Some(0x9F),
+            // Zero-width characters and emoji variation selectors.
+            '\u{200b}'..='\u{200f}' | '\u{fe00}'..='\u{fe0f}' => None,
+            c if c.is_control() => None,
+            _ => Some(b'?'),
+        })
+        .collect()
+}
+
+/// Breaks encoded text into lines no wider than `width` (the first line may be narrower),
+/// keeping explicit line breaks and splitting words that do not fit on a line of their own.
+fn wrap(text: &[u8], font: Font, size: f32, first_width: f32, width: f32) -> Vec<Vec<u8>> {
+    let space = font.width(b" ", size);
+    let mut lines: Vec<Vec<u8>> = Vec::new();
+
+    for paragraph in text.split(|b| *b == b'\n') {
+        let mut line: Vec<u8> = Vec::new();
+        let mut line_width = 0.0;
+
+        for word in paragraph.split(|b| *b == b' ') {
+            let max = if lines.is_empty() { first_width } else { width };
+            let word_width = font.width(word, size);
+
+            if !line.is_empty() && line_width + space + word_width > max {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0.0;
+            } else if !line.is_empty() {
+                line.push(b' ');
+                line_width += space;
+            }
+
+            for &byte in word {
+                let max = if lines.is_empty() { first_width } else { width };
+                let glyph = font.width(&[byte], size);
+                if !line.is_empty() && line_width + glyph > max {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0.0;
+                }
+                line.push(byte);
+                line_width += glyph;
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+struct Page {
+    content: Content,
+    /// Images drawn on this page, as (resource name, object).
+    images: Vec<(String, Ref)>,
+}
+
+struct Embedded {
+    id: Ref,
+    name: String,
+    width: u32,
+    height: u32,
+}
+
+/// Lays out report content top to bottom, starting new pages as needed.
+struct Layout<'a> {
+    export_dir: &'a Path,
+    pdf: Pdf,
+    next_ref: i32,
+    pages: Vec<Page>,
+    y: f32,
+    /// Embedded images by path; `None` when the file could not be decoded.
+    images: HashMap<PathBuf, Option<Embedded>>,
+}
+
+const CATALOG: Ref = Ref::new(1);
+const PAGE_TREE: Ref = Ref::new(2);
+const FONTS: [(Font, Ref); 3] = [
+    (Font::Regular, Ref::new(3)),
+    (Font::Bold, Ref::new(4)),
+    (Font::Mono, Ref::new(5)),
+];
+
+impl<'a> Layout<'a> {
+    fn new(export_dir: &'a Path) -> Self {
+        let mut layout = Layout {
+            export_dir,
+            pdf: Pdf::new(),
+            next_ref: 6,
+            pages: Vec::new(),
+            y: 0.0,
+            images: HashMap::new(),
+        };
+        layout.new_page();
+        layout
+    }
+
+    fn alloc(&mut self) -> Ref {
+        let id = Ref::new(self.next_ref);
+        self.next_ref += 1;
+        id
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(Page {
+            content: Content::new(),
+            images: Vec::new(),
+        });
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn page(&mut self) -> &mut Page {
+        self.pages.last_mut().expect("layout always has a page")
+    }
+
+    /// Starts a new page unless `height` still fits on the current one.
+    fn ensure(&mut self, height: f32) {
+        let at_top = self.y >= PAGE_HEIGHT - MARGIN;
+        if !at_top && self.y - height < MARGIN + FOOTER_HEIGHT {
+            self.new_page();
+        }
+    }
+
+    fn gap(&mut self, height: f32) {
+        if self.y < PAGE_HEIGHT - MARGIN {
+            self.y -= height;
+        }
+    }
+
+    fn show(&mut self, x: f32, baseline: f32, font: Font, size: f32, text: &[u8]) {
+        self.page()
+            .content
+            .begin_text()
+            .set_font(font.resource(), size)
+            .next_line(x, baseline)
+            .show(Str(text))
+            .end_text();
+    }
+
+    fn paragraph(&mut self, text: &str, font: Font, size: f32, leading: f32, indent: f32) {
+        let width = CONTENT_WIDTH - indent;
+        for line in wrap(&encode(text), font, size, width, width) {
+            self.ensure(leading);
+            self.show(MARGIN + indent, self.y - size, font, size, &line);
+            self.y -= leading;
+        }
+    }
+
+    fn heading(&mut self, text: &str, size: f32) {
+        self.gap(size * 0.8);
+        // Keep the heading together with at least two lines of what follows.
+        self.ensure(size * 1.4 + 2.0 * BODY_LEADING);
+        self.paragraph(text, Font::Bold, size, size * 1.4, 0.0);
+        self.gap(4.0);
+    }
+
+    /// A `Label: value` metadata line with a bold label.
+    fn label_value(&mut self, label: &str, value: &str) {
+        let label = encode(&format!("{}: ", label));
+        let label_width = Font::Bold.width(&label, BODY_SIZE);
+        let lines = wrap(
+            &encode(value),
+            Font::Regular,
+            BODY_SIZE,
+            CONTENT_WIDTH - label_width,
+            CONTENT_WIDTH,
+        );
+
+        for (i, line) in lines.iter().enumerate() {
+            self.ensure(BODY_LEADING);
+            let baseline = self.y - BODY_SIZE;
+            let mut x = MARGIN;
+            if i == 0 {
+                self.show(x, baseline, Font::Bold, BODY_SIZE, &label);
+                x += label_width;
+            }
+            self.show(x, baseline, Font::Regular, BODY_SIZE, line);
+            self.y -= BODY_LEADING;
+        }
+    }
+
+    /// A note (or summary line) with its type icon to the left of the text.
+    fn icon_line(&mut self, icon: &str, text: &str) {
+        self.ensure(BODY_LEADING.max(ICON_SIZE));
+        let top = self.y;
+        let page = self.pages.len();
+        // Register the icon before the text can run onto a new page.
+        let icon = self.embed(icon);
+        self.paragraph(text, Font::Regular, BODY_SIZE, BODY_LEADING, ICON_INDENT);
+
+        // Draw the icon next to the first line, on the page that line landed on.
+        if let Some((name, _, _)) = icon {
+            let y = top - (BODY_LEADING + ICON_SIZE) / 2.0 + 1.0;
+            let page = &mut self.pages[page - 1];
+            page.content
+                .save_state()
+                .transform([ICON_SIZE, 0.0, 0.0, ICON_SIZE, MARGIN, y])
+                .x_object(Name(name.as_bytes()))
+                .restore_state();
+        }
+    }
+
+    /// Draws a screenshot scaled to the content width (never upscaled), on a new page when
+    /// it does not fit. Returns false when the file cannot be read as an image.
+    fn image(&mut self, rel_path: &str) -> bool {
+        let Some((name, width, height)) = self.embed(rel_path) else {
+            return false;
+        };
+
+        let max_height = PAGE_HEIGHT - 2.0 * MARGIN - FOOTER_HEIGHT;
+        // 0.75pt per pixel is the 96 dpi screen size; small captures are not blown up past it.
+        let scale = (CONTENT_WIDTH / width).min(max_height / height).min(0.75);
+        let (w, h) = (width * scale, height * scale);
+
+        self.ensure(h);
+        let y = self.y - h;
+        self.page()
+            .content
+            .save_state()
+            .transform([w, 0.0, 0.0, h, MARGIN, y])
+            .x_object(Name(name.as_bytes()))
+            .restore_state();
+        self.y = y - 6.0;
+
+        true
+    }
+
+    /// A monospaced block on a light grey background, split across pages as needed.
+    fn snippet(&mut self, text: &str) {
+        let padding = 6.0;
+        let width = CONTENT_WIDTH - 2.0 * padding;
+        let lines = wrap(&encode(text), Font::Mono, MONO_SIZE, width, width);
+
+        for (i, line) in lines.iter().enumerate() {
+            let top_pad = if i == 0 { 3.0 } else { 0.0 };
+            let bottom_pad = if i + 1 == lines.len() { 3.0 } else { 0.0 };
+            let height = MONO_LEADING + top_pad + bottom_pad;
+            self.ensure(height);
+
+            let y = self.y;
+            let content = &mut self.page().content;
+            content
+                .set_fill_gray(0.94)
+                .rect(MARGIN, y - height, CONTENT_WIDTH, height)
+                .fill_nonzero()
+                .set_fill_gray(0.0);
+            self.show(
+                MARGIN + padding,
+                y - top_pad - MONO_SIZE,
+                Font::Mono,
+                MONO_SIZE,
+                line,
+            );
+            self.y -= height;
+        }
+    }
+
+    /// Embeds an image from the export folder once and registers it on the current page.
+    /// Returns its resource name and pixel size.
+    fn embed(&mut self, rel_path: &str) -> Option<(String, f32, f32)> {
+        let path = self.export_dir.join(rel_path);
+        if !self.images.contains_key(&path) {
+            let embedded = self.decode_and_write(&path);
+            self.images.insert(path.clone(), embedded);
+        }
+
+        let embedded = self.images.get(&path)?.as_ref()?;
+        let (id, name) = (embedded.id, embedded.name.clone());
+        let size = (embedded.width as f32, embedded.height as f32);
+
+        let page = self.page();
+        if !page.images.iter().any(|(_, r)| *r == id) {
+            page.images.push((name.clone(), id));
+        }
+
+        Some((name, size.0, size.1))
+    }
+
+    fn decode_and_write(&mut self, path: &Path) -> Option<Embedded> {
+        // GIF and APNG recordings decode to their first frame.
+        let decoded = image::open(path).ok()?;
+        let (width, height) = (decoded.width(), decoded.height());
+        let decoded = if width > MAX_IMAGE_PIXELS {
+            decoded.resize(
+                MAX_IMAGE_PIXELS,
+                u32::MAX,
+                image::imageops::FilterType::Triangle,
+            )
+        } else {
+            decoded
+        };
+
+        let id = self.alloc();
+        let mask_id = decoded.color().has_alpha().then(|| self.alloc());
+
+        let rgb = compress_to_vec_zlib(decoded.to_rgb8().as_raw(), 6);
+        let mut xobject = self.pdf.image_xobject(id, &rgb);
+        xobject.filter(Filter::FlateDecode);
+        xobject.width(decoded.width() as i32);
+        xobject.height(decoded.height() as i32);
+        xobject.color_space().device_rgb();
+        xobject.bits_per_component(8);
+        if let Some(mask_id) = mask_id {
+            xobject.s_mask(mask_id);
+        }
+        xobject.finish();
+
+        if let Some(mask_id) = mask_id {
+            let alpha: Vec<u8> = decoded.to_rgba8().pixels().map(|p| p.0[3]).collect();
+            let alpha = compress_to_vec_zlib(&alpha, 6);
+            let mut mask = self.pdf.image_xobject(mask_id, &alpha);
+            mask.filter(Filter::FlateDecode);
+            mask.width(decoded.width() as i32);
+            mask.height(decoded.height() as i32);
+            mask.color_space().device_gray();
+            mask.bits_per_component(8);
+            mask.finish();
+        }
+
+        Some(Embedded {
+            id,
+            name: format!("Im{}", id.get()),
+            // Layout uses the original size so downscaling does not shrink the image on paper.
+            width,
+            height,
+        })
+    }
+
+    fn finish(mut self, title: &str, catalog: &Catalog) -> Vec<u8> {
+        let total = self.pages.len();
+        let pages = std::mem::take(&mut self.pages);
+        let mut page_ids = Vec::with_capacity(total);
+
+        for (i, mut page) in pages.into_iter().enumerate() {
+            let footer = encode(&catalog.page_footer(i + 1, total));
+            let x = PAGE_WIDTH - MARGIN - Font::Regular.width(&footer, 8.0);
+            page.content
+                .set_fill_gray(0.45)
+                .begin_text()
+                .set_font(Font::Regular.resource(), 8.0)
+                .next_line(x, MARGIN / 2.0)
+                .show(Str(&footer))
+                .end_text();
+
+            let page_id = self.alloc();
+            let content_id = self.alloc();
+            page_ids.push(page_id);
+
+            let stream = compress_to_vec_zlib(&page.content.finish(), 6);
+            self.pdf
+                .stream(content_id, &stream)
+                .filter(Filter::FlateDecode);
+
+            let mut pdf_page = self.pdf.page(page_id);
+            pdf_page.media_box(Rect::new(0.0, 0.0, PAGE_WIDTH, PAGE_HEIGHT));
+            pdf_page.parent(PAGE_TREE);
+            pdf_page.contents(content_id);
+            let mut resources = pdf_page.resources();
+            let mut fonts = resources.fonts();
+            for (font, id) in FONTS {
+                fonts.pair(font.resource(), id);
+            }
+            fonts.finish();
+            let mut x_objects = resources.x_objects();
+            for (name, id) in &page.images {
+                x_objects.pair(Name(name.as_bytes()), *id);
+            }
+            x_objects.finish();
+            resources.finish();
+            pdf_page.finish();
+        }
+
+        for (font, id) in FONTS {
+            self.pdf
+                .type1_font(id)
+                .base_font(font.base_font())
+                .encoding_predefined(Name(b"WinAnsiEncoding"));
+        }
+
+        self.pdf.catalog(CATALOG).pages(PAGE_TREE);
+        self.pdf.pages(PAGE_TREE).kids(page_ids).count(total as i32);
+
+        let info_id = self.alloc();
+        self.pdf
+            .document_info(info_id)
+            .title(TextStr(title))
+            .creator(TextStr(concat!(
+                "Rapid Reporter ",
+                env!("CARGO_PKG_VERSION")
+            )));
+
+        self.pdf.finish()
+    }
+}
+
+/// Lays out the report as a PDF: metadata, environment, summary, then every note with its
+/// icon, snippets in monospace and screenshots scaled to the page. Images are read from
+/// `export_dir`, where the markdown export copied them. `catalog` gives the document title and
+/// page footers in the report language.
+pub(crate) fn render_report_pdf(
+    data: &ReportData,
+    export_dir: &Path,
+    catalog: &Catalog,
+) -> Vec<u8> {
+    let mut layout = Layout::new(export_dir);
+
+    layout.paragraph(data.label("reportTitle"), Font::Bold, 20.0, 26.0, 0.0);
+    layout.gap(6.0);
+
+    let session = &data.session;
+    if let Some(tester) = session.tester.as_deref() {
+        layout.label_value(data.label("tester"), tester);
+    }
+    layout.label_value(data.label("charter"), &session.charter);
+    if let Some(charter) = session.library_charter.as_ref() {
+        layout.label_value(
+            data.label("libraryCharter"),
+            &format!("{} ({})", charter.title, charter.id),
+        );
+    }
+    layout.label_value(data.label("started"), &session.started);
+    for entry in &session.timing {
+        layout.label_value(&entry.label, &entry.value);
+    }
+
+    if !data.environment.is_empty() {
+        layout.heading(data.label("environment"), 14.0);
+        for entry in &data.environment {
+            layout.label_value(&entry.label, &entry.value);
+        }
+    }
+
+    if !data.summary.is_empty() {
+        layout.heading(data.label("summary"), 14.0);
+        for count in &data.summary {
+            layout.icon_line(&count.icon, &count.label);
+        }
+    }
+
+    layout.heading(data.label("notes"), 14.0);
+    for note in &data.notes {
+        match note.kind {
+            "image" => {
+                let alt = note
+                    .image
+                    .as_ref()
+                    .map_or(data.label("screenshot"), |i| i.alt.as_str());
+                match note.image.as_ref().and_then(|i| i.path.as_deref()) {
+                    Some(path) if layout.image(path) => {}
+                    Some(path) => layout.paragraph(
+                        &format!("{}: {}", alt, path),
+                        Font::Regular,
+                        BODY_SIZE,
+                        BODY_LEADING,
+                        0.0,
+                    ),
+                    None => layout.paragraph(
+                        &format!("{} ({}): {}", alt, data.label("copyFailed"), note.text),
+                        Font::Regular,
+                        BODY_SIZE,
+                        BODY_LEADING,
+                        0.0,
+                    ),
+                }
+            }
+            "attachment" => {
+                let line = match note.attachment.as_ref() {
+                    Some(a) => format!(
+                        "{}: {} ({}, SHA-256 {})",
+                        data.label("attachment"),
+                        a.file_name,
+                        a.size,
+                        a.sha256
+                    ),
+                    None => format!(
+                        "{} ({}): {}",
+                        data.label("attachment"),
+                        data.label("copyFailed"),
+                        note.text
+                    ),
+                };
+                layout.paragraph(&line, Font::Regular, BODY_SIZE, BODY_LEADING, 0.0);
+            }
+            "snippet" => layout.snippet(&note.raw_text),
+            _ => match note.icon.as_deref() {
+                Some(icon) => layout.icon_line(icon, &note.raw_text),
+                None => {
+                    layout.paragraph(&note.raw_text, Font::Regular, BODY_SIZE, BODY_LEADING, 0.0)
+                }
+            },
+        }
+        layout.gap(6.0);
+    }
+
+    layout.gap(10.0);
+    layout.paragraph(
+        &format!("{} v{}", data.label("generatedBy"), data.app.version),
+        Font::Regular,
+        8.0,
+        10.0,
+        0.0,
+    );
+
+    layout.finish(&catalog.document_title(&session.charter), catalog)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::template::sample_data;
+    use crate::commands::export::test_support::TempDir;
+
+    #[test]
+    fn text_is_encoded_for_the_standard_fonts() {
+        assert_eq!(
+            encode("Café – 5€ 📎\u{fe0f}"),
+            b"Caf\xe9 \x96 5\x80 ?".to_vec()
+        );
+    }
+
+    #[test]
+    fn wrap_respects_widths_and_breaks_long_words() {
+        let text = encode("Refund times out after 30 seconds on the payment page");
+        let width = Font::Regular.width(b"Refund times out after", 10.0) + 1.0;
+        let lines = wrap(&text, Font::Regular, 10.0, width, width);
+
+        assert_eq!(
+            lines,
+            vec![
+                b"Refund times out after".to_vec(),
+                b"30 seconds on the".to_vec(),
+                b"payment page".to_vec(),
+            ]
+        );
+        for line in &lines {
+            assert!(Font::Regular.width(line, 10.0) <= width);
+        }
+
+        let lines = wrap(b"abcdefghij\nxy", Font::Mono, 10.0, 30.0, 30.0);
+        assert_eq!(
+            lines,
+            vec![b"abcde".to_vec(), b"fghij".to_vec(), b"xy".to_vec()]
+        );
+    }
+
+    #[test]
+    fn long_sessions_flow_onto_more_pages_with_screenshots_embedded() {
+        let dir = TempDir::new("pdf");
+        std::fs::create_dir_all(dir.join("assets/screenshots")).unwrap();
+        image::RgbImage::from_pixel(1200, 800, image::Rgb([200, 30, 30]))
+            .save(dir.join("assets/screenshots/refund.png"))
+            .unwrap();
+
+        let mut data = sample_data();
+        let snippet = data.notes[6].clone();
+        for _ in 0..60 {
+            data.notes.push(snippet.clone());
+        }
+
+        let pdf = render_report_pdf(&data, &dir, Catalog::english());
+        let raw = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(raw.contains("/Subtype /Image"));
+        assert!(raw.contains("/BaseFont /Courier"));
+        let pages = raw.matches("/Type /Page\n").count() + raw.matches("/Type /Page ").count();
+        assert!(pages >= 2, "expected several pages, got {}", pages);
+    }
+
+    #[test]
+    fn icons_are_registered_on_the_page_of_the_first_line() {
+        let dir = TempDir::new("pdf-icon");
+        std::fs::create_dir_all(dir.join("assets/icons")).unwrap();
+        image::RgbImage::from_pixel(8, 8, image::Rgb([30, 30, 200]))
+            .save(dir.join("assets/icons/bug.png"))
+            .unwrap();
+
+        let mut layout = Layout::new(&dir);
+        // Room for the first line of the note only; the rest goes onto the next page.
+        layout.y = MARGIN + FOOTER_HEIGHT + BODY_LEADING.max(ICON_SIZE) + 1.0;
+        layout.icon_line("assets/icons/bug.png", &"Refund times out ".repeat(40));
+
+        assert_eq!(layout.pages.len(), 2);
+        assert_eq!(layout.pages[0].images.len(), 1);
+        assert!(layout.pages[1].images.is_empty());
+    }
+}
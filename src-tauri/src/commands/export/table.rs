@@ -0,0 +1,287 @@
+use super::document::{xml_escape, Package};
+use super::template::ReportData;
+use super::zone;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+
+/// Column headers, as report labels.
+const COLUMNS: [&str; 7] = [
+    "index",
+    "timestamp",
+    "type",
+    "text",
+    "path",
+    "tester",
+    "charter",
+];
+
+/// One row of the notes table.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NoteRow {
+    pub(crate) index: usize,
+    /// When the note was taken, in the report's time zone, when the frontend sent it.
+    pub(crate) timestamp: Option<DateTime<FixedOffset>>,
+    pub(crate) note_type: String,
+    pub(crate) text: String,
+    /// Screenshot or attachment path relative to the export folder.
+    pub(crate) path: String,
+    pub(crate) tester: String,
+    pub(crate) charter: String,
+}
+
+/// Column headers in the report's language.
+pub(crate) fn note_headers(data: &ReportData) -> Vec<String> {
+    COLUMNS
+        .iter()
+        .map(|column| data.label(column).to_string())
+        .collect()
+}
+
+/// One row per note, in the order of the report.
+pub(crate) fn note_rows(data: &ReportData) -> Vec<NoteRow> {
+    data.notes
+        .iter()
+        .map(|note| NoteRow {
+            index: note.number,
+            timestamp: note
+                .timestamp_iso
+                .as_deref()
+                .and_then(|iso| DateTime::parse_from_rfc3339(iso).ok()),
+            note_type: note.note_type.clone(),
+            text: note.raw_text.clone(),
+            path: note
+                .image
+                .as_ref()
+                .and_then(|i| i.path.clone())
+                .or_else(|| note.attachment.as_ref().map(|a| a.path.clone()))
+                .unwrap_or_default(),
+            tester: data.session.tester.clone().unwrap_or_default(),
+            charter: data.session.charter.clone(),
+        })
+        .collect()
+}
+
+fn format_timestamp(timestamp: Option<DateTime<FixedOffset>>) -> String {
+    timestamp.map(|t| zone::iso(&t)).unwrap_or_default()
+}
+
+/// Quotes a CSV field when needed (RFC 4180). Text that a spreadsheet would run as a formula
+/// is prefixed with `'`.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// The notes table as CSV: UTF-8 with a byte order mark (so Excel detects the encoding) and
+/// CRLF row endings; multi-line notes stay in one quoted field. Timestamps are ISO 8601 with
+/// their UTC offset.
+pub(crate) fn render_notes_csv(headers: &[String], rows: &[NoteRow]) -> String {
+    let mut csv = String::from("\u{feff}");
+    let headers: Vec<String> = headers.iter().map(|h| csv_field(h)).collect();
+    csv.push_str(&headers.join(","));
+    csv.push_str("\r\n");
+
+    for row in rows {
+        let fields = [
+            row.index.to_string(),
+            format_timestamp(row.timestamp),
+            row.note_type.clone(),
+            row.text.clone(),
+            row.path.clone(),
+            row.tester.clone(),
+            row.charter.clone(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+/// Spreadsheet column letter for a 0-based column index (up to 26 columns).
+fn column(index: usize) -> char {
+    (b'A' + index as u8) as char
+}
+
+/// Days since 1899-12-30, the date serial number spreadsheets store date-times as.
+fn serial_date(timestamp: NaiveDateTime) -> f64 {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .expect("valid epoch");
+    (timestamp - epoch).num_milliseconds() as f64 / 86_400_000.0
+}
+
+fn string_cell(reference: &str, value: &str, style: u32) -> String {
+    format!(
+        "<c r=\"{}\" s=\"{}\" t=\"inlineStr\"><is><t xml:space=\"preserve\">{}</t></is></c>",
+        reference,
+        style,
+        xml_escape(value)
+    )
+}
+
+const XLSX_CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/></Types>"#;
+
+const XLSX_PACKAGE_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#;
+
+/// Workbook with the hidden filter range Excel expects next to the sheet's `autoFilter`;
+/// `{last_row}` is the last row of the filter.
+const XLSX_WORKBOOK: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Notes" sheetId="1" r:id="rId1"/></sheets><definedNames><definedName name="_xlnm._FilterDatabase" localSheetId="0" hidden="1">Notes!$A$1:$G${last_row}</definedName></definedNames></workbook>"#;
+
+const XLSX_WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+/// Cell styles: 0 default, 1 bold header, 2 date-time, 3 wrapped text.
+const XLSX_STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><numFmts count="1"><numFmt numFmtId="164" formatCode="yyyy\-mm\-dd\ hh:mm:ss"/></numFmts><fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders><cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs><cellXfs count="4"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"><alignment vertical="top"/></xf><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/><xf numFmtId="164" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"><alignment vertical="top"/></xf><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0" applyAlignment="1"><alignment vertical="top" wrapText="1"/></xf></cellXfs><cellStyles count="1"><cellStyle name="Normal" xfId="0" builtinId="0"/></cellStyles></styleSheet>"#;
+
+/// The notes table as an Excel workbook with a frozen, filterable header row. Timestamps are
+/// real date-time cells (wall-clock time in the report's time zone) so the sheet can be
+/// sorted by time.
+pub(crate) fn render_notes_xlsx(headers: &[String], rows: &[NoteRow]) -> Result<Vec<u8>, String> {
+    let mut sheet = String::from(concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        "\n",
+        r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+        r#"<sheetViews><sheetView workbookViewId="0"><pane ySplit="1" topLeftCell="A2" activePane="bottomLeft" state="frozen"/></sheetView></sheetViews>"#,
+        r#"<cols><col min="1" max="1" width="7" customWidth="1"/><col min="2" max="2" width="20" customWidth="1"/>"#,
+        r#"<col min="3" max="3" width="13" customWidth="1"/><col min="4" max="4" width="70" customWidth="1"/>"#,
+        r#"<col min="5" max="5" width="40" customWidth="1"/><col min="6" max="7" width="24" customWidth="1"/></cols>"#,
+        "<sheetData>"
+    ));
+
+    sheet.push_str("<row r=\"1\">");
+    for (i, header) in headers.iter().enumerate() {
+        sheet.push_str(&string_cell(&format!("{}1", column(i)), header, 1));
+    }
+    sheet.push_str("</row>");
+
+    for (i, row) in rows.iter().enumerate() {
+        let r = i + 2;
+        sheet.push_str(&format!(
+            "<row r=\"{}\"><c r=\"A{}\"><v>{}</v></c>",
+            r, r, row.index
+        ));
+        if let Some(timestamp) = row.timestamp {
+            sheet.push_str(&format!(
+                "<c r=\"B{}\" s=\"2\"><v>{}</v></c>",
+                r,
+                serial_date(timestamp.naive_local())
+            ));
+        }
+        sheet.push_str(&string_cell(&format!("C{}", r), &row.note_type, 0));
+        sheet.push_str(&string_cell(&format!("D{}", r), &row.text, 3));
+        sheet.push_str(&string_cell(&format!("E{}", r), &row.path, 0));
+        sheet.push_str(&string_cell(&format!("F{}", r), &row.tester, 0));
+        sheet.push_str(&string_cell(&format!("G{}", r), &row.charter, 0));
+        sheet.push_str("</row>");
+    }
+
+    // The header and every note row, in both the sheet and the workbook's filter range.
+    let last_row = (rows.len() + 1).to_string();
+    sheet.push_str(&format!(
+        "</sheetData><autoFilter ref=\"A1:G{}\"/></worksheet>",
+        last_row
+    ));
+    let workbook = XLSX_WORKBOOK.replace("{last_row}", &last_row);
+
+    let mut package = Package::new();
+    package.add("[Content_Types].xml", XLSX_CONTENT_TYPES.as_bytes())?;
+    package.add("_rels/.rels", XLSX_PACKAGE_RELS.as_bytes())?;
+    package.add("xl/workbook.xml", workbook.as_bytes())?;
+    package.add("xl/_rels/workbook.xml.rels", XLSX_WORKBOOK_RELS.as_bytes())?;
+    package.add("xl/styles.xml", XLSX_STYLES.as_bytes())?;
+    package.add("xl/worksheets/sheet1.xml", sheet.as_bytes())?;
+    package.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::template::sample_data;
+    use std::io::Read;
+
+    fn rows() -> Vec<NoteRow> {
+        let mut rows = note_rows(&sample_data());
+        rows[6].text = "HTTP 504 \"Gateway Timeout\"\nretry, then fail".to_string();
+        rows[6].timestamp = None;
+        rows[0].text = "=HYPERLINK(\"x\")".to_string();
+        rows[0].timestamp = DateTime::parse_from_rfc3339("2024-03-01T12:00:00+01:00").ok();
+        rows
+    }
+
+    #[test]
+    fn rows_carry_paths_and_session_details() {
+        let rows = note_rows(&sample_data());
+
+        assert_eq!(rows.len(), 7);
+        assert_eq!(rows[1].path, "assets/screenshots/refund.png");
+        assert_eq!(rows[3].path, "");
+        assert_eq!(rows[4].path, "assets/attachments/gateway.log");
+        assert_eq!(rows[2].note_type, "bug");
+        assert_eq!(
+            rows[2].timestamp.map(|t| t.to_rfc3339()),
+            Some("1970-01-01T00:02:00+00:00".to_string())
+        );
+        assert_eq!(rows[2].tester, "Ada");
+        assert_eq!(rows[2].charter, "Explore refunds");
+    }
+
+    #[test]
+    fn csv_quotes_multi_line_fields_and_defuses_formulas() {
+        let csv = render_notes_csv(&note_headers(&sample_data()), &rows());
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+
+        assert_eq!(
+            lines[0],
+            "\u{feff}Index,Timestamp,Type,Text,Path,Tester,Charter"
+        );
+        assert_eq!(
+            lines[1],
+            "1,2024-03-01T12:00:00+01:00,test,\"'=HYPERLINK(\"\"x\"\")\",,Ada,Explore refunds"
+        );
+        assert!(csv.contains(
+            "\r\n7,,snippet,\"HTTP 504 \"\"Gateway Timeout\"\"\nretry, then fail\",,Ada,Explore refunds\r\n"
+        ));
+    }
+
+    #[test]
+    fn xlsx_has_typed_cells_and_a_filterable_header() {
+        let bytes = render_notes_xlsx(&note_headers(&sample_data()), &rows()).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut sheet = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+
+        assert!(sheet.contains(r#"<c r="A2"><v>1</v></c><c r="B2" s="2"><v>45352.5</v></c>"#));
+        assert!(sheet.contains("HTTP 504 &quot;Gateway Timeout&quot;\nretry, then fail"));
+        // Formulas are only run from formula cells, so text is kept as typed.
+        assert!(sheet.contains(">=HYPERLINK(&quot;x&quot;)<"));
+        assert!(sheet.contains(r#"<autoFilter ref="A1:G8"/>"#));
+        let mut workbook = String::new();
+        archive
+            .by_name("xl/workbook.xml")
+            .unwrap()
+            .read_to_string(&mut workbook)
+            .unwrap();
+        assert!(workbook.contains(">Notes!$A$1:$G$8</definedName>"));
+        for name in ["[Content_Types].xml", "xl/workbook.xml", "xl/styles.xml"] {
+            assert!(archive.by_name(name).is_ok(), "missing {}", name);
+        }
+    }
+}
@@ -0,0 +1,1009 @@
+use super::assets::AttachmentAsset;
+use super::bugs::BugDraft;
+use super::locale::Catalog;
+use super::markdown::{self, ReferencedTicket};
+use crate::models::{CharterRef, ReportTemplates, TagEntry, TaskBreakdown};
+use handlebars::{handlebars_helper, Handlebars};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Name of the built-in template, which reproduces the classic report layout.
+pub(crate) const DEFAULT_TEMPLATE: &str = "default";
+const DEFAULT_SOURCE: &str = include_str!("../../../assets/templates/report.md.hbs");
+/// Built-in flavour for Obsidian and other knowledge bases: YAML front matter, plain
+/// `![](...)` images, wiki-links for attachments and `^note-N` block ids.
+pub(crate) const OBSIDIAN_TEMPLATE: &str = "obsidian";
+const OBSIDIAN_SOURCE: &str = include_str!("../../../assets/templates/obsidian.md.hbs");
+const BUILT_IN: [(&str, &str); 2] = [
+    (DEFAULT_TEMPLATE, DEFAULT_SOURCE),
+    (OBSIDIAN_TEMPLATE, OBSIDIAN_SOURCE),
+];
+
+/// Report templates are `<name>.md.hbs` files in this folder of the app config directory.
+const TEMPLATES_DIR: &str = "templates";
+const TEMPLATE_SUFFIX: &str = ".md.hbs";
+/// Partials shared by all templates (`{{> name}}`), as `<name>.hbs` files.
+const PARTIALS_DIR: &str = "partials";
+/// Files (logos, stylesheets, ...) copied to `assets/template/` of every export using a
+/// custom template.
+const TEMPLATE_ASSETS_DIR: &str = "assets";
+/// Copies of the built-in templates are saved as `<name>.md.hbs.example`.
+const EXAMPLE_SUFFIX: &str = ".md.hbs.example";
+
+// `{{json value}}` writes a JSON literal, which is also a safely quoted YAML scalar.
+handlebars_helper!(json: |value: Json| serde_json::to_string(value).unwrap_or_default());
+// `{{encodeUri path}}` percent-encodes a relative path for use as a markdown link target.
+handlebars_helper!(encode_uri: |path: str| url_path(path));
+
+/// Percent-encodes everything but unreserved characters and `/` in a relative path.
+pub(crate) fn url_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// Everything a report template can use. Serialized with camelCase field names.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReportData {
+    pub(crate) app: AppData,
+    /// Language of the report, e.g. `de`.
+    pub(crate) locale: String,
+    /// Headings and labels in that language, e.g. `labels.notes`.
+    pub(crate) labels: BTreeMap<String, String>,
+    pub(crate) session: SessionData,
+    pub(crate) environment: Vec<LabelValue>,
+    /// Number of notes per (lowercased) note type, e.g. `{"bug": 2, "test": 5}`.
+    pub(crate) counts: BTreeMap<String, usize>,
+    /// Counts of the icon-backed note types present in the session.
+    pub(crate) summary: Vec<SummaryCount>,
+    pub(crate) task_breakdown: Option<TaskBreakdown>,
+    pub(crate) tickets: Vec<TicketData>,
+    pub(crate) tags: Vec<TagData>,
+    pub(crate) mentions: Vec<TagData>,
+    /// Notes oldest first, or newest first for `order: "reverse"`.
+    pub(crate) notes: Vec<NoteData>,
+    /// Per-type sections of the grouped layout; empty unless `order: "grouped"` asked for it.
+    pub(crate) groups: Vec<GroupData>,
+    pub(crate) bug_drafts: Vec<BugDraftData>,
+    pub(crate) assets: AssetPaths,
+    pub(crate) sections: Sections,
+}
+
+impl ReportData {
+    /// Label `name` in the report's language, e.g. `label("libraryCharter")`.
+    pub(crate) fn label<'a>(&'a self, name: &'a str) -> &'a str {
+        self.labels.get(name).map_or(name, String::as_str)
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AppData {
+    pub(crate) version: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionData {
+    pub(crate) tester: Option<String>,
+    pub(crate) charter: String,
+    pub(crate) library_charter: Option<CharterRef>,
+    /// Epoch milliseconds.
+    pub(crate) started_at: i64,
+    /// e.g. `1 March 2025 14:05 UTC+01:00 (Europe/Berlin)`.
+    pub(crate) started: String,
+    /// RFC 3339 with the UTC offset, e.g. `2025-03-01T14:05:00+01:00`.
+    pub(crate) started_iso: String,
+    pub(crate) date: String,
+    pub(crate) time: String,
+    /// IANA name of the report's time zone (e.g. `Europe/Berlin`, `UTC`), or `UTC+05:30`
+    /// for a fixed offset.
+    pub(crate) timezone: String,
+    /// UTC offset at the start of the session, e.g. `+01:00`.
+    pub(crate) utc_offset: String,
+    pub(crate) planned_minutes: Option<i64>,
+    /// Set when the backend session clock ran.
+    pub(crate) actual_minutes: Option<i64>,
+    /// `Duration` (and `Paused`) metadata.
+    pub(crate) timing: Vec<LabelValue>,
+    /// Ready-made `- **Duration**` (and `- **Paused**`) metadata lines.
+    pub(crate) duration_lines: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LabelValue {
+    pub(crate) label: String,
+    pub(crate) value: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SummaryCount {
+    #[serde(rename = "type")]
+    pub(crate) note_type: String,
+    pub(crate) count: usize,
+    /// e.g. `2 Bugs`.
+    pub(crate) label: String,
+    /// Name of the note type, e.g. `Bug`.
+    pub(crate) name: String,
+    pub(crate) icon: String,
+}
+
+/// A link to a note's anchor in the report.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NoteRef {
+    pub(crate) number: usize,
+    pub(crate) anchor: String,
+}
+
+impl NoteRef {
+    pub(crate) fn new(index: usize) -> Self {
+        NoteRef {
+            number: index + 1,
+            anchor: super::note_anchor(index),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TicketData {
+    pub(crate) key: String,
+    pub(crate) url: String,
+    pub(crate) notes: Vec<NoteRef>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TagData {
+    pub(crate) name: String,
+    pub(crate) count: usize,
+    pub(crate) notes: Vec<NoteRef>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NoteData {
+    /// 1-based position in the session.
+    pub(crate) number: usize,
+    /// Id of the note's anchor, e.g. `note-3`.
+    pub(crate) anchor: String,
+    /// Whether something links to the note: the Referenced Tickets list, the Tag Index, a
+    /// grouped-layout section, or a tracker issue (bugs and questions). The default template
+    /// only places `<a id="...">` anchors in front of these notes.
+    pub(crate) linked: bool,
+    /// Lowercased note type (`bug`, `test`, `screenshot`, ...).
+    #[serde(rename = "type")]
+    pub(crate) note_type: String,
+    /// When the note was taken (epoch ms), if known.
+    pub(crate) timestamp: Option<i64>,
+    /// The same time as RFC 3339 in the report's time zone, e.g. `2025-03-01T14:07:12+01:00`.
+    pub(crate) timestamp_iso: Option<String>,
+    /// `text`, `image`, `attachment` or `snippet`.
+    pub(crate) kind: &'static str,
+    /// Trimmed note text; for text notes, ticket references are already links.
+    pub(crate) text: String,
+    pub(crate) raw_text: String,
+    /// Icon path for bug, warning, observation, question and idea notes.
+    pub(crate) icon: Option<String>,
+    /// Display name of those note types, e.g. `Bug`.
+    pub(crate) type_label: Option<String>,
+    pub(crate) image: Option<ImageData>,
+    pub(crate) attachment: Option<AttachmentData>,
+    /// Why a screenshot or attachment could not be copied (safe inside an HTML comment).
+    pub(crate) copy_error: Option<String>,
+    pub(crate) bug_draft: Option<BugDraftData>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) mentions: Vec<String>,
+}
+
+/// The notes of one summary type (all bugs, all questions, ...) in the grouped layout.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GroupData {
+    #[serde(rename = "type")]
+    pub(crate) note_type: String,
+    /// Section heading, e.g. `Bugs`.
+    pub(crate) title: String,
+    /// Id of the section's `<a id="...">` anchor, e.g. `bugs`.
+    pub(crate) anchor: String,
+    /// e.g. `2 Bugs`.
+    pub(crate) label: String,
+    pub(crate) notes: Vec<NoteData>,
+}
+
+impl GroupData {
+    /// Picks the group's notes out of `notes`, which may leave some out.
+    pub(crate) fn new(group: &markdown::NoteGroup, notes: &[NoteData], catalog: &Catalog) -> Self {
+        let (title, anchor) = markdown::group_heading(group.note_type, catalog).unwrap_or_default();
+        GroupData {
+            note_type: group.note_type.to_string(),
+            title,
+            anchor,
+            label: catalog.count(&format!("count-{}", group.note_type), group.notes.len()),
+            notes: group
+                .notes
+                .iter()
+                .filter_map(|&i| notes.iter().find(|n| n.number == i + 1).cloned())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ImageData {
+    /// Relative path of the copied file; `None` when copying failed.
+    pub(crate) path: Option<String>,
+    /// `Screenshot` or `Recording`.
+    pub(crate) alt: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AttachmentData {
+    pub(crate) file_name: String,
+    pub(crate) path: String,
+    /// Human-readable size, e.g. `12.3 KB`.
+    pub(crate) size: String,
+    pub(crate) size_bytes: u64,
+    pub(crate) sha256: String,
+    /// Ready-made link line with size and hash.
+    pub(crate) markdown: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BugDraftData {
+    pub(crate) number: usize,
+    pub(crate) title: String,
+    /// File name inside `bugs/`.
+    pub(crate) file_name: String,
+    pub(crate) note_number: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AssetPaths {
+    pub(crate) icons: String,
+    pub(crate) screenshots: String,
+    pub(crate) attachments: String,
+    /// Where the template's own assets were copied, if it ships any.
+    pub(crate) template: Option<String>,
+}
+
+/// The built-in sections as ready-made markdown (without trailing blank lines); `None` when
+/// the section would be empty.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Sections {
+    pub(crate) environment: Option<String>,
+    pub(crate) summary: Option<String>,
+    pub(crate) task_breakdown: Option<String>,
+    pub(crate) referenced_tickets: Option<String>,
+    pub(crate) tag_index: Option<String>,
+    /// The per-type sections of the grouped layout.
+    pub(crate) groups: Option<String>,
+}
+
+impl Sections {
+    pub(crate) fn section(md: Option<String>) -> Option<String> {
+        md.map(|md| md.trim_end().to_string())
+    }
+}
+
+impl From<&ReferencedTicket> for TicketData {
+    fn from(ticket: &ReferencedTicket) -> Self {
+        TicketData {
+            key: ticket.key.clone(),
+            url: ticket.url.clone(),
+            notes: ticket.notes.iter().copied().map(NoteRef::new).collect(),
+        }
+    }
+}
+
+impl From<&TagEntry> for TagData {
+    fn from(entry: &TagEntry) -> Self {
+        TagData {
+            name: entry.name.clone(),
+            count: entry.notes.len(),
+            notes: entry.notes.iter().copied().map(NoteRef::new).collect(),
+        }
+    }
+}
+
+impl From<&BugDraft> for BugDraftData {
+    fn from(draft: &BugDraft) -> Self {
+        BugDraftData {
+            number: draft.number,
+            title: draft.title.clone(),
+            file_name: draft.file_name.clone(),
+            note_number: draft.note_index + 1,
+        }
+    }
+}
+
+impl From<&AttachmentAsset> for AttachmentData {
+    fn from(asset: &AttachmentAsset) -> Self {
+        AttachmentData {
+            file_name: asset.file_name.clone(),
+            path: asset.rel_path.clone(),
+            size: markdown::format_size(asset.size_bytes),
+            size_bytes: asset.size_bytes,
+            sha256: asset.sha256.clone(),
+            markdown: markdown::attachment_line(asset).trim_end().to_string(),
+        }
+    }
+}
+
+/// A report template ready to render, with the partials from the templates folder.
+pub(crate) struct ReportTemplate {
+    name: String,
+    registry: Handlebars<'static>,
+    assets_dir: Option<PathBuf>,
+}
+
+impl ReportTemplate {
+    /// Loads template `name` from `dir`; `None` or empty selects the built-in `default`.
+    pub(crate) fn load(dir: &Path, name: Option<&str>) -> Result<Self, String> {
+        let name = name
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .unwrap_or(DEFAULT_TEMPLATE);
+
+        let mut registry = Handlebars::new();
+        registry.register_escape_fn(handlebars::no_escape);
+        // Typos such as `{{sesion.tester}}` fail loudly instead of rendering nothing.
+        registry.set_strict_mode(true);
+        registry.register_helper("json", Box::new(json));
+        registry.register_helper("encodeUri", Box::new(encode_uri));
+        for (built_in, source) in BUILT_IN {
+            registry
+                .register_template_string(built_in, source)
+                .map_err(|e| {
+                    format!(
+                        "The built-in report template \"{}\" is invalid: {}",
+                        built_in, e
+                    )
+                })?;
+        }
+
+        if BUILT_IN.iter().any(|(built_in, _)| *built_in == name) {
+            return Ok(ReportTemplate {
+                name: name.to_string(),
+                registry,
+                assets_dir: None,
+            });
+        }
+
+        if name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(format!("\"{}\" is not a valid report template name", name));
+        }
+
+        let path = dir.join(format!("{}{}", name, TEMPLATE_SUFFIX));
+        if !path.is_file() {
+            return Err(format!(
+                "Report template \"{}\" was not found; expected {}",
+                name,
+                path.display()
+            ));
+        }
+
+        let partials = dir.join(PARTIALS_DIR);
+        if partials.is_dir() {
+            for entry in std::fs::read_dir(&partials).map_err(|e| e.to_string())? {
+                let partial_path = entry.map_err(|e| e.to_string())?.path();
+                let Some(partial) = partial_path
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .and_then(|s| s.strip_suffix(".hbs"))
+                else {
+                    continue;
+                };
+
+                let source = std::fs::read_to_string(&partial_path).map_err(|e| e.to_string())?;
+                registry.register_partial(partial, source).map_err(|e| {
+                    format!("Partial {} is invalid:\n{}", partial_path.display(), e)
+                })?;
+            }
+        }
+
+        let source = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        registry
+            .register_template_string(name, source)
+            .map_err(|e| format!("Report template {} is invalid:\n{}", path.display(), e))?;
+
+        Ok(ReportTemplate {
+            name: name.to_string(),
+            registry,
+            assets_dir: Some(dir.join(TEMPLATE_ASSETS_DIR)).filter(|d| d.is_dir()),
+        })
+    }
+
+    /// Renders the template against [`sample_data`] and [`minimal_sample_data`], so that a
+    /// template failing on a missing tester or an empty note list is caught up front.
+    pub(crate) fn check(&self) -> Result<(), String> {
+        self.render(&sample_data())?;
+        self.render(&minimal_sample_data())
+            .map(|_| ())
+            .map_err(|e| format!("{} (with every optional field empty)", e))
+    }
+
+    pub(crate) fn render(&self, data: &ReportData) -> Result<String, String> {
+        self.registry.render(&self.name, data).map_err(|e| {
+            format!(
+                "Report template \"{}\" could not be rendered: {}",
+                self.name, e
+            )
+        })
+    }
+
+    /// Copies the template's assets into `assets/template/` of the export folder and returns
+    /// that relative path, or `None` when the template ships no assets.
+    pub(crate) fn copy_assets(&self, export_dir: &Path) -> Result<Option<String>, String> {
+        let Some(source) = self.assets_dir.as_ref() else {
+            return Ok(None);
+        };
+
+        const REL_DIR: &str = "assets/template";
+        copy_dir(source, &export_dir.join(REL_DIR))?;
+        Ok(Some(REL_DIR.to_string()))
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(to).map_err(|e| e.to_string())?;
+
+    for entry in std::fs::read_dir(from).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        if path.is_dir() {
+            copy_dir(&path, &to.join(name))?;
+        } else {
+            std::fs::copy(&path, to.join(name)).map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn templates_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    use tauri::Manager;
+
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(TEMPLATES_DIR))
+}
+
+/// Names of the custom templates in `dir`, sorted; files named like a built-in are ignored.
+fn template_names(dir: &Path) -> Result<Vec<String>, String> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut names = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if let Some(name) = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.strip_suffix(TEMPLATE_SUFFIX))
+            .filter(|n| BUILT_IN.iter().all(|(b, _)| b != n) && path.is_file())
+        {
+            names.push(name.to_string());
+        }
+    }
+    names.sort();
+
+    Ok(names)
+}
+
+/// Data covering every note kind and optional field, used to validate templates.
+pub(crate) fn sample_data() -> ReportData {
+    let catalog = Catalog::english();
+    let note = |index: usize, note_type: &str, kind: &'static str, text: &str| NoteData {
+        number: index + 1,
+        anchor: super::note_anchor(index),
+        // The tag index links note 1; the ticket list and the bugs section link note 3.
+        linked: index == 0 || index == 2,
+        note_type: note_type.to_string(),
+        timestamp: Some(60_000 * index as i64),
+        timestamp_iso: Some(format!("1970-01-01T00:{:02}:00+00:00", index)),
+        kind,
+        text: text.to_string(),
+        raw_text: text.to_string(),
+        icon: None,
+        type_label: None,
+        image: None,
+        attachment: None,
+        copy_error: None,
+        bug_draft: None,
+        tags: Vec::new(),
+        mentions: Vec::new(),
+    };
+
+    let draft = BugDraftData {
+        number: 1,
+        title: "Refund times out".to_string(),
+        file_name: "BUG-01-refund-times-out.md".to_string(),
+        note_number: 3,
+    };
+
+    let mut notes = vec![
+        note(0, "test", "text", "Refund a paid order #refunds"),
+        note(1, "screenshot", "image", "/tmp/refund.png"),
+        note(
+            2,
+            "bug",
+            "text",
+            "Refund times out, see [PAY-7](https://jira.example.com/browse/PAY-7)",
+        ),
+        note(3, "screenshot", "image", "/tmp/missing.png"),
+        note(4, "attachment", "attachment", "/tmp/gateway.log"),
+        note(5, "attachment", "attachment", "/tmp/missing.har"),
+        note(6, "snippet", "snippet", "HTTP 504 Gateway Timeout"),
+    ];
+    notes[0].tags = vec!["refunds".to_string()];
+    notes[1].image = Some(ImageData {
+        path: Some("assets/screenshots/refund.png".to_string()),
+        alt: "Screenshot".to_string(),
+    });
+    notes[2].icon = Some("assets/icons/bug.png".to_string());
+    notes[2].type_label = Some("Bug".to_string());
+    notes[2].bug_draft = Some(draft.clone());
+    notes[3].image = Some(ImageData {
+        path: None,
+        alt: "Screenshot".to_string(),
+    });
+    notes[3].copy_error = Some("Screenshot file does not exist".to_string());
+    notes[4].attachment = Some(AttachmentData {
+        file_name: "gateway.log".to_string(),
+        path: "assets/attachments/gateway.log".to_string(),
+        size: "2.0 KB".to_string(),
+        size_bytes: 2048,
+        sha256: "0".repeat(64),
+        markdown: "📎 [gateway.log](assets/attachments/gateway.log)".to_string(),
+    });
+    notes[5].copy_error = Some("Attachment file does not exist".to_string());
+
+    ReportData {
+        app: AppData {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        },
+        locale: catalog.code().to_string(),
+        labels: catalog.labels(),
+        session: SessionData {
+            tester: Some("Ada".to_string()),
+            charter: "Explore refunds".to_string(),
+            library_charter: Some(CharterRef {
+                id: "refunds".to_string(),
+                title: "Refunds".to_string(),
+            }),
+            started_at: 0,
+            started: "1 January 1970 00:00 UTC".to_string(),
+            started_iso: "1970-01-01T00:00:00+00:00".to_string(),
+            date: "1 January 1970".to_string(),
+            time: "00:00".to_string(),
+            timezone: "UTC".to_string(),
+            utc_offset: "+00:00".to_string(),
+            planned_minutes: Some(60),
+            actual_minutes: Some(55),
+            timing: vec![LabelValue {
+                label: "Duration".to_string(),
+                value: "60 minutes planned, 55 minutes actual".to_string(),
+            }],
+            duration_lines: vec![
+                "- **Duration**: 60 minutes planned, 55 minutes actual".to_string()
+            ],
+        },
+        environment: vec![LabelValue {
+            label: "OS".to_string(),
+            value: "macOS 15".to_string(),
+        }],
+        counts: [
+            ("attachment", 2),
+            ("bug", 1),
+            ("screenshot", 2),
+            ("snippet", 1),
+            ("test", 1),
+        ]
+        .into_iter()
+        .map(|(note_type, count)| (note_type.to_string(), count))
+        .collect(),
+        summary: vec![SummaryCount {
+            note_type: "bug".to_string(),
+            count: 1,
+            label: "1 Bug".to_string(),
+            name: "Bug".to_string(),
+            icon: "assets/icons/bug.png".to_string(),
+        }],
+        task_breakdown: Some(TaskBreakdown::default()),
+        tickets: vec![TicketData {
+            key: "PAY-7".to_string(),
+            url: "https://jira.example.com/browse/PAY-7".to_string(),
+            notes: vec![NoteRef::new(2)],
+        }],
+        tags: vec![TagData {
+            name: "refunds".to_string(),
+            count: 1,
+            notes: vec![NoteRef::new(0)],
+        }],
+        mentions: Vec::new(),
+        groups: vec![GroupData::new(
+            &markdown::NoteGroup {
+                note_type: "bug",
+                notes: vec![2],
+            },
+            &notes,
+            catalog,
+        )],
+        notes,
+        bug_drafts: vec![draft],
+        assets: AssetPaths {
+            icons: "assets/icons".to_string(),
+            screenshots: "assets/screenshots".to_string(),
+            attachments: "assets/attachments".to_string(),
+            template: Some("assets/template".to_string()),
+        },
+        sections: Sections {
+            environment: Some("## Environment\n\n- **OS**: macOS 15".to_string()),
+            summary: Some("## Summary\n\n1 Bug".to_string()),
+            task_breakdown: Some("## Task Breakdown".to_string()),
+            referenced_tickets: Some("## Referenced Tickets".to_string()),
+            tag_index: Some("## Tag Index".to_string()),
+            groups: Some("<a id=\"bugs\"></a>\n## Bugs".to_string()),
+        },
+    }
+}
+
+/// Data with every optional field unset and every list empty, apart from one plain text note.
+pub(crate) fn minimal_sample_data() -> ReportData {
+    let mut data = sample_data();
+    let session = &mut data.session;
+    session.tester = None;
+    session.library_charter = None;
+    session.planned_minutes = None;
+    session.actual_minutes = None;
+    session.timing.clear();
+    session.duration_lines.clear();
+
+    let mut note = data.notes.swap_remove(0);
+    note.timestamp = None;
+    note.timestamp_iso = None;
+    note.tags.clear();
+    data.notes = vec![note];
+
+    data.environment.clear();
+    data.counts.clear();
+    data.summary.clear();
+    data.task_breakdown = None;
+    data.tickets.clear();
+    data.tags.clear();
+    data.mentions.clear();
+    data.groups.clear();
+    data.bug_drafts.clear();
+    data.assets.template = None;
+    data.sections = Sections::default();
+    data
+}
+
+/// Lists the report templates: the built-in `default` and `obsidian` plus any in the
+/// templates folder.
+///
+/// Creates the folder on first use, with copies of the built-in templates as a starting point.
+#[tauri::command]
+pub(crate) fn list_report_templates(app: tauri::AppHandle) -> Result<ReportTemplates, String> {
+    let dir = templates_dir(&app)?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    for (built_in, source) in BUILT_IN {
+        let example = dir.join(format!("{}{}", built_in, EXAMPLE_SUFFIX));
+        if !example.exists() {
+            std::fs::write(&example, source).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let mut names: Vec<String> = BUILT_IN.iter().map(|(n, _)| n.to_string()).collect();
+    names.extend(template_names(&dir)?);
+
+    Ok(ReportTemplates {
+        dir: dir.to_string_lossy().to_string(),
+        names,
+    })
+}
+
+/// Checks that a template compiles and renders against sample data covering every field, and
+/// against sample data with none of the optional ones.
+#[tauri::command]
+pub(crate) fn validate_report_template(app: tauri::AppHandle, name: String) -> Result<(), String> {
+    ReportTemplate::load(&templates_dir(&app)?, Some(&name))?.check()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::test_support::TempDir;
+
+    fn templates_folder(name: &str, files: &[(&str, &str)]) -> TempDir {
+        let dir = TempDir::new(&format!("templates-{}", name));
+        for (file, source) in files {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn default_template_renders_the_classic_layout() {
+        let dir = templates_folder("default", &[]);
+        let md = ReportTemplate::load(&dir, None)
+            .unwrap()
+            .render(&sample_data())
+            .unwrap();
+
+        assert_eq!(
+            md,
+            "# Rapid Reporter Session\n\n\
+             - **Tester**: Ada\n\
+             - **Charter**: Explore refunds\n\
+             - **Library charter**: Refunds (`refunds`)\n\
+             - **Started**: 1 January 1970 00:00 UTC\n\
+             - **Duration**: 60 minutes planned, 55 minutes actual\n\n\
+             ## Environment\n\n- **OS**: macOS 15\n\n\
+             ## Summary\n\n1 Bug\n\n\
+             ## Task Breakdown\n\n\
+             ## Referenced Tickets\n\n\
+             ## Tag Index\n\n\
+             ## Notes\n\n\
+             <a id=\"note-1\"></a>Refund a paid order #refunds\n\n\
+             <img src=\"assets/screenshots/refund.png\" width=\"900\" alt=\"Screenshot\">\n\n\
+             <a id=\"note-3\"></a><img src=\"assets/icons/bug.png\" width=\"50\" valign=\"middle\"> \
+             Refund times out, see [PAY-7](https://jira.example.com/browse/PAY-7) \
+             ([bug report draft](bugs/BUG-01-refund-times-out.md))\n\n\
+             Screenshot (copy failed): /tmp/missing.png\n\n\
+             <!-- Screenshot file does not exist -->\n\n\
+             📎 [gateway.log](assets/attachments/gateway.log)\n\n\
+             Attachment (copy failed): /tmp/missing.har\n\n\
+             <!-- Attachment file does not exist -->\n\n\
+             ```\nHTTP 504 Gateway Timeout\n```\n\n\
+             <a id=\"bugs\"></a>\n## Bugs\n\n\
+             ---\n\
+             Generated by Rapid Reporter v"
+                .to_string()
+                + env!("CARGO_PKG_VERSION")
+                + "\n"
+        );
+
+        // Notes nothing links to keep the anchor-free classic lines.
+        let mut data = sample_data();
+        data.notes[0].linked = false;
+        let md = ReportTemplate::load(&dir, None)
+            .unwrap()
+            .render(&data)
+            .unwrap();
+        assert!(md.contains("## Notes\n\nRefund a paid order #refunds\n\n"));
+    }
+
+    #[test]
+    fn obsidian_template_writes_front_matter_and_plain_markdown() {
+        let dir = templates_folder("obsidian", &[("obsidian.md.hbs", "shadowed")]);
+        assert!(template_names(&dir).unwrap().is_empty());
+
+        let md = ReportTemplate::load(&dir, Some(OBSIDIAN_TEMPLATE))
+            .unwrap()
+            .render(&sample_data())
+            .unwrap();
+        assert!(md.starts_with(
+            "---\n\
+             tester: \"Ada\"\n\
+             charter: \"Explore refunds\"\n\
+             library_charter: \"refunds\"\n\
+             start: 1970-01-01T00:00:00+00:00\n\
+             timezone: \"UTC\"\n\
+             duration: 55\n\
+             planned_duration: 60\n\
+             counts:\n  attachment: 2\n  bug: 1\n  screenshot: 2\n  snippet: 1\n  test: 1\n\
+             tags:\n  - \"refunds\"\n\
+             ---\n# Rapid Reporter Session\n"
+        ));
+        assert!(!md.contains("<img") && !md.contains("<a id"));
+        assert!(
+            md.contains("- [PAY-7](https://jira.example.com/browse/PAY-7) — [[#^note-3|note 3]]\n")
+        );
+        assert!(md.contains("## Summary\n\n- [[#Bugs|1 Bug]]\n"));
+        assert!(md.contains(
+            "\n## Bugs\n\n- [[#^note-3|Note 3]]: Refund times out, see \
+             [PAY-7](https://jira.example.com/browse/PAY-7)\n"
+        ));
+        assert!(md.contains("\n![Screenshot](assets/screenshots/refund.png)\n"));
+        assert!(md.contains("\n📎 [[assets/attachments/gateway.log|gateway.log]] (2.0 KB, "));
+        assert!(md.contains(
+            "\n**Bug**: Refund times out, see [PAY-7](https://jira.example.com/browse/PAY-7) \
+             ([[bugs/BUG-01-refund-times-out.md|bug report draft]]) ^note-3\n"
+        ));
+        assert_eq!(
+            url_path("assets/screenshots/a b(1).png"),
+            "assets/screenshots/a%20b%281%29.png"
+        );
+
+        // Issue links filed later go in front of the block id.
+        let export_dir = dir.join("RapidReporter-obsidian");
+        std::fs::create_dir_all(&export_dir).unwrap();
+        std::fs::write(super::super::report_path(&export_dir), &md).unwrap();
+        let issue = crate::models::IssueLink {
+            tracker: "github".to_string(),
+            note_index: 0,
+            key: "#12".to_string(),
+            url: "https://github.com/acme/shop/issues/12".to_string(),
+        };
+        super::super::link_note_in_report(&export_dir, 0, &issue).unwrap();
+        let md = std::fs::read_to_string(super::super::report_path(&export_dir)).unwrap();
+        assert!(md.contains(
+            "\nRefund a paid order #refunds \
+             (GitHub [#12](https://github.com/acme/shop/issues/12)) ^note-1\n"
+        ));
+
+        // Bug issues join the draft link, and the draft lists them too.
+        std::fs::create_dir_all(export_dir.join("bugs")).unwrap();
+        let draft_path = export_dir.join("bugs/BUG-01-refund-times-out.md");
+        std::fs::write(&draft_path, "# Bug: Refund times out\n\n- **Charter**: x\n").unwrap();
+        let draft = super::super::bugs::BugDraft {
+            number: 1,
+            note_index: 2,
+            title: "Refund times out".to_string(),
+            description: String::new(),
+            steps: Vec::new(),
+            evidence: Vec::new(),
+            file_name: "BUG-01-refund-times-out.md".to_string(),
+        };
+        let issue = crate::models::IssueLink {
+            tracker: "jira".to_string(),
+            note_index: 2,
+            key: "QA-7".to_string(),
+            url: "https://jira.example.com/browse/QA-7".to_string(),
+        };
+        super::super::bugs::link_issue(&export_dir, &draft, &issue).unwrap();
+        let md = std::fs::read_to_string(super::super::report_path(&export_dir)).unwrap();
+        assert!(md.contains(
+            "([[bugs/BUG-01-refund-times-out.md|bug report draft]], \
+             Jira [QA-7](https://jira.example.com/browse/QA-7)) ^note-3\n"
+        ));
+        assert_eq!(
+            std::fs::read_to_string(&draft_path).unwrap(),
+            "# Bug: Refund times out\n\n- **Charter**: x\n\
+             - **Jira**: [QA-7](https://jira.example.com/browse/QA-7)\n"
+        );
+    }
+
+    #[test]
+    fn built_in_templates_use_the_report_language() {
+        let dir = templates_folder("german", &[]);
+        let german = Catalog::load(Some("de")).unwrap();
+        let mut data = sample_data();
+        data.locale = german.code().to_string();
+        data.labels = german.labels();
+
+        let md = ReportTemplate::load(&dir, None)
+            .unwrap()
+            .render(&data)
+            .unwrap();
+        assert!(md.starts_with(
+            "# Rapid-Reporter-Sitzung\n\n- **Tester**: Ada\n- **Charter**: Explore refunds\n\
+             - **Charter aus der Bibliothek**: Refunds (`refunds`)\n- **Beginn**: "
+        ));
+        assert!(md.contains("\n## Notizen\n\n"));
+        assert!(md.contains("([Entwurf des Fehlerberichts](bugs/BUG-01-refund-times-out.md))"));
+        assert!(md.contains("\nAnhang (Kopieren fehlgeschlagen): /tmp/missing.har\n"));
+        assert!(md.contains("\n---\nErstellt mit Rapid Reporter v"));
+
+        let md = ReportTemplate::load(&dir, Some(OBSIDIAN_TEMPLATE))
+            .unwrap()
+            .render(&data)
+            .unwrap();
+        assert!(md.contains("— [[#^note-3|Notiz 3]]\n"));
+        assert!(md.contains("\n## Tag-Index\n\n"));
+    }
+
+    #[test]
+    fn custom_templates_use_partials_and_list_by_name() {
+        let dir = templates_folder(
+            "custom",
+            &[
+                (
+                    "team.md.hbs",
+                    "{{> logo}}\n# {{session.charter}}\n{{#each notes}}\n- {{number}}. {{type}}\n{{/each}}",
+                ),
+                ("partials/logo.hbs", "![ACME]({{assets.template}}/logo.png)\n"),
+                ("assets/logo.png", "png"),
+                ("notes.txt", "ignored"),
+            ],
+        );
+
+        assert_eq!(template_names(&dir).unwrap(), vec!["team"]);
+
+        let template = ReportTemplate::load(&dir, Some("team")).unwrap();
+        let md = template.render(&sample_data()).unwrap();
+        assert!(md.starts_with("![ACME](assets/template/logo.png)\n# Explore refunds\n- 1. test\n"));
+
+        let export_dir = dir.join("export");
+        assert_eq!(
+            template.copy_assets(&export_dir).unwrap().as_deref(),
+            Some("assets/template")
+        );
+        assert!(export_dir.join("assets/template/logo.png").is_file());
+    }
+
+    #[test]
+    fn template_errors_name_the_template_and_position() {
+        let dir = templates_folder(
+            "errors",
+            &[
+                (
+                    "broken.md.hbs",
+                    "# Report\n{{#if session.tester}}\n{{/each}}\n",
+                ),
+                ("typo.md.hbs", "# Report\n\n{{sesion.charter}}\n"),
+            ],
+        );
+
+        let err = ReportTemplate::load(&dir, Some("broken")).err().unwrap();
+        assert!(err.contains("broken.md.hbs is invalid"), "{}", err);
+        assert!(
+            err.contains("\"if\" was opened, but \"each\" is closing"),
+            "{}",
+            err
+        );
+
+        let err = ReportTemplate::load(&dir, Some("typo"))
+            .unwrap()
+            .render(&sample_data())
+            .unwrap_err();
+        assert!(err.starts_with("Report template \"typo\" could not be rendered"));
+        assert!(err.contains("line 3"), "{}", err);
+        assert!(err.contains("sesion.charter"), "{}", err);
+
+        let err = ReportTemplate::load(&dir, Some("missing")).err().unwrap();
+        assert!(err.starts_with("Report template \"missing\" was not found"));
+        assert!(ReportTemplate::load(&dir, Some("../typo")).is_err());
+    }
+
+    #[test]
+    fn templates_are_checked_without_optional_data_too() {
+        let dir = templates_folder(
+            "optional",
+            &[(
+                "charter-title.md.hbs",
+                "# {{session.libraryCharter.title}}\n",
+            )],
+        );
+
+        let template = ReportTemplate::load(&dir, Some("charter-title")).unwrap();
+        assert!(template.render(&sample_data()).is_ok());
+        let err = template.check().unwrap_err();
+        assert!(err.contains("libraryCharter.title"), "{}", err);
+        assert!(
+            err.ends_with("(with every optional field empty)"),
+            "{}",
+            err
+        );
+
+        for built_in in [DEFAULT_TEMPLATE, OBSIDIAN_TEMPLATE] {
+            ReportTemplate::load(&dir, Some(built_in))
+                .unwrap()
+                .check()
+                .unwrap();
+        }
+    }
+}
@@ -0,0 +1,545 @@
+use super::locale::Catalog;
+use super::markdown::task_breakdown_tables;
+use super::template::{NoteData, ReportData, TagData};
+use crate::commands::links::LinkRules;
+
+/// Height of the note-type icons next to notes and in the summary table.
+const ICON_SIZE: u32 = 24;
+/// Width of embedded screenshots, matching the markdown report.
+const IMAGE_WIDTH: u32 = 900;
+
+/// File name of an export asset such as `assets/screenshots/refund.png`, which is how both
+/// Confluence and Jira refer to files attached to the page or issue.
+fn file_name(rel_path: &str) -> &str {
+    rel_path.rsplit('/').next().unwrap_or(rel_path)
+}
+
+/// Splits note text into plain runs and ticket links `(text, url)`, using the link rules.
+fn segments<'a>(text: &'a str, rules: &LinkRules) -> Vec<(&'a str, Option<String>)> {
+    let mut out = Vec::new();
+    let mut last = 0;
+    for ticket in rules.find(text) {
+        out.push((&text[last..ticket.start], None));
+        out.push((&text[ticket.start..ticket.end], Some(ticket.url)));
+        last = ticket.end;
+    }
+    out.push((&text[last..], None));
+    out
+}
+
+fn copy_failed(data: &ReportData, note: &NoteData) -> String {
+    let label = match note.kind {
+        "image" => note
+            .image
+            .as_ref()
+            .map_or(data.label("screenshot"), |i| i.alt.as_str()),
+        _ => data.label("attachment"),
+    };
+    format!("{} ({}): {}", label, data.label("copyFailed"), note.text)
+}
+
+fn xhtml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Text for a CDATA section, splitting any `]]>` it contains.
+fn cdata(text: &str) -> String {
+    format!("<![CDATA[{}]]>", text.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+fn confluence_text(text: &str, rules: &LinkRules) -> String {
+    segments(text, rules)
+        .into_iter()
+        .map(|(run, url)| match url {
+            Some(url) => format!(
+                "<a href=\"{}\">{}</a>",
+                xhtml_escape(&url),
+                xhtml_escape(run)
+            ),
+            None => xhtml_escape(run).replace('\n', "<br />"),
+        })
+        .collect()
+}
+
+fn confluence_image(rel_path: &str, size: &str) -> String {
+    format!(
+        "<ac:image {}><ri:attachment ri:filename=\"{}\" /></ac:image>",
+        size,
+        xhtml_escape(file_name(rel_path))
+    )
+}
+
+fn confluence_icon(icon: &str) -> String {
+    confluence_image(icon, &format!("ac:height=\"{}\"", ICON_SIZE))
+}
+
+/// Link to a note's anchor macro on the same page.
+fn confluence_note_link(data: &ReportData, anchor: &str, number: usize) -> String {
+    format!(
+        "<ac:link ac:anchor=\"{}\"><ac:plain-text-link-body>{}</ac:plain-text-link-body></ac:link>",
+        anchor,
+        cdata(&format!("{} {}", data.label("noteLink"), number))
+    )
+}
+
+/// Every `#tag`, then every `@mention`, with its sigil.
+fn tag_entries(data: &ReportData) -> impl Iterator<Item = (char, &TagData)> {
+    data.tags
+        .iter()
+        .map(|t| ('#', t))
+        .chain(data.mentions.iter().map(|m| ('@', m)))
+}
+
+/// Renders the report in Confluence storage format (the XHTML Confluence saves pages in).
+///
+/// Screenshots, icons and attachments are referenced as page attachments by file name, so
+/// upload the files from the export's `assets/` folders to the page.
+pub(crate) fn render_confluence(data: &ReportData, rules: &LinkRules, catalog: &Catalog) -> String {
+    let mut out = format!(
+        "<h1>{}</h1>\n<ul>\n",
+        xhtml_escape(data.label("reportTitle"))
+    );
+    let field = |label: &str, value: &str| {
+        format!(
+            "<li><strong>{}</strong>: {}</li>\n",
+            xhtml_escape(label),
+            xhtml_escape(value)
+        )
+    };
+
+    let session = &data.session;
+    if let Some(tester) = session.tester.as_deref() {
+        out.push_str(&field(data.label("tester"), tester));
+    }
+    out.push_str(&field(data.label("charter"), &session.charter));
+    if let Some(charter) = session.library_charter.as_ref() {
+        out.push_str(&format!(
+            "<li><strong>{}</strong>: {} (<code>{}</code>)</li>\n",
+            xhtml_escape(data.label("libraryCharter")),
+            xhtml_escape(&charter.title),
+            xhtml_escape(&charter.id)
+        ));
+    }
+    out.push_str(&field(data.label("started"), &session.started));
+    for entry in &session.timing {
+        out.push_str(&field(&entry.label, &entry.value));
+    }
+    out.push_str("</ul>\n");
+
+    if !data.environment.is_empty() {
+        out.push_str(&format!(
+            "<h2>{}</h2>\n<ul>\n",
+            xhtml_escape(data.label("environment"))
+        ));
+        for entry in &data.environment {
+            out.push_str(&field(&entry.label, &entry.value));
+        }
+        out.push_str("</ul>\n");
+    }
+
+    if !data.summary.is_empty() {
+        out.push_str(&format!(
+            "<h2>{}</h2>\n<table><tbody>\n<tr><th>{}</th><th>{}</th></tr>\n",
+            xhtml_escape(data.label("summary")),
+            xhtml_escape(data.label("noteType")),
+            xhtml_escape(data.label("count"))
+        ));
+        for count in &data.summary {
+            out.push_str(&format!(
+                "<tr><td>{} {}</td><td>{}</td></tr>\n",
+                confluence_icon(&count.icon),
+                xhtml_escape(&count.name),
+                count.count
+            ));
+        }
+        out.push_str("</tbody></table>\n");
+    }
+
+    let breakdown = data.task_breakdown.as_ref();
+    if let Some(tables) = breakdown.and_then(|b| task_breakdown_tables(b, catalog)) {
+        out.push_str(&format!(
+            "<h2>{}</h2>\n",
+            xhtml_escape(data.label("taskBreakdown"))
+        ));
+        for table in tables {
+            out.push_str(&format!(
+                "<table><tbody>\n<tr><th>{}</th><th>{}</th><th>{}</th></tr>\n",
+                xhtml_escape(&table.heading),
+                xhtml_escape(data.label("time")),
+                xhtml_escape(data.label("share"))
+            ));
+            for row in table.rows {
+                out.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}%</td></tr>\n",
+                    xhtml_escape(&row.label),
+                    xhtml_escape(&row.time),
+                    row.share
+                ));
+            }
+            out.push_str("</tbody></table>\n");
+        }
+    }
+
+    if !data.tickets.is_empty() {
+        out.push_str(&format!(
+            "<h2>{}</h2>\n<ul>\n",
+            xhtml_escape(data.label("referencedTickets"))
+        ));
+        for ticket in &data.tickets {
+            let notes: Vec<String> = ticket
+                .notes
+                .iter()
+                .map(|n| confluence_note_link(data, &n.anchor, n.number))
+                .collect();
+            out.push_str(&format!(
+                "<li><a href=\"{}\">{}</a> – {}</li>\n",
+                xhtml_escape(&ticket.url),
+                xhtml_escape(&ticket.key),
+                notes.join(", ")
+            ));
+        }
+        out.push_str("</ul>\n");
+    }
+
+    if !data.tags.is_empty() || !data.mentions.is_empty() {
+        out.push_str(&format!(
+            "<h2>{}</h2>\n<ul>\n",
+            xhtml_escape(data.label("tagIndex"))
+        ));
+        for (sigil, entry) in tag_entries(data) {
+            let notes: Vec<String> = entry
+                .notes
+                .iter()
+                .map(|n| confluence_note_link(data, &n.anchor, n.number))
+                .collect();
+            out.push_str(&format!(
+                "<li>{}{} ({}) – {}</li>\n",
+                sigil,
+                xhtml_escape(&entry.name),
+                entry.count,
+                notes.join(", ")
+            ));
+        }
+        out.push_str("</ul>\n");
+    }
+
+    out.push_str(&format!("<h2>{}</h2>\n", xhtml_escape(data.label("notes"))));
+    for note in &data.notes {
+        let block = match note.kind {
+            "image" => match note.image.as_ref().and_then(|i| i.path.as_deref()) {
+                Some(path) => format!(
+                    "<p>{}</p>",
+                    confluence_image(path, &format!("ac:width=\"{}\"", IMAGE_WIDTH))
+                ),
+                None => format!("<p>{}</p>", xhtml_escape(&copy_failed(data, note))),
+            },
+            "attachment" => match note.attachment.as_ref() {
+                Some(a) => format!(
+                    "<p><ac:link><ri:attachment ri:filename=\"{}\" /><ac:plain-text-link-body>{}</ac:plain-text-link-body></ac:link> ({}, SHA-256 <code>{}</code>)</p>",
+                    xhtml_escape(&a.file_name),
+                    cdata(&a.file_name),
+                    xhtml_escape(&a.size),
+                    a.sha256
+                ),
+                None => format!("<p>{}</p>", xhtml_escape(&copy_failed(data, note))),
+            },
+            "snippet" => format!(
+                "<ac:structured-macro ac:name=\"code\"><ac:plain-text-body>{}</ac:plain-text-body></ac:structured-macro>",
+                cdata(&note.raw_text)
+            ),
+            _ => format!(
+                "<p><ac:structured-macro ac:name=\"anchor\"><ac:parameter ac:name=\"\">{}</ac:parameter></ac:structured-macro>{}{}</p>",
+                note.anchor,
+                note.icon
+                    .as_deref()
+                    .map(|icon| format!("{} ", confluence_icon(icon)))
+                    .unwrap_or_default(),
+                confluence_text(&note.raw_text, rules)
+            ),
+        };
+        out.push_str(&block);
+        out.push('\n');
+    }
+
+    out.push_str(&format!(
+        "<hr />\n<p>{} v{}</p>\n",
+        xhtml_escape(data.label("generatedBy")),
+        data.app.version
+    ));
+    out
+}
+
+/// Escapes characters that Jira wiki markup would read as formatting.
+fn jira_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '*' | '_' | '+' | '^' | '~' | '{' | '}' | '[' | ']' | '|' | '!' | '#'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn jira_text(text: &str, rules: &LinkRules) -> String {
+    segments(text, rules)
+        .into_iter()
+        .map(|(run, url)| match url {
+            Some(url) => format!("[{}|{}]", jira_escape(run), url),
+            None => jira_escape(run),
+        })
+        .collect()
+}
+
+fn jira_icon(icon: &str) -> String {
+    format!("!{}|height={}!", file_name(icon), ICON_SIZE)
+}
+
+/// Renders the report as Jira wiki markup, ready to paste into a comment or description.
+///
+/// Screenshots, icons and attachments are referenced by file name (`!refund.png!`,
+/// `[^gateway.log]`), so attach the files from the export's `assets/` folders to the issue.
+pub(crate) fn render_jira_wiki(data: &ReportData, rules: &LinkRules, catalog: &Catalog) -> String {
+    let mut out = format!("h1. {}\n\n", jira_escape(data.label("reportTitle")));
+    let field =
+        |label: &str, value: &str| format!("* *{}*: {}\n", jira_escape(label), jira_escape(value));
+
+    let session = &data.session;
+    if let Some(tester) = session.tester.as_deref() {
+        out.push_str(&field(data.label("tester"), tester));
+    }
+    out.push_str(&field(data.label("charter"), &session.charter));
+    if let Some(charter) = session.library_charter.as_ref() {
+        out.push_str(&format!(
+            "* *{}*: {} ({{{{{}}}}})\n",
+            jira_escape(data.label("libraryCharter")),
+            jira_escape(&charter.title),
+            charter.id
+        ));
+    }
+    out.push_str(&field(data.label("started"), &session.started));
+    for entry in &session.timing {
+        out.push_str(&field(&entry.label, &entry.value));
+    }
+    out.push('\n');
+
+    if !data.environment.is_empty() {
+        out.push_str(&format!("h2. {}\n\n", data.label("environment")));
+        for entry in &data.environment {
+            out.push_str(&field(&entry.label, &entry.value));
+        }
+        out.push('\n');
+    }
+
+    if !data.summary.is_empty() {
+        out.push_str(&format!(
+            "h2. {}\n\n||{}||{}||\n",
+            data.label("summary"),
+            jira_escape(data.label("noteType")),
+            jira_escape(data.label("count"))
+        ));
+        for count in &data.summary {
+            out.push_str(&format!(
+                "|{} {}|{}|\n",
+                jira_icon(&count.icon),
+                jira_escape(&count.name),
+                count.count
+            ));
+        }
+        out.push('\n');
+    }
+
+    let breakdown = data.task_breakdown.as_ref();
+    if let Some(tables) = breakdown.and_then(|b| task_breakdown_tables(b, catalog)) {
+        out.push_str(&format!("h2. {}\n\n", data.label("taskBreakdown")));
+        for table in tables {
+            out.push_str(&format!(
+                "||{}||{}||{}||\n",
+                jira_escape(&table.heading),
+                jira_escape(data.label("time")),
+                jira_escape(data.label("share"))
+            ));
+            for row in table.rows {
+                out.push_str(&format!(
+                    "|{}|{}|{}%|\n",
+                    jira_escape(&row.label),
+                    jira_escape(&row.time),
+                    row.share
+                ));
+            }
+            out.push('\n');
+        }
+    }
+
+    if !data.tickets.is_empty() {
+        out.push_str(&format!("h2. {}\n\n", data.label("referencedTickets")));
+        for ticket in &data.tickets {
+            let notes: Vec<String> = ticket
+                .notes
+                .iter()
+                .map(|n| format!("[{} {}|#{}]", data.label("noteLink"), n.number, n.anchor))
+                .collect();
+            out.push_str(&format!(
+                "* [{}|{}] – {}\n",
+                jira_escape(&ticket.key),
+                ticket.url,
+                notes.join(", ")
+            ));
+        }
+        out.push('\n');
+    }
+
+    if !data.tags.is_empty() || !data.mentions.is_empty() {
+        out.push_str(&format!("h2. {}\n\n", data.label("tagIndex")));
+        for (sigil, entry) in tag_entries(data) {
+            let notes: Vec<String> = entry
+                .notes
+                .iter()
+                .map(|n| format!("[{} {}|#{}]", data.label("noteLink"), n.number, n.anchor))
+                .collect();
+            out.push_str(&format!(
+                "* {}{} ({}) – {}\n",
+                jira_escape(&sigil.to_string()),
+                jira_escape(&entry.name),
+                entry.count,
+                notes.join(", ")
+            ));
+        }
+        out.push('\n');
+    }
+
+    out.push_str(&format!("h2. {}\n\n", data.label("notes")));
+    for note in &data.notes {
+        let block = match note.kind {
+            "image" => match note.image.as_ref().and_then(|i| i.path.as_deref()) {
+                Some(path) => format!("!{}|width={}!", file_name(path), IMAGE_WIDTH),
+                None => jira_escape(&copy_failed(data, note)),
+            },
+            "attachment" => match note.attachment.as_ref() {
+                Some(a) => format!(
+                    "[^{}] ({}, SHA-256 {{{{{}}}}})",
+                    a.file_name, a.size, a.sha256
+                ),
+                None => jira_escape(&copy_failed(data, note)),
+            },
+            // `{noformat}` is used when the snippet itself contains a `{code}` tag.
+            "snippet" if note.raw_text.contains("{code") => {
+                format!("{{noformat}}\n{}\n{{noformat}}", note.raw_text)
+            }
+            "snippet" => format!("{{code}}\n{}\n{{code}}", note.raw_text),
+            _ => format!(
+                "{{anchor:{}}}{}{}",
+                note.anchor,
+                note.icon
+                    .as_deref()
+                    .map(|icon| format!("{} ", jira_icon(icon)))
+                    .unwrap_or_default(),
+                jira_text(&note.raw_text, rules)
+            ),
+        };
+        out.push_str(&block);
+        out.push_str("\n\n");
+    }
+
+    out.push_str(&format!(
+        "----\n{} v{}\n",
+        data.label("generatedBy"),
+        data.app.version
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::template::sample_data;
+    use crate::commands::export::template::{NoteRef, TagData};
+    use crate::models::{LinkRule, TaskBreakdown};
+
+    fn rules() -> LinkRules {
+        LinkRules::compile(&[LinkRule {
+            name: None,
+            pattern: r"PAY-\d+".to_string(),
+            url: "https://jira.example.com/browse/$0".to_string(),
+        }])
+        .unwrap()
+    }
+
+    fn data() -> ReportData {
+        let mut data = sample_data();
+        data.notes[2].raw_text = "Refund times out <PAY-7> & retries".to_string();
+        data.notes[6].raw_text = "if a < b {code} ]]> done".to_string();
+        data.task_breakdown = Some(TaskBreakdown {
+            setup_seconds: 300,
+            test_seconds: 900,
+            charter_seconds: 1200,
+            ..TaskBreakdown::default()
+        });
+        data.mentions = vec![TagData {
+            name: "dana".to_string(),
+            count: 2,
+            notes: vec![NoteRef::new(0), NoteRef::new(2)],
+        }];
+        data
+    }
+
+    #[test]
+    fn confluence_uses_attachments_macros_and_escapes_text() {
+        let page = render_confluence(&data(), &rules(), Catalog::english());
+
+        assert!(page.contains(
+            r#"<tr><td><ac:image ac:height="24"><ri:attachment ri:filename="bug.png" /></ac:image> Bug</td><td>1</td></tr>"#
+        ));
+        assert!(page.contains(r#"<p><ac:image ac:width="900"><ri:attachment ri:filename="refund.png" /></ac:image></p>"#));
+        assert!(page.contains(
+            r#"<ac:parameter ac:name="">note-3</ac:parameter></ac:structured-macro><ac:image ac:height="24"><ri:attachment ri:filename="bug.png" /></ac:image> Refund times out &lt;<a href="https://jira.example.com/browse/PAY-7">PAY-7</a>&gt; &amp; retries</p>"#
+        ));
+        assert!(page.contains(
+            r#"<ac:link><ri:attachment ri:filename="gateway.log" /><ac:plain-text-link-body><![CDATA[gateway.log]]></ac:plain-text-link-body></ac:link> (2.0 KB"#
+        ));
+        assert!(page.contains(
+            r#"<ac:plain-text-body><![CDATA[if a < b {code} ]]]]><![CDATA[> done]]></ac:plain-text-body>"#
+        ));
+        assert!(page.contains(
+            r#"<ac:link ac:anchor="note-3"><ac:plain-text-link-body><![CDATA[note 3]]>"#
+        ));
+        assert!(page.contains(
+            "<tr><th>Task</th><th>Time</th><th>Share</th></tr>\n<tr><td>Session setup</td><td>5 min</td><td>25%</td></tr>\n"
+        ));
+        assert!(page.contains("<tr><td>Charter</td><td>20 min</td><td>100%</td></tr>"));
+        assert!(page.contains(
+            r#"<li>@dana (2) – <ac:link ac:anchor="note-1"><ac:plain-text-link-body><![CDATA[note 1]]></ac:plain-text-link-body></ac:link>, <ac:link ac:anchor="note-3">"#
+        ));
+        assert!(page.contains("<h2>Tag Index</h2>\n<ul>\n<li>#refunds (1) – "));
+        assert!(!page.contains("<img"));
+    }
+
+    #[test]
+    fn jira_wiki_uses_image_and_code_markup() {
+        let wiki = render_jira_wiki(&data(), &rules(), Catalog::english());
+
+        assert!(wiki.contains("||Note type||Count||\n|!bug.png|height=24! Bug|1|\n"));
+        assert!(wiki.contains("\n!refund.png|width=900!\n"));
+        assert!(wiki.contains(
+            "{anchor:note-3}!bug.png|height=24! Refund times out <[PAY-7|https://jira.example.com/browse/PAY-7]> & retries\n"
+        ));
+        assert!(wiki.contains("Refund a paid order \\#refunds\n"));
+        assert!(wiki.contains("[^gateway.log] (2.0 KB, SHA-256 {{000"));
+        assert!(wiki.contains("{noformat}\nif a < b {code} ]]> done\n{noformat}"));
+        assert!(wiki.contains("* [PAY-7|https://jira.example.com/browse/PAY-7] – [note 3|#note-3]"));
+        assert!(wiki.contains(
+            "h2. Task Breakdown\n\n||Task||Time||Share||\n|Session setup|5 min|25%|\n|Test design and execution|15 min|75%|\n"
+        ));
+        assert!(wiki.contains("||Focus||Time||Share||\n|Charter|20 min|100%|\n"));
+        assert!(wiki.contains(
+            "h2. Tag Index\n\n* \\#refunds (1) – [note 1|#note-1]\n* @dana (2) – [note 1|#note-1], [note 3|#note-3]\n"
+        ));
+        assert!(!wiki.contains("<img"));
+    }
+}
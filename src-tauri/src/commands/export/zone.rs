@@ -0,0 +1,150 @@
+use chrono::{DateTime, FixedOffset, Local, Offset, Utc};
+use chrono_tz::Tz;
+
+/// Time zone the times of a report are shown in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ReportZone {
+    /// An IANA zone such as `Europe/Berlin` (or `UTC`), with its daylight saving rules.
+    Named(Tz),
+    /// A fixed UTC offset such as `+05:30`.
+    Fixed(FixedOffset),
+    /// The system zone, when its IANA name cannot be determined.
+    System,
+}
+
+impl ReportZone {
+    /// Parses the `timezone` export option: an IANA name (`Europe/Berlin`), `UTC` or a UTC
+    /// offset (`+05:30`). The system zone when unset.
+    pub(crate) fn load(zone: Option<&str>) -> Result<Self, String> {
+        let Some(zone) = zone.map(str::trim).filter(|z| !z.is_empty()) else {
+            return Ok(Self::system());
+        };
+
+        if zone.eq_ignore_ascii_case("utc") || zone.eq_ignore_ascii_case("z") {
+            return Ok(ReportZone::Named(Tz::UTC));
+        }
+        let parsed = if zone.starts_with(['+', '-']) {
+            zone.parse::<FixedOffset>().ok().map(ReportZone::Fixed)
+        } else {
+            zone.parse::<Tz>().ok().map(ReportZone::Named)
+        };
+        parsed.ok_or_else(|| {
+            format!(
+                "Unknown time zone \"{}\"; use an IANA name such as Europe/Berlin, UTC or a UTC offset such as +05:30",
+                zone
+            )
+        })
+    }
+
+    /// The zone the system clock is set to, by IANA name when the OS reports one.
+    pub(crate) fn system() -> Self {
+        iana_time_zone::get_timezone()
+            .ok()
+            .and_then(|name| name.parse::<Tz>().ok())
+            .map_or(ReportZone::System, ReportZone::Named)
+    }
+
+    /// `millis` (epoch ms) as a time in this zone, with the UTC offset in force at that moment.
+    pub(crate) fn at(&self, millis: i64) -> Option<DateTime<FixedOffset>> {
+        let utc = DateTime::<Utc>::from_timestamp_millis(millis)?;
+        Some(match self {
+            ReportZone::Named(tz) => utc.with_timezone(tz).fixed_offset(),
+            ReportZone::Fixed(offset) => utc.with_timezone(offset),
+            ReportZone::System => utc.with_timezone(&Local).fixed_offset(),
+        })
+    }
+
+    /// IANA name of the zone, or the UTC offset of `time` when it has none, e.g.
+    /// `Europe/Berlin` or `UTC+05:30`.
+    pub(crate) fn name(&self, time: &DateTime<FixedOffset>) -> String {
+        match self {
+            ReportZone::Named(tz) => tz.name().to_string(),
+            _ => utc_offset(time.offset()),
+        }
+    }
+
+    /// Zone shown after a time: the UTC offset, followed by the IANA name when there is one,
+    /// e.g. `UTC+01:00 (Europe/Berlin)`, `UTC` or `UTC-05:00`.
+    pub(crate) fn label(&self, time: &DateTime<FixedOffset>) -> String {
+        let offset = utc_offset(time.offset());
+        match self {
+            ReportZone::Named(tz) if *tz != Tz::UTC => format!("{} ({})", offset, tz.name()),
+            _ => offset,
+        }
+    }
+}
+
+/// `UTC`, `UTC+01:00` or `UTC-04:00`.
+pub(crate) fn utc_offset(offset: &FixedOffset) -> String {
+    if offset.fix().local_minus_utc() == 0 {
+        "UTC".to_string()
+    } else {
+        format!("UTC{}", offset)
+    }
+}
+
+/// ISO 8601 / RFC 3339 timestamp with its UTC offset, e.g. `2025-03-30T03:30:00+02:00`.
+pub(crate) fn iso(time: &DateTime<FixedOffset>) -> String {
+    time.to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// Epoch ms of a UTC date and time.
+    fn utc_millis(y: i32, m: u32, d: u32, h: u32, min: u32) -> i64 {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .and_then(|d| d.and_hms_opt(h, min, 0))
+            .unwrap()
+            .and_utc()
+            .timestamp_millis()
+    }
+
+    #[test]
+    fn times_follow_daylight_saving_transitions() {
+        let berlin = ReportZone::load(Some("Europe/Berlin")).unwrap();
+        // Clocks went forward from 02:00 CET to 03:00 CEST at 01:00 UTC on 30 March 2025.
+        let before = berlin.at(utc_millis(2025, 3, 30, 0, 30)).unwrap();
+        let after = berlin.at(utc_millis(2025, 3, 30, 1, 30)).unwrap();
+        assert_eq!(iso(&before), "2025-03-30T01:30:00+01:00");
+        assert_eq!(iso(&after), "2025-03-30T03:30:00+02:00");
+        assert_eq!(berlin.label(&before), "UTC+01:00 (Europe/Berlin)");
+        assert_eq!(berlin.label(&after), "UTC+02:00 (Europe/Berlin)");
+        assert_eq!(berlin.name(&after), "Europe/Berlin");
+
+        // 01:30 happened twice in New York on 2 November 2025; the offset tells them apart.
+        let new_york = ReportZone::load(Some("America/New_York")).unwrap();
+        let first = new_york.at(utc_millis(2025, 11, 2, 5, 30)).unwrap();
+        let second = new_york.at(utc_millis(2025, 11, 2, 6, 30)).unwrap();
+        assert_eq!(iso(&first), "2025-11-02T01:30:00-04:00");
+        assert_eq!(iso(&second), "2025-11-02T01:30:00-05:00");
+        assert!(first < second);
+    }
+
+    #[test]
+    fn utc_and_fixed_offsets_are_accepted() {
+        let at = utc_millis(2025, 7, 1, 12, 0);
+
+        let utc = ReportZone::load(Some("utc")).unwrap();
+        let time = utc.at(at).unwrap();
+        assert_eq!(iso(&time), "2025-07-01T12:00:00+00:00");
+        assert_eq!(
+            (utc.name(&time), utc.label(&time)),
+            ("UTC".into(), "UTC".into())
+        );
+
+        let india = ReportZone::load(Some("+05:30")).unwrap();
+        let time = india.at(at).unwrap();
+        assert_eq!(iso(&time), "2025-07-01T17:30:00+05:30");
+        assert_eq!(india.name(&time), "UTC+05:30");
+        assert_eq!(india.label(&time), "UTC+05:30");
+
+        assert_eq!(ReportZone::load(Some("  ")).unwrap(), ReportZone::system());
+        assert_eq!(
+            ReportZone::load(Some("Mars/Olympus")).unwrap_err(),
+            "Unknown time zone \"Mars/Olympus\"; use an IANA name such as Europe/Berlin, UTC or a UTC offset such as +05:30"
+        );
+    }
+}
@@ -0,0 +1,716 @@
+use crate::commands::export::bugs::{self, BugDraft};
+use crate::commands::export::locale::Catalog;
+use crate::commands::export::{
+    link_note_in_report, markdown, read_session_record, write_session_record,
+};
+use crate::commands::{http, secrets};
+use crate::models::{ForgeIssue, ForgeKind, ForgeSettings, IssueLink, Note, SessionRecord};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const SETTINGS_FILE: &str = "forge.json";
+/// Keychain account of the access token.
+const KEYCHAIN_ACCOUNT: &str = "forge-token";
+
+const GITHUB_API: &str = "https://api.github.com";
+const GITLAB_API: &str = "https://gitlab.com";
+
+/// Serialises reads and writes of the settings file.
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+impl ForgeKind {
+    fn id(self) -> &'static str {
+        match self {
+            ForgeKind::Github => "github",
+            ForgeKind::Gitlab => "gitlab",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ForgeKind::Github => "GitHub",
+            ForgeKind::Gitlab => "GitLab",
+        }
+    }
+}
+
+/// Percent-encodes a GitLab project path for use as the `:id` URL segment.
+fn encode_project_path(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Blocking client for issue creation on GitHub or GitLab.
+pub(crate) struct ForgeClient {
+    agent: ureq::Agent,
+    kind: ForgeKind,
+    api_url: String,
+    repo: String,
+    token: String,
+}
+
+impl ForgeClient {
+    pub(crate) fn new(settings: &ForgeSettings) -> Result<Self, String> {
+        let repo = settings.repo.trim().trim_matches('/').to_string();
+        if !repo.contains('/') {
+            return Err(format!(
+                "The {} repository must be given as owner/name.",
+                settings.kind.label()
+            ));
+        }
+        if settings.token.trim().is_empty() {
+            return Err(format!("A {} token is required.", settings.kind.label()));
+        }
+
+        let default_api = match settings.kind {
+            ForgeKind::Github => GITHUB_API,
+            ForgeKind::Gitlab => GITLAB_API,
+        };
+        let api_url = settings
+            .api_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+            .unwrap_or(default_api)
+            .trim_end_matches('/')
+            .to_string();
+        if !api_url.starts_with("http://") && !api_url.starts_with("https://") {
+            return Err("The API URL must start with http:// or https://.".to_string());
+        }
+
+        Ok(ForgeClient {
+            agent: http::agent(),
+            kind: settings.kind,
+            api_url,
+            repo,
+            token: settings.token.trim().to_string(),
+        })
+    }
+
+    fn request(&self, path: &str) -> ureq::Request {
+        match self.kind {
+            ForgeKind::Github => self
+                .agent
+                .post(&format!("{}/repos/{}{}", self.api_url, self.repo, path))
+                .set("Authorization", &format!("Bearer {}", self.token))
+                .set("Accept", "application/vnd.github+json")
+                .set("X-GitHub-Api-Version", "2022-11-28"),
+            ForgeKind::Gitlab => self
+                .agent
+                .post(&format!(
+                    "{}/api/v4/projects/{}{}",
+                    self.api_url,
+                    encode_project_path(&self.repo),
+                    path
+                ))
+                .set("PRIVATE-TOKEN", &self.token),
+        }
+    }
+
+    /// Creates an issue and returns its reference (`#42`) and web URL.
+    pub(crate) fn create_issue(
+        &self,
+        title: &str,
+        body: &str,
+        labels: &[String],
+    ) -> Result<(String, String), String> {
+        let payload = match self.kind {
+            ForgeKind::Github => serde_json::json!({
+                "title": title,
+                "body": body,
+                "labels": labels,
+            }),
+            ForgeKind::Gitlab => serde_json::json!({
+                "title": title,
+                "description": body,
+                "labels": labels.join(","),
+            }),
+        };
+
+        let response: serde_json::Value = self
+            .request("/issues")
+            .send_json(payload)
+            .map_err(|e| http::error_message(self.kind.label(), e))?
+            .into_json()
+            .map_err(|e| e.to_string())?;
+
+        let (number, url) = match self.kind {
+            ForgeKind::Github => (&response["number"], &response["html_url"]),
+            ForgeKind::Gitlab => (&response["iid"], &response["web_url"]),
+        };
+        match (number.as_u64(), url.as_str()) {
+            (Some(number), Some(url)) => Ok((format!("#{}", number), url.to_string())),
+            _ => Err(format!(
+                "{} did not return an issue number.",
+                self.kind.label()
+            )),
+        }
+    }
+
+    /// Uploads a file to a GitLab project and returns the markdown that embeds it.
+    fn upload(&self, path: &Path) -> Result<String, String> {
+        let (content_type, body) = http::multipart_file("file", path)?;
+
+        let response: serde_json::Value = self
+            .request("/uploads")
+            .set("Content-Type", &content_type)
+            .send_bytes(&body)
+            .map_err(|e| http::error_message(self.kind.label(), e))?
+            .into_json()
+            .map_err(|e| e.to_string())?;
+
+        response["markdown"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "GitLab did not return markdown for the upload.".to_string())
+    }
+
+    /// Markdown for a screenshot in the issue body. GitLab screenshots are uploaded to the
+    /// project (except in a dry run). The GitHub API has no upload endpoint, so GitHub issues
+    /// name the screenshot and say plainly that it is not attached.
+    fn screenshot_markdown(&self, path: &Path, dry_run: bool) -> Result<String, String> {
+        let name = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("screenshot");
+
+        match self.kind {
+            ForgeKind::Gitlab if dry_run => {
+                Ok(format!("- `{}` (uploaded when the issue is created)", name))
+            }
+            ForgeKind::Gitlab => self.upload(path),
+            ForgeKind::Github => Ok(format!(
+                "- `{}`: **not attached**, as GitHub does not accept uploads through its API; \
+                 add it to the issue by hand from the session export",
+                name
+            )),
+        }
+    }
+}
+
+/// A bug or question note to be turned into an issue.
+struct IssuePlan<'a> {
+    note_index: usize,
+    note_type: &'static str,
+    title: String,
+    text: &'a str,
+    /// Set for bugs; questions have no draft.
+    draft: Option<BugDraft>,
+    screenshots: Vec<PathBuf>,
+    snippets: Vec<&'a str>,
+}
+
+/// Issues to create for the bugs and questions of an export. Bugs the report left out (they
+/// have no draft under `bugs/`) are skipped.
+fn plan_issues<'a>(notes: &[&'a Note], export_dir: &Path) -> Vec<IssuePlan<'a>> {
+    let mut drafts = bugs::build_bug_drafts(notes).into_iter().peekable();
+    let mut plans = Vec::new();
+
+    for (index, note) in notes.iter().enumerate() {
+        if let Some(draft) = drafts.next_if(|d| d.note_index == index) {
+            if !bugs::draft_written(export_dir, &draft) {
+                continue;
+            }
+            plans.push(IssuePlan {
+                note_index: index,
+                note_type: "bug",
+                title: draft.title.clone(),
+                text: note.text.trim(),
+                screenshots: bugs::evidence_files(&draft, notes, export_dir),
+                snippets: bugs::evidence_snippets(&draft, notes),
+                draft: Some(draft),
+            });
+        } else if note.note_type.eq_ignore_ascii_case("question") {
+            plans.push(IssuePlan {
+                note_index: index,
+                note_type: "question",
+                title: format!("Question: {}", bugs::draft_title(&note.text)),
+                text: note.text.trim(),
+                draft: None,
+                screenshots: Vec::new(),
+                snippets: Vec::new(),
+            });
+        }
+    }
+
+    plans
+}
+
+/// Markdown issue body built from the note, its reproduction steps and evidence, the session
+/// charter and the environment.
+fn issue_body(plan: &IssuePlan, record: &SessionRecord, screenshots: &[String]) -> String {
+    let mut md = String::new();
+    md.push_str(plan.text);
+    md.push_str("\n\n");
+
+    if let Some(draft) = plan.draft.as_ref() {
+        md.push_str("### Steps to Reproduce\n\n");
+        if draft.steps.is_empty() {
+            md.push_str("_No test notes were recorded before this bug._\n\n");
+        } else {
+            for (i, step) in draft.steps.iter().enumerate() {
+                md.push_str(&format!("{}. {}\n", i + 1, step));
+            }
+            md.push('\n');
+        }
+    }
+
+    md.push_str("### Session Charter\n\n");
+    md.push_str(record.session.charter.trim());
+    md.push_str("\n\n");
+
+    // Issue descriptions are English on purpose, whatever the report language: trackers are
+    // shared with people who never saw the report.
+    let environment = record
+        .session
+        .environment
+        .as_ref()
+        .map(|env| markdown::environment_entries(env, Catalog::english()))
+        .unwrap_or_default();
+    if !environment.is_empty() {
+        md.push_str("### Environment\n\n");
+        for (label, value) in environment {
+            md.push_str(&format!("- **{}**: {}\n", label, value));
+        }
+        md.push('\n');
+    }
+
+    if !screenshots.is_empty() {
+        md.push_str("### Screenshots\n\n");
+        for line in screenshots {
+            md.push_str(line);
+            md.push('\n');
+        }
+        md.push('\n');
+    }
+
+    for snippet in &plan.snippets {
+        md.push_str("```\n");
+        md.push_str(snippet);
+        md.push_str("\n```\n\n");
+    }
+
+    md.push_str("---\n");
+    match record
+        .session
+        .tester_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        Some(tester) => md.push_str(&format!("Reported by {} with Rapid Reporter\n", tester)),
+        None => md.push_str("Reported with Rapid Reporter\n"),
+    }
+
+    md
+}
+
+/// Creates a GitHub / GitLab issue for every bug and question in an export folder that has not
+/// been filed there yet. Issue links are written back into the report, bug drafts and
+/// `session.json`.
+///
+/// With `dry_run` nothing is sent or written; the issues that would be created are returned.
+pub(crate) fn file_export_findings(
+    settings: &ForgeSettings,
+    export_dir: &Path,
+    dry_run: bool,
+) -> Result<Vec<ForgeIssue>, String> {
+    let client = ForgeClient::new(settings)?;
+    let mut record = read_session_record(export_dir)?;
+    let labels: Vec<String> = settings
+        .labels
+        .iter()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+
+    let notes: Vec<&Note> = record.session.notes.iter().rev().collect();
+    let plans = plan_issues(&notes, export_dir);
+
+    let mut issues = Vec::new();
+    let mut links = Vec::new();
+    let mut failure = None;
+
+    for plan in &plans {
+        let already_filed = record
+            .issues
+            .iter()
+            .any(|i| i.tracker == client.kind.id() && i.note_index == plan.note_index);
+        if already_filed {
+            continue;
+        }
+
+        let mut screenshots = Vec::new();
+        for path in &plan.screenshots {
+            match client.screenshot_markdown(path, dry_run) {
+                Ok(line) => screenshots.push(line),
+                Err(err) => {
+                    failure.get_or_insert(err);
+                }
+            }
+        }
+        let body = issue_body(plan, &record, &screenshots);
+
+        let mut issue = ForgeIssue {
+            note_index: plan.note_index,
+            note_type: plan.note_type.to_string(),
+            title: plan.title.clone(),
+            body,
+            labels: labels.clone(),
+            key: None,
+            url: None,
+        };
+
+        if !dry_run {
+            let (key, url) = match client.create_issue(&issue.title, &issue.body, &labels) {
+                Ok(created) => created,
+                Err(err) => {
+                    failure = Some(err);
+                    break;
+                }
+            };
+
+            let link = IssueLink {
+                tracker: client.kind.id().to_string(),
+                note_index: plan.note_index,
+                key: key.clone(),
+                url: url.clone(),
+            };
+            // Keep the issue even when the links cannot be written back, so it is saved in
+            // `session.json` and not created again on a retry.
+            let linked = match plan.draft.as_ref() {
+                Some(draft) => bugs::link_issue(export_dir, draft, &link),
+                None => link_note_in_report(export_dir, plan.note_index, &link),
+            };
+            if let Err(err) = linked {
+                failure.get_or_insert(format!(
+                    "{} could not be linked in the export: {}",
+                    link.key, err
+                ));
+            }
+            links.push(link);
+
+            issue.key = Some(key);
+            issue.url = Some(url);
+        }
+
+        issues.push(issue);
+    }
+
+    if !links.is_empty() {
+        record.issues.extend(links);
+        write_session_record(export_dir, &record)?;
+    }
+
+    match failure {
+        Some(err) if issues.iter().all(|i| i.key.is_none()) => Err(err),
+        Some(err) => Err(format!(
+            "Created {}, but {}",
+            issues
+                .iter()
+                .filter_map(|i| i.key.as_deref())
+                .collect::<Vec<_>>()
+                .join(", "),
+            err
+        )),
+        None => Ok(issues),
+    }
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    use tauri::Manager;
+
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(SETTINGS_FILE))
+}
+
+/// Loads the saved GitHub / GitLab settings, if the integration has been configured.
+fn load_settings(app: &tauri::AppHandle) -> Result<Option<ForgeSettings>, String> {
+    let _guard = SETTINGS_LOCK.lock().map_err(|e| e.to_string())?;
+
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let mut settings: ForgeSettings = serde_json::from_str(&raw)
+        .map_err(|e| format!("Issue settings {} are invalid: {}", path.display(), e))?;
+    // The settings file only holds the token when no keychain was available.
+    if settings.token.is_empty() {
+        settings.token = secrets::load_token(KEYCHAIN_ACCOUNT).unwrap_or_default();
+    }
+    Ok(Some(settings))
+}
+
+/// Returns the saved GitHub / GitLab settings, if any, with the token redacted.
+#[tauri::command]
+pub(crate) fn forge_settings(app: tauri::AppHandle) -> Result<Option<ForgeSettings>, String> {
+    Ok(load_settings(&app)?.map(|mut settings| {
+        settings.token = secrets::redact(&settings.token);
+        settings
+    }))
+}
+
+/// Validates and saves the GitHub / GitLab settings. An empty or redacted token keeps the
+/// saved one; the token goes into the OS keychain when there is one.
+#[tauri::command]
+pub(crate) fn save_forge_settings(
+    app: tauri::AppHandle,
+    mut settings: ForgeSettings,
+) -> Result<(), String> {
+    let saved = load_settings(&app)?;
+    settings.token = secrets::keep_saved(&settings.token, saved.as_ref().map(|s| s.token.as_str()));
+    ForgeClient::new(&settings)?;
+
+    if secrets::store_token(KEYCHAIN_ACCOUNT, &settings.token) {
+        settings.token.clear();
+    }
+
+    let _guard = SETTINGS_LOCK.lock().map_err(|e| e.to_string())?;
+
+    let path = settings_path(&app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Creates GitHub / GitLab issues for the bugs and questions of an exported session
+/// (see `file_export_findings`). Pass `dryRun` to preview the issues without creating them.
+#[tauri::command]
+pub(crate) async fn create_forge_issues(
+    app: tauri::AppHandle,
+    export_dir: String,
+    dry_run: Option<bool>,
+) -> Result<Vec<ForgeIssue>, String> {
+    let settings = load_settings(&app)?
+        .ok_or_else(|| "GitHub / GitLab issues are not configured yet.".to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        file_export_findings(&settings, Path::new(&export_dir), dry_run.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::report_path;
+    use crate::commands::export::test_support::{export_folder, export_folder_with, note, TempDir};
+    use crate::commands::http::mock;
+    use crate::models::ExportOptions;
+
+    fn settings(kind: ForgeKind, api_url: &str) -> ForgeSettings {
+        ForgeSettings {
+            kind,
+            api_url: Some(api_url.to_string()),
+            repo: "acme/shop".to_string(),
+            token: "t0ken".to_string(),
+            labels: vec!["exploratory".to_string()],
+        }
+    }
+
+    fn findings(name: &str, shot: &Path) -> TempDir {
+        std::fs::write(shot, b"png-bytes").unwrap();
+        export_folder(
+            name,
+            vec![
+                note("question", "Should refunds email the customer?"),
+                note("screenshot", shot.to_str().unwrap()),
+                note("bug", "Refund button does nothing"),
+                note("test", "Open order history"),
+            ],
+        )
+    }
+
+    #[test]
+    fn dry_run_previews_issues_without_requests() {
+        let shots = TempDir::new("forge-dry-shots");
+        let shot = shots.join("refund.png");
+        let dir = findings("forge-dry", &shot);
+
+        // Nothing listens on this address; any request would fail the run.
+        let issues = file_export_findings(
+            &settings(ForgeKind::Github, "http://127.0.0.1:9"),
+            &dir,
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].title, "Refund button does nothing");
+        assert_eq!(issues[0].note_type, "bug");
+        assert!(issues[0]
+            .body
+            .contains("### Steps to Reproduce\n\n1. Open order history\n"));
+        assert!(issues[0]
+            .body
+            .contains("### Session Charter\n\nExplore checkout\n"));
+        assert!(issues[0].body.contains(&format!(
+            "- `{}`: **not attached**, as GitHub does not accept uploads",
+            shot.file_name().unwrap().to_str().unwrap()
+        )));
+        assert_eq!(
+            issues[1].title,
+            "Question: Should refunds email the customer?"
+        );
+        assert!(issues.iter().all(|i| i.key.is_none()));
+        assert!(read_session_record(&dir).unwrap().issues.is_empty());
+    }
+
+    #[test]
+    fn github_issues_are_created_and_linked() {
+        let shots = TempDir::new("forge-gh-shots");
+        let shot = shots.join("refund.png");
+        let dir = findings("forge-github", &shot);
+
+        let (api_url, server) = mock::serve(vec![
+            (
+                201,
+                r#"{"number":12,"html_url":"https://github.com/acme/shop/issues/12"}"#.to_string(),
+            ),
+            (
+                201,
+                r#"{"number":13,"html_url":"https://github.com/acme/shop/issues/13"}"#.to_string(),
+            ),
+        ]);
+
+        let issues =
+            file_export_findings(&settings(ForgeKind::Github, &api_url), &dir, false).unwrap();
+        let requests = server.join().unwrap();
+
+        assert_eq!(issues[0].key.as_deref(), Some("#12"));
+        assert_eq!(requests[0].path, "/repos/acme/shop/issues");
+        assert_eq!(requests[0].header("Authorization"), Some("Bearer t0ken"));
+        assert_eq!(requests[0].json()["labels"][0], "exploratory");
+        assert_eq!(
+            requests[1].json()["title"],
+            "Question: Should refunds email the customer?"
+        );
+
+        let report = std::fs::read_to_string(report_path(&dir)).unwrap();
+        assert!(report.contains(
+            "(bugs/BUG-01-refund-button-does-nothing.md), GitHub [#12](https://github.com/acme/shop/issues/12))"
+        ));
+        assert!(report.contains(
+            "Should refunds email the customer? (GitHub [#13](https://github.com/acme/shop/issues/13))"
+        ));
+        assert_eq!(read_session_record(&dir).unwrap().issues.len(), 2);
+    }
+
+    #[test]
+    fn gitlab_uploads_screenshots_into_the_description() {
+        let shots = TempDir::new("forge-gl-shots");
+        let shot = shots.join("refund.png");
+        let dir = findings("forge-gitlab", &shot);
+
+        let (api_url, server) = mock::serve(vec![
+            (
+                201,
+                r#"{"markdown":"![shot](/uploads/abc/shot.png)"}"#.to_string(),
+            ),
+            (
+                201,
+                r#"{"iid":4,"web_url":"https://gitlab.com/acme/shop/-/issues/4"}"#.to_string(),
+            ),
+            (
+                201,
+                r#"{"iid":5,"web_url":"https://gitlab.com/acme/shop/-/issues/5"}"#.to_string(),
+            ),
+        ]);
+
+        let issues =
+            file_export_findings(&settings(ForgeKind::Gitlab, &api_url), &dir, false).unwrap();
+        let requests = server.join().unwrap();
+
+        assert_eq!(issues.len(), 2);
+        assert_eq!(requests[0].path, "/api/v4/projects/acme%2Fshop/uploads");
+        assert_eq!(requests[0].header("PRIVATE-TOKEN"), Some("t0ken"));
+        assert!(String::from_utf8_lossy(&requests[0].body).contains("png-bytes"));
+
+        let created = requests[1].json();
+        assert_eq!(requests[1].path, "/api/v4/projects/acme%2Fshop/issues");
+        assert_eq!(created["labels"], "exploratory");
+        assert!(created["description"]
+            .as_str()
+            .unwrap()
+            .contains("### Screenshots\n\n![shot](/uploads/abc/shot.png)\n"));
+    }
+
+    #[test]
+    fn bugs_left_out_of_the_report_are_not_filed() {
+        let options: ExportOptions =
+            serde_json::from_str(r#"{"includeTypes": ["question"]}"#).unwrap();
+        let dir = export_folder_with(
+            "forge-questions-only",
+            vec![
+                note("question", "Should refunds email the customer?"),
+                note("bug", "Refund button does nothing"),
+            ],
+            &options,
+        );
+        let (api_url, server) = mock::serve(vec![(
+            201,
+            r#"{"number":15,"html_url":"https://github.com/acme/shop/issues/15"}"#.to_string(),
+        )]);
+
+        let settings = settings(ForgeKind::Github, &api_url);
+        let issues = file_export_findings(&settings, &dir, false).unwrap();
+        let requests = server.join().unwrap();
+
+        assert_eq!(requests.len(), 1);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].note_type, "question");
+        assert!(!dir
+            .join("bugs/BUG-01-refund-button-does-nothing.md")
+            .exists());
+
+        // Filing again (or filing with Jira) finds nothing left to file.
+        assert!(file_export_findings(&settings, &dir, false)
+            .unwrap()
+            .is_empty());
+        let jira = crate::models::JiraSettings {
+            base_url: "http://127.0.0.1:9/".to_string(),
+            project_key: "QA".to_string(),
+            issue_type: "Bug".to_string(),
+            email: None,
+            api_token: "secret".to_string(),
+        };
+        assert!(crate::commands::jira::file_export_bugs(&jira, &dir)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn issues_are_kept_when_links_cannot_be_written_back() {
+        let dir = export_folder(
+            "forge-unlinked",
+            vec![note("question", "Should refunds email the customer?")],
+        );
+        // A custom template that leaves out the note anchors.
+        std::fs::write(report_path(&dir), "# Session\n").unwrap();
+        let (api_url, server) = mock::serve(vec![(
+            201,
+            r#"{"number":14,"html_url":"https://github.com/acme/shop/issues/14"}"#.to_string(),
+        )]);
+
+        let err =
+            file_export_findings(&settings(ForgeKind::Github, &api_url), &dir, false).unwrap_err();
+        server.join().unwrap();
+
+        assert_eq!(
+            err,
+            "Created #14, but #14 could not be linked in the export: The report has no line for note 1, so GitHub #14 is only linked in session.json"
+        );
+        assert_eq!(read_session_record(&dir).unwrap().issues[0].key, "#14");
+    }
+}
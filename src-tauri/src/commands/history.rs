@@ -0,0 +1,533 @@
+use crate::commands::export::is_text_note;
+use crate::commands::export::zone::{self, ReportZone};
+use crate::commands::tags;
+use crate::models::{Note, NoteMatch, Session, SessionQuery, SessionSummary};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const INDEX_FILE: &str = "session-history.json";
+
+/// Serialises read-modify-write cycles on the index file.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct IndexedNote {
+    note_type: String,
+    text: String,
+}
+
+impl IndexedNote {
+    /// `#tag` tokens of the note; only text notes carry tags, as in the exported tag index.
+    fn tags(&self) -> Vec<String> {
+        let note = Note {
+            note_type: self.note_type.clone(),
+            text: self.text.clone(),
+            timestamp: None,
+            timestamp_iso: None,
+        };
+        if is_text_note(&note) {
+            tags::parse_tokens(&self.text).tags
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IndexedSession {
+    /// Export folder name, e.g. `RapidReporter-2025-03-01-1405`. Re-exports replace the entry.
+    id: String,
+    export_dir: String,
+    markdown_path: String,
+    tester: Option<String>,
+    charter: String,
+    started_at: i64,
+    duration_minutes: Option<i64>,
+    /// Notes in chronological order.
+    notes: Vec<IndexedNote>,
+    indexed_at: i64,
+    /// `started_at` and `indexed_at` as ISO 8601 with the UTC offset of the report's time
+    /// zone (missing for sessions indexed before they were recorded).
+    #[serde(default)]
+    started_at_iso: Option<String>,
+    #[serde(default)]
+    indexed_at_iso: Option<String>,
+}
+
+impl IndexedSession {
+    pub(crate) fn from_export(
+        session: &Session,
+        export_dir: &Path,
+        markdown_path: &Path,
+        zone: &ReportZone,
+    ) -> Self {
+        let indexed_at = Utc::now().timestamp_millis();
+        let iso_at = |millis: i64| zone.at(millis).map(|time| zone::iso(&time));
+        let id = export_dir
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string();
+
+        IndexedSession {
+            id,
+            export_dir: export_dir.to_string_lossy().to_string(),
+            markdown_path: markdown_path.to_string_lossy().to_string(),
+            tester: session
+                .tester_name
+                .as_deref()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string),
+            charter: session.charter.trim().to_string(),
+            started_at: session.started_at,
+            duration_minutes: session.duration_minutes,
+            // The frontend keeps notes newest-first.
+            notes: session
+                .notes
+                .iter()
+                .rev()
+                .map(|n| IndexedNote {
+                    note_type: n.note_type.to_lowercase(),
+                    text: n.text.trim().to_string(),
+                })
+                .collect(),
+            indexed_at,
+            started_at_iso: iso_at(session.started_at),
+            indexed_at_iso: iso_at(indexed_at),
+        }
+    }
+
+    fn searchable_texts(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.charter.as_str()).chain(self.notes.iter().map(|n| n.text.as_str()))
+    }
+}
+
+/// On-disk session history: session records plus an inverted index of lowercase word →
+/// session ids, used to narrow free-text searches before notes are scanned.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HistoryIndex {
+    #[serde(default)]
+    sessions: Vec<IndexedSession>,
+    #[serde(default)]
+    terms: BTreeMap<String, BTreeSet<String>>,
+}
+
+/// Splits text into lowercase words for indexing and querying.
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+}
+
+impl HistoryIndex {
+    /// Adds or replaces a session and rebuilds its postings.
+    pub(crate) fn upsert(&mut self, entry: IndexedSession) {
+        self.remove(&entry.id);
+
+        for text in entry.searchable_texts() {
+            for word in tokenize(text) {
+                self.terms.entry(word).or_default().insert(entry.id.clone());
+            }
+        }
+
+        self.sessions.push(entry);
+        self.sessions
+            .sort_by_key(|s| std::cmp::Reverse(s.started_at));
+    }
+
+    pub(crate) fn remove(&mut self, id: &str) -> bool {
+        let before = self.sessions.len();
+        self.sessions.retain(|s| s.id != id);
+
+        self.terms.retain(|_, ids| {
+            ids.remove(id);
+            !ids.is_empty()
+        });
+
+        self.sessions.len() != before
+    }
+
+    /// Returns matching sessions, newest first.
+    pub(crate) fn search(&self, query: &SessionQuery) -> Vec<SessionSummary> {
+        let words: Vec<String> = query
+            .text
+            .as_deref()
+            .map(tokenize)
+            .into_iter()
+            .flatten()
+            .collect();
+        let note_type = query
+            .note_type
+            .as_deref()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty());
+        let tag = query.tag.as_deref().and_then(tags::normalize_tag);
+        let tester = query
+            .tester
+            .as_deref()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty());
+
+        // Sessions containing every query word somewhere. Words only need to prefix-match
+        // indexed terms, so "timeo" finds "timeout"; notes and charters are matched the same
+        // way below.
+        let candidates: Option<BTreeSet<&str>> = if words.is_empty() {
+            None
+        } else {
+            let mut sets = words.iter().map(|word| {
+                self.terms
+                    .range(word.clone()..)
+                    .take_while(|(term, _)| term.starts_with(word.as_str()))
+                    .flat_map(|(_, ids)| ids.iter().map(String::as_str))
+                    .collect::<BTreeSet<&str>>()
+            });
+            let first = sets.next().unwrap_or_default();
+            Some(sets.fold(first, |acc, set| acc.intersection(&set).copied().collect()))
+        };
+
+        let matches_all = |text: &str| {
+            let terms: Vec<String> = tokenize(text).collect();
+            words
+                .iter()
+                .all(|w| terms.iter().any(|term| term.starts_with(w.as_str())))
+        };
+
+        self.sessions
+            .iter()
+            .filter(|s| {
+                candidates
+                    .as_ref()
+                    .is_none_or(|c| c.contains(s.id.as_str()))
+            })
+            .filter(|s| query.from.is_none_or(|from| s.started_at >= from))
+            .filter(|s| query.to.is_none_or(|to| s.started_at <= to))
+            .filter(|s| {
+                tester.as_ref().is_none_or(|t| {
+                    s.tester
+                        .as_deref()
+                        .is_some_and(|name| name.to_lowercase().contains(t.as_str()))
+                })
+            })
+            .filter_map(|s| {
+                let filtered = !words.is_empty() || note_type.is_some() || tag.is_some();
+                let matches: Vec<NoteMatch> = if !filtered {
+                    Vec::new()
+                } else {
+                    s.notes
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, n)| note_type.as_ref().is_none_or(|t| &n.note_type == t))
+                        .filter(|(_, n)| matches_all(&n.text))
+                        .map(|(index, n)| (index, n, n.tags()))
+                        .filter(|(_, _, note_tags)| {
+                            tag.as_ref().is_none_or(|t| note_tags.contains(t))
+                        })
+                        .map(|(index, n, note_tags)| NoteMatch {
+                            index,
+                            note_type: n.note_type.clone(),
+                            text: n.text.clone(),
+                            tags: note_tags,
+                        })
+                        .collect()
+                };
+
+                let charter_hit = !words.is_empty()
+                    && note_type.is_none()
+                    && tag.is_none()
+                    && matches_all(&s.charter);
+                if filtered && matches.is_empty() && !charter_hit {
+                    return None;
+                }
+
+                Some(SessionSummary {
+                    id: s.id.clone(),
+                    export_dir: s.export_dir.clone(),
+                    markdown_path: s.markdown_path.clone(),
+                    tester: s.tester.clone(),
+                    charter: s.charter.clone(),
+                    started_at: s.started_at,
+                    started_at_iso: s.started_at_iso.clone(),
+                    duration_minutes: s.duration_minutes,
+                    note_count: s.notes.len(),
+                    matches,
+                })
+            })
+            .collect()
+    }
+}
+
+fn index_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    use tauri::Manager;
+
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(INDEX_FILE))
+}
+
+fn load_index(path: &Path) -> Result<HistoryIndex, String> {
+    if !path.exists() {
+        return Ok(HistoryIndex::default());
+    }
+
+    let raw = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&raw)
+        .map_err(|e| format!("Session history {} is invalid: {}", path.display(), e))
+}
+
+fn save_index(path: &Path, index: &HistoryIndex) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let json = serde_json::to_string(index).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+/// Records an exported session in the history index (called by the exporter).
+pub(crate) fn record_export(
+    app: &tauri::AppHandle,
+    session: &Session,
+    export_dir: &Path,
+    markdown_path: &Path,
+    zone: &ReportZone,
+) -> Result<(), String> {
+    let _guard = INDEX_LOCK.lock().map_err(|e| e.to_string())?;
+
+    let path = index_path(app)?;
+    let mut index = load_index(&path)?;
+    index.upsert(IndexedSession::from_export(
+        session,
+        export_dir,
+        markdown_path,
+        zone,
+    ));
+    save_index(&path, &index)
+}
+
+/// Lists exported sessions from the local history, newest first, filtered by `query`.
+///
+/// Answers questions like "have we seen this error before?" without searching export folders.
+#[tauri::command]
+pub(crate) fn list_sessions(
+    app: tauri::AppHandle,
+    query: Option<SessionQuery>,
+) -> Result<Vec<SessionSummary>, String> {
+    let _guard = INDEX_LOCK.lock().map_err(|e| e.to_string())?;
+
+    let index = load_index(&index_path(&app)?)?;
+    Ok(index.search(&query.unwrap_or_default()))
+}
+
+/// Removes a session from the history index. The export folder itself is left untouched.
+#[tauri::command]
+pub(crate) fn forget_session(app: tauri::AppHandle, id: String) -> Result<bool, String> {
+    let _guard = INDEX_LOCK.lock().map_err(|e| e.to_string())?;
+
+    let path = index_path(&app)?;
+    let mut index = load_index(&path)?;
+    let removed = index.remove(&id);
+    save_index(&path, &index)?;
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indexed(id: &str, tester: &str, started_at: i64, notes: &[(&str, &str)]) -> IndexedSession {
+        IndexedSession {
+            id: id.to_string(),
+            export_dir: format!("/home/t/{}", id),
+            markdown_path: format!("/home/t/{}/{}.md", id, id),
+            tester: Some(tester.to_string()),
+            charter: "Explore checkout".to_string(),
+            started_at,
+            duration_minutes: Some(60),
+            notes: notes
+                .iter()
+                .map(|(t, text)| IndexedNote {
+                    note_type: t.to_string(),
+                    text: text.to_string(),
+                })
+                .collect(),
+            indexed_at: 0,
+            started_at_iso: None,
+            indexed_at_iso: None,
+        }
+    }
+
+    fn sample() -> HistoryIndex {
+        let mut index = HistoryIndex::default();
+        index.upsert(indexed(
+            "s1",
+            "Ada",
+            100,
+            &[
+                ("test", "Pay with Visa"),
+                ("bug", "Gateway timeout on refund"),
+            ],
+        ));
+        index.upsert(indexed(
+            "s2",
+            "Grace",
+            200,
+            &[
+                ("bug", "Basket total is wrong"),
+                ("question", "Is a timeout expected?"),
+            ],
+        ));
+        index
+    }
+
+    fn ids(results: &[SessionSummary]) -> Vec<&str> {
+        results.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn free_text_finds_matching_notes_across_sessions() {
+        let query = SessionQuery {
+            text: Some("TIMEOUT".to_string()),
+            ..Default::default()
+        };
+        let results = sample().search(&query);
+
+        assert_eq!(ids(&results), vec!["s2", "s1"]);
+        assert_eq!(results[1].matches[0].text, "Gateway timeout on refund");
+        assert_eq!(results[1].matches[0].index, 1);
+    }
+
+    #[test]
+    fn words_match_notes_by_prefix_as_in_the_index() {
+        let mut index = sample();
+        index.upsert(indexed(
+            "s3",
+            "Ada",
+            300,
+            &[("bug", "Gateway outage"), ("test", "Checkout timeout")],
+        ));
+
+        let query = SessionQuery {
+            text: Some("out".to_string()),
+            ..Default::default()
+        };
+        let results = index.search(&query);
+        assert_eq!(ids(&results), vec!["s3"]);
+        let texts: Vec<&str> = results[0].matches.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["Gateway outage"]);
+    }
+
+    #[test]
+    fn filters_combine() {
+        let query = SessionQuery {
+            text: Some("timeout".to_string()),
+            note_type: Some("bug".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(&sample().search(&query)), vec!["s1"]);
+
+        let query = SessionQuery {
+            tester: Some("grace".to_string()),
+            from: Some(150),
+            ..Default::default()
+        };
+        assert_eq!(ids(&sample().search(&query)), vec!["s2"]);
+
+        let query = SessionQuery {
+            to: Some(150),
+            ..Default::default()
+        };
+        assert_eq!(ids(&sample().search(&query)), vec!["s1"]);
+    }
+
+    #[test]
+    fn tag_filter_matches_tagged_notes_only() {
+        let mut index = sample();
+        index.upsert(indexed(
+            "s3",
+            "Ada",
+            300,
+            &[
+                ("bug", "Coupon rejected #checkout #promo"),
+                ("test", "Apply coupon #Promo"),
+                ("test", "Ticket #123 is about #checkout"),
+                ("snippet", "grep #promo app.log"),
+                ("screenshot", "/tmp/#promo.png"),
+            ],
+        ));
+
+        let query = SessionQuery {
+            tag: Some("#PROMO".to_string()),
+            ..Default::default()
+        };
+        let results = index.search(&query);
+        assert_eq!(ids(&results), vec!["s3"]);
+        let indices: Vec<usize> = results[0].matches.iter().map(|m| m.index).collect();
+        assert_eq!(indices, vec![0, 1]);
+        assert_eq!(results[0].matches[0].tags, vec!["checkout", "promo"]);
+
+        let query = SessionQuery {
+            tag: Some("checkout".to_string()),
+            note_type: Some("test".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(index.search(&query)[0].matches[0].index, 2);
+    }
+
+    #[test]
+    fn reindexing_replaces_the_previous_entry() {
+        let mut index = sample();
+        index.upsert(indexed("s1", "Ada", 100, &[("test", "Nothing odd")]));
+
+        let query = SessionQuery {
+            text: Some("gateway".to_string()),
+            ..Default::default()
+        };
+        assert!(index.search(&query).is_empty());
+        assert_eq!(index.sessions.len(), 2);
+    }
+
+    #[test]
+    fn exports_are_indexed_with_iso_times_in_the_report_zone() {
+        let session = Session {
+            tester_name: None,
+            charter: "Explore checkout".to_string(),
+            duration_minutes: None,
+            started_at: 1_700_000_000_000,
+            notes: Vec::new(),
+            environment: None,
+            library_charter: None,
+        };
+        let zone = ReportZone::load(Some("+05:30")).unwrap();
+        let entry = IndexedSession::from_export(
+            &session,
+            Path::new("/home/t/RapidReporter-2023-11-15-0343"),
+            Path::new("/home/t/RapidReporter-2023-11-15-0343/report.md"),
+            &zone,
+        );
+
+        assert_eq!(entry.id, "RapidReporter-2023-11-15-0343");
+        assert_eq!(
+            entry.started_at_iso.as_deref(),
+            Some("2023-11-15T03:43:20+05:30")
+        );
+        assert!(entry.indexed_at_iso.as_deref().unwrap().ends_with("+05:30"));
+
+        let mut index = HistoryIndex::default();
+        index.upsert(entry);
+        assert_eq!(
+            index.search(&SessionQuery::default())[0]
+                .started_at_iso
+                .as_deref(),
+            Some("2023-11-15T03:43:20+05:30")
+        );
+    }
+}
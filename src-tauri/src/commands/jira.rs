@@ -0,0 +1,468 @@
+use crate::commands::export::bugs::{self, BugDraft};
+use crate::commands::export::locale::Catalog;
+use crate::commands::export::{read_session_record, write_session_record};
+use crate::commands::{export::markdown, http, secrets};
+use crate::models::{IssueLink, JiraSettings, Note, SessionRecord};
+use base64::Engine;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const SETTINGS_FILE: &str = "jira.json";
+/// Keychain account of the API token.
+const KEYCHAIN_ACCOUNT: &str = "jira-api-token";
+
+/// Serialises reads and writes of the settings file.
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Blocking client for the parts of the Jira REST API (v2) used to file bugs.
+pub(crate) struct JiraClient {
+    agent: ureq::Agent,
+    base_url: String,
+    authorization: String,
+}
+
+impl JiraClient {
+    pub(crate) fn new(settings: &JiraSettings) -> Result<Self, String> {
+        let base_url = settings.base_url.trim().trim_end_matches('/').to_string();
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err("The Jira base URL must start with http:// or https://.".to_string());
+        }
+        if settings.project_key.trim().is_empty() {
+            return Err("A Jira project key is required.".to_string());
+        }
+        if settings.api_token.trim().is_empty() {
+            return Err("A Jira API token is required.".to_string());
+        }
+
+        let token = settings.api_token.trim();
+        let authorization = match settings
+            .email
+            .as_deref()
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+        {
+            Some(email) => format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", email, token))
+            ),
+            None => format!("Bearer {}", token),
+        };
+
+        Ok(JiraClient {
+            agent: http::agent(),
+            base_url,
+            authorization,
+        })
+    }
+
+    /// Creates an issue and returns its key (e.g. `QA-123`).
+    pub(crate) fn create_issue(
+        &self,
+        settings: &JiraSettings,
+        summary: &str,
+        description: &str,
+    ) -> Result<String, String> {
+        let payload = serde_json::json!({
+            "fields": {
+                "project": { "key": settings.project_key.trim() },
+                "issuetype": { "name": settings.issue_type.trim() },
+                "summary": summary,
+                "description": description,
+            }
+        });
+
+        let response: serde_json::Value = self
+            .agent
+            .post(&format!("{}/rest/api/2/issue", self.base_url))
+            .set("Authorization", &self.authorization)
+            .set("Accept", "application/json")
+            .send_json(payload)
+            .map_err(|e| http::error_message("Jira", e))?
+            .into_json()
+            .map_err(|e| e.to_string())?;
+
+        response["key"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "Jira did not return an issue key.".to_string())
+    }
+
+    /// Uploads a file as an attachment of issue `key`.
+    pub(crate) fn attach_file(&self, key: &str, path: &Path) -> Result<(), String> {
+        let (content_type, body) = http::multipart_file("file", path)?;
+
+        self.agent
+            .post(&format!(
+                "{}/rest/api/2/issue/{}/attachments",
+                self.base_url, key
+            ))
+            .set("Authorization", &self.authorization)
+            .set("X-Atlassian-Token", "no-check")
+            .set("Content-Type", &content_type)
+            .send_bytes(&body)
+            .map_err(|e| http::error_message("Jira", e))?;
+
+        Ok(())
+    }
+
+    pub(crate) fn browse_url(&self, key: &str) -> String {
+        format!("{}/browse/{}", self.base_url, key)
+    }
+}
+
+/// Renders a bug draft as a Jira wiki-markup description. Screenshots are attached to the
+/// issue and shown inline as thumbnails.
+pub(crate) fn issue_description(
+    draft: &BugDraft,
+    record: &SessionRecord,
+    snippets: &[&str],
+    attachments: &[PathBuf],
+) -> String {
+    let mut text = String::new();
+    text.push_str(&draft.description);
+    text.push_str("\n\n");
+
+    text.push_str("h3. Steps to Reproduce\n");
+    if draft.steps.is_empty() {
+        text.push_str("_No test notes were recorded before this bug._\n");
+    } else {
+        for step in &draft.steps {
+            text.push_str(&format!("# {}\n", step));
+        }
+    }
+    text.push('\n');
+
+    // Issue descriptions are English on purpose, whatever the report language: trackers are
+    // shared with people who never saw the report.
+    let environment = record
+        .session
+        .environment
+        .as_ref()
+        .map(|env| markdown::environment_entries(env, Catalog::english()))
+        .unwrap_or_default();
+    if !environment.is_empty() {
+        text.push_str("h3. Environment\n");
+        for (label, value) in environment {
+            text.push_str(&format!("* *{}*: {}\n", label, value));
+        }
+        text.push('\n');
+    }
+
+    if !snippets.is_empty() || !attachments.is_empty() {
+        text.push_str("h3. Evidence\n");
+        for snippet in snippets {
+            text.push_str(&format!("{{noformat}}\n{}\n{{noformat}}\n", snippet));
+        }
+        for path in attachments {
+            if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
+                text.push_str(&format!("!{}|thumbnail!\n", name));
+            }
+        }
+        text.push('\n');
+    }
+
+    text.push_str("----\n");
+    text.push_str(&format!(
+        "Charter: {}\n",
+        record
+            .session
+            .charter
+            .trim()
+            .lines()
+            .next()
+            .unwrap_or_default()
+    ));
+    if let Some(tester) = record
+        .session
+        .tester_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        text.push_str(&format!("Reported by {} with Rapid Reporter\n", tester));
+    }
+
+    text
+}
+
+/// Files a Jira issue for every bug in an export folder that has not been filed yet. Bugs the
+/// report left out by its type filters (they have no draft under `bugs/`) are skipped.
+///
+/// Screenshots near each bug are uploaded as attachments, and the issue keys are written
+/// back into the report, the bug drafts and `session.json`. Returns the newly created links.
+pub(crate) fn file_export_bugs(
+    settings: &JiraSettings,
+    export_dir: &Path,
+) -> Result<Vec<IssueLink>, String> {
+    let client = JiraClient::new(settings)?;
+    let mut record = read_session_record(export_dir)?;
+
+    let notes: Vec<&Note> = record.session.notes.iter().rev().collect();
+    let drafts = bugs::build_bug_drafts(&notes);
+
+    let mut created = Vec::new();
+    let mut failure = None;
+
+    for draft in &drafts {
+        let already_filed = record
+            .issues
+            .iter()
+            .any(|i| i.tracker == "jira" && i.note_index == draft.note_index);
+        if already_filed || !bugs::draft_written(export_dir, draft) {
+            continue;
+        }
+
+        let attachments = bugs::evidence_files(draft, &notes, export_dir);
+        let snippets = bugs::evidence_snippets(draft, &notes);
+        let description = issue_description(draft, &record, &snippets, &attachments);
+
+        let key = match client.create_issue(settings, &draft.title, &description) {
+            Ok(key) => key,
+            Err(err) => {
+                failure = Some(err);
+                break;
+            }
+        };
+
+        for path in &attachments {
+            if let Err(err) = client.attach_file(&key, path) {
+                failure.get_or_insert(err);
+            }
+        }
+
+        let issue = IssueLink {
+            tracker: "jira".to_string(),
+            note_index: draft.note_index,
+            url: client.browse_url(&key),
+            key,
+        };
+        // Keep the issue even when the links cannot be written back, so it is saved in
+        // `session.json` and not filed again on a retry.
+        if let Err(err) = bugs::link_issue(export_dir, draft, &issue) {
+            failure.get_or_insert(format!(
+                "{} could not be linked in the export: {}",
+                issue.key, err
+            ));
+        }
+        created.push(issue);
+    }
+
+    // Save whatever was filed before reporting a failure, so a retry skips those bugs.
+    record.issues.extend(created.iter().cloned());
+    write_session_record(export_dir, &record)?;
+
+    match failure {
+        Some(err) if created.is_empty() => Err(err),
+        Some(err) => Err(format!(
+            "Filed {}, but {}",
+            created
+                .iter()
+                .map(|i| i.key.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            err
+        )),
+        None => Ok(created),
+    }
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    use tauri::Manager;
+
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(SETTINGS_FILE))
+}
+
+/// Loads the saved Jira settings, if the integration has been configured.
+pub(crate) fn load_settings(app: &tauri::AppHandle) -> Result<Option<JiraSettings>, String> {
+    let _guard = SETTINGS_LOCK.lock().map_err(|e| e.to_string())?;
+
+    let path = settings_path(app)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let mut settings: JiraSettings = serde_json::from_str(&raw)
+        .map_err(|e| format!("Jira settings {} are invalid: {}", path.display(), e))?;
+    // The settings file only holds the token when no keychain was available.
+    if settings.api_token.is_empty() {
+        settings.api_token = secrets::load_token(KEYCHAIN_ACCOUNT).unwrap_or_default();
+    }
+    Ok(Some(settings))
+}
+
+/// Returns the saved Jira settings, if any, with the API token redacted.
+#[tauri::command]
+pub(crate) fn jira_settings(app: tauri::AppHandle) -> Result<Option<JiraSettings>, String> {
+    Ok(load_settings(&app)?.map(|mut settings| {
+        settings.api_token = secrets::redact(&settings.api_token);
+        settings
+    }))
+}
+
+/// Validates and saves the Jira settings. An empty or redacted API token keeps the saved one;
+/// the token goes into the OS keychain when there is one.
+#[tauri::command]
+pub(crate) fn save_jira_settings(
+    app: tauri::AppHandle,
+    mut settings: JiraSettings,
+) -> Result<(), String> {
+    let saved = load_settings(&app)?;
+    settings.api_token = secrets::keep_saved(
+        &settings.api_token,
+        saved.as_ref().map(|s| s.api_token.as_str()),
+    );
+    JiraClient::new(&settings)?;
+
+    if secrets::store_token(KEYCHAIN_ACCOUNT, &settings.api_token) {
+        settings.api_token.clear();
+    }
+
+    let _guard = SETTINGS_LOCK.lock().map_err(|e| e.to_string())?;
+
+    let path = settings_path(&app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Files Jira issues for the bugs of an already exported session (see `file_export_bugs`).
+#[tauri::command]
+pub(crate) async fn create_jira_issues(
+    app: tauri::AppHandle,
+    export_dir: String,
+) -> Result<Vec<IssueLink>, String> {
+    let settings = load_settings(&app)?.ok_or_else(|| "Jira is not configured yet.".to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        file_export_bugs(&settings, Path::new(&export_dir))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::test_support::{export_folder, note, TempDir};
+    use crate::commands::http::mock;
+
+    fn settings(base_url: &str, email: Option<&str>) -> JiraSettings {
+        JiraSettings {
+            base_url: format!("{}/", base_url),
+            project_key: "QA".to_string(),
+            issue_type: "Bug".to_string(),
+            email: email.map(str::to_string),
+            api_token: "secret".to_string(),
+        }
+    }
+
+    #[test]
+    fn files_bugs_and_writes_keys_back() {
+        let shots = TempDir::new("jira-shots");
+        let shot = shots.join("refund.png");
+        std::fs::write(&shot, b"png-bytes").unwrap();
+
+        let dir = export_folder(
+            "jira-export",
+            vec![
+                note("screenshot", shot.to_str().unwrap()),
+                note("bug", "Refund button does nothing"),
+                note("test", "Open order history"),
+            ],
+        );
+
+        let (base_url, server) = mock::serve(vec![
+            (201, r#"{"id":"10001","key":"QA-7"}"#.to_string()),
+            (200, "[]".to_string()),
+        ]);
+
+        let created = file_export_bugs(&settings(&base_url, Some("ada@example.com")), &dir)
+            .expect("issues should be filed");
+        let requests = server.join().unwrap();
+
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].key, "QA-7");
+        assert_eq!(created[0].url, format!("{}/browse/QA-7", base_url));
+
+        let create = &requests[0];
+        assert_eq!(create.method, "POST");
+        assert_eq!(create.path, "/rest/api/2/issue");
+        assert_eq!(
+            create.header("Authorization"),
+            Some("Basic YWRhQGV4YW1wbGUuY29tOnNlY3JldA==")
+        );
+        let fields = &create.json()["fields"];
+        assert_eq!(fields["project"]["key"], "QA");
+        assert_eq!(fields["issuetype"]["name"], "Bug");
+        assert_eq!(fields["summary"], "Refund button does nothing");
+        let description = fields["description"].as_str().unwrap();
+        assert!(description.contains("h3. Steps to Reproduce\n# Open order history\n"));
+        assert!(description.contains(&format!(
+            "!{}|thumbnail!",
+            shot.file_name().unwrap().to_str().unwrap()
+        )));
+
+        let upload = &requests[1];
+        assert_eq!(upload.path, "/rest/api/2/issue/QA-7/attachments");
+        assert_eq!(upload.header("X-Atlassian-Token"), Some("no-check"));
+        assert!(String::from_utf8_lossy(&upload.body).contains("png-bytes"));
+
+        let report = std::fs::read_to_string(crate::commands::export::report_path(&dir)).unwrap();
+        assert!(report.contains(&format!(
+            "([bug report draft](bugs/BUG-01-refund-button-does-nothing.md), Jira [QA-7]({}/browse/QA-7))",
+            base_url
+        )));
+        let draft =
+            std::fs::read_to_string(dir.join("bugs/BUG-01-refund-button-does-nothing.md")).unwrap();
+        assert!(draft.contains(&format!(
+            "- **Charter**: x\n- **Jira**: [QA-7]({}/browse/QA-7)\n\n## Description",
+            base_url
+        )));
+        assert_eq!(read_session_record(&dir).unwrap().issues, created);
+
+        // Bugs that already have an issue are not filed twice.
+        assert!(file_export_bugs(&settings(&base_url, None), &dir)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn jira_errors_are_reported_with_the_response_body() {
+        let dir = export_folder("jira-error", vec![note("bug", "Broken")]);
+        let (base_url, server) = mock::serve(vec![(
+            400,
+            r#"{"errors":{"issuetype":"issue type is required"}}"#.to_string(),
+        )]);
+
+        let err = file_export_bugs(&settings(&base_url, None), &dir).unwrap_err();
+        let requests = server.join().unwrap();
+
+        assert_eq!(requests[0].header("Authorization"), Some("Bearer secret"));
+        assert!(err.starts_with("Jira returned HTTP 400"));
+        assert!(err.contains("issue type is required"));
+        assert!(read_session_record(&dir).unwrap().issues.is_empty());
+    }
+
+    #[test]
+    fn issues_are_kept_when_the_report_has_no_line_for_the_bug() {
+        let dir = export_folder("jira-unlinked", vec![note("bug", "Broken")]);
+        // A custom template that leaves out the note anchors.
+        std::fs::write(crate::commands::export::report_path(&dir), "# Session\n").unwrap();
+        let (base_url, server) = mock::serve(vec![(201, r#"{"id":"1","key":"QA-8"}"#.to_string())]);
+
+        let err = file_export_bugs(&settings(&base_url, None), &dir).unwrap_err();
+        server.join().unwrap();
+
+        assert_eq!(
+            err,
+            "Filed QA-8, but QA-8 could not be linked in the export: The report has no line for note 1, so Jira QA-8 is only linked in session.json"
+        );
+        let issues = read_session_record(&dir).unwrap().issues;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].key, "QA-8");
+    }
+}
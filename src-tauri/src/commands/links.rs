@@ -0,0 +1,235 @@
+use crate::models::LinkRule;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const RULES_FILE: &str = "link-rules.json";
+
+/// Serialises reads and writes of the rules file.
+static RULES_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct RulesFile {
+    #[serde(default)]
+    rules: Vec<LinkRule>,
+}
+
+/// A ticket reference found in note text.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TicketRef {
+    /// The matched text, e.g. `PROJ-123` or `#456`.
+    pub(crate) key: String,
+    pub(crate) url: String,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+/// Compiled link rules, applied in order; earlier rules win where matches overlap.
+#[derive(Debug, Default)]
+pub(crate) struct LinkRules(Vec<(Regex, String)>);
+
+impl LinkRules {
+    /// Compiles `rules`, naming the offending rule when a pattern or URL template is invalid.
+    pub(crate) fn compile(rules: &[LinkRule]) -> Result<Self, String> {
+        let mut compiled = Vec::with_capacity(rules.len());
+
+        for (i, rule) in rules.iter().enumerate() {
+            let name = rule
+                .name
+                .as_deref()
+                .filter(|n| !n.trim().is_empty())
+                .map(|n| format!("\"{}\"", n.trim()))
+                .unwrap_or_else(|| format!("#{}", i + 1));
+
+            let regex = Regex::new(rule.pattern.trim())
+                .map_err(|e| format!("Link rule {} has an invalid pattern: {}", name, e))?;
+            let url = rule.url.trim();
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(format!(
+                    "Link rule {} needs a URL template starting with http:// or https://",
+                    name
+                ));
+            }
+
+            compiled.push((regex, url.to_string()));
+        }
+
+        Ok(LinkRules(compiled))
+    }
+
+    /// Every ticket referenced in `text`, in order of appearance, without overlaps.
+    pub(crate) fn find(&self, text: &str) -> Vec<TicketRef> {
+        let mut found: Vec<TicketRef> = Vec::new();
+
+        for (regex, template) in &self.0 {
+            for caps in regex.captures_iter(text) {
+                let Some(whole) = caps.get(0).filter(|m| !m.as_str().is_empty()) else {
+                    continue;
+                };
+                if found
+                    .iter()
+                    .any(|t| whole.start() < t.end && t.start < whole.end())
+                {
+                    continue;
+                }
+
+                let mut url = String::new();
+                caps.expand(template, &mut url);
+                found.push(TicketRef {
+                    key: whole.as_str().to_string(),
+                    url,
+                    start: whole.start(),
+                    end: whole.end(),
+                });
+            }
+        }
+
+        found.sort_by_key(|t| t.start);
+        found
+    }
+
+    /// Rewrites ticket references in `text` as markdown links.
+    pub(crate) fn linkify(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+
+        for ticket in self.find(text) {
+            out.push_str(&text[last..ticket.start]);
+            let key = ticket.key.replace('[', "\\[").replace(']', "\\]");
+            out.push_str(&format!("[{}]({})", key, ticket.url));
+            last = ticket.end;
+        }
+        out.push_str(&text[last..]);
+
+        out
+    }
+}
+
+fn rules_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    use tauri::Manager;
+
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    Ok(dir.join(RULES_FILE))
+}
+
+fn read_rules(app: &tauri::AppHandle) -> Result<Vec<LinkRule>, String> {
+    let path = rules_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let raw = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str::<RulesFile>(&raw)
+        .map(|file| file.rules)
+        .map_err(|e| format!("Link rules {} are invalid: {}", path.display(), e))
+}
+
+/// Loads and compiles the configured link rules (none until the user adds some).
+pub(crate) fn load_rules(app: &tauri::AppHandle) -> Result<LinkRules, String> {
+    let _guard = RULES_LOCK.lock().map_err(|e| e.to_string())?;
+    LinkRules::compile(&read_rules(app)?)
+}
+
+/// Returns the configured link rules.
+#[tauri::command]
+pub(crate) fn list_link_rules(app: tauri::AppHandle) -> Result<Vec<LinkRule>, String> {
+    let _guard = RULES_LOCK.lock().map_err(|e| e.to_string())?;
+    read_rules(&app)
+}
+
+/// Validates and saves the link rules, replacing the current set.
+///
+/// Each rule maps a regex to a URL template; `$0` is the whole match and `$1`, `$2`, ...
+/// (or named groups) are capture groups, e.g. `#(\d+)` → `https://github.com/acme/shop/issues/$1`.
+#[tauri::command]
+pub(crate) fn save_link_rules(app: tauri::AppHandle, rules: Vec<LinkRule>) -> Result<(), String> {
+    LinkRules::compile(&rules)?;
+
+    let _guard = RULES_LOCK.lock().map_err(|e| e.to_string())?;
+
+    let path = rules_path(&app)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(&RulesFile { rules }).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, url: &str) -> LinkRule {
+        LinkRule {
+            name: None,
+            pattern: pattern.to_string(),
+            url: url.to_string(),
+        }
+    }
+
+    fn rules() -> LinkRules {
+        LinkRules::compile(&[
+            rule(
+                r"\b[A-Z][A-Z0-9]+-\d+\b",
+                "https://jira.example.com/browse/$0",
+            ),
+            rule(r"#(\d+)\b", "https://github.com/acme/shop/issues/$1"),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn linkify_expands_templates_for_every_rule() {
+        assert_eq!(
+            rules().linkify("see PROJ-123 and #456, not #abc"),
+            "see [PROJ-123](https://jira.example.com/browse/PROJ-123) and \
+             [#456](https://github.com/acme/shop/issues/456), not #abc"
+        );
+    }
+
+    #[test]
+    fn linkify_escapes_brackets_in_keys() {
+        let rules =
+            LinkRules::compile(&[rule(r"\[(\d+)\]", "https://tracker.example.com/$1")]).unwrap();
+
+        assert_eq!(
+            rules.linkify("see [42]"),
+            "see [\\[42\\]](https://tracker.example.com/42)"
+        );
+    }
+
+    #[test]
+    fn earlier_rules_win_overlapping_matches() {
+        let rules = LinkRules::compile(&[
+            rule(r"QA-\d+", "https://a.example.com/$0"),
+            rule(r"\d+", "https://b.example.com/$0"),
+        ])
+        .unwrap();
+
+        let keys: Vec<String> = rules
+            .find("QA-7 then 42")
+            .into_iter()
+            .map(|t| t.key)
+            .collect();
+        assert_eq!(keys, vec!["QA-7", "42"]);
+    }
+
+    #[test]
+    fn invalid_rules_are_named_in_the_error() {
+        let err = LinkRules::compile(&[
+            rule(r"ok-\d+", "https://x.example.com/$0"),
+            LinkRule {
+                name: Some("Broken".to_string()),
+                pattern: "(".to_string(),
+                url: "https://x.example.com/$0".to_string(),
+            },
+        ])
+        .unwrap_err();
+        assert!(err.starts_with("Link rule \"Broken\" has an invalid pattern"));
+
+        let err = LinkRules::compile(&[rule(r"\d+", "jira/$0")]).unwrap_err();
+        assert!(err.starts_with("Link rule #1 needs a URL template"));
+    }
+}
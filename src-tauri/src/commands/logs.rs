@@ -0,0 +1,390 @@
+use crate::commands::{emit_to_main, NOTE_CAPTURED_EVENT};
+use crate::models::{LogExcerpt, LogLineMatch, Note};
+use chrono::Local;
+use regex::Regex;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Event emitted for every new log line matching a watch pattern.
+pub(crate) const LOG_LINE_EVENT: &str = "log-line-matched";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const DEFAULT_TAIL_LINES: usize = 50;
+const MAX_TAIL_LINES: usize = 2_000;
+
+/// Managed state holding a stop flag for every running log watch, keyed by watch id.
+#[derive(Default)]
+pub(crate) struct LogWatchers(Mutex<HashMap<String, Arc<AtomicBool>>>);
+
+/// Read position within a watched file, carried between polls.
+#[derive(Debug, Default)]
+pub(crate) struct TailState {
+    offset: u64,
+    line_number: u64,
+    /// Bytes of an unfinished last line, decoded once its newline arrives so that a UTF-8
+    /// character split across two polls is not garbled.
+    partial: Vec<u8>,
+}
+
+impl TailState {
+    /// Starts watching from the current end of `path`, so only lines written from now on match.
+    pub(crate) fn at_end_of(path: &Path) -> Result<Self, String> {
+        let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+        let mut reader = std::io::BufReader::new(file);
+        let mut state = TailState::default();
+
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            let read = reader
+                .read_until(b'\n', &mut buf)
+                .map_err(|e| e.to_string())?;
+            if read == 0 {
+                break;
+            }
+            state.offset += read as u64;
+            if buf.ends_with(b"\n") {
+                state.line_number += 1;
+            } else {
+                state.partial = std::mem::take(&mut buf);
+            }
+        }
+
+        Ok(state)
+    }
+}
+
+/// Reads complete lines appended to `path` since the last call, with 1-based line numbers.
+///
+/// A trailing line without a newline is held back until it is finished. If the file shrank
+/// (truncated or rotated), reading restarts from the top.
+pub(crate) fn read_new_lines(
+    path: &Path,
+    state: &mut TailState,
+) -> Result<Vec<(u64, String)>, String> {
+    let mut file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+
+    if len < state.offset {
+        *state = TailState::default();
+    }
+    if len == state.offset {
+        return Ok(Vec::new());
+    }
+
+    file.seek(SeekFrom::Start(state.offset))
+        .map_err(|e| e.to_string())?;
+    let mut buf = Vec::new();
+    file.take(len - state.offset)
+        .read_to_end(&mut buf)
+        .map_err(|e| e.to_string())?;
+    state.offset += buf.len() as u64;
+
+    let mut chunk = std::mem::take(&mut state.partial);
+    chunk.extend_from_slice(&buf);
+
+    let mut lines = Vec::new();
+    let mut start = 0;
+    while let Some(pos) = chunk[start..].iter().position(|&b| b == b'\n') {
+        let end = start + pos;
+        state.line_number += 1;
+        let line = String::from_utf8_lossy(&chunk[start..end]);
+        lines.push((state.line_number, line.trim_end_matches('\r').to_string()));
+        start = end + 1;
+    }
+    state.partial = chunk.split_off(start);
+
+    Ok(lines)
+}
+
+/// Formats log lines as the text of a `snippet` note, headed by `file:line`.
+pub(crate) fn snippet_note_text(source: &Path, first_line: u64, text: &str) -> String {
+    let name = source
+        .file_name()
+        .and_then(|s| s.to_str())
+        .map(str::to_string)
+        .unwrap_or_else(|| source.to_string_lossy().to_string());
+
+    format!("{}:{}\n{}", name, first_line, text)
+}
+
+/// Starts tailing one or more log files in the background.
+///
+/// Every new line matching `pattern` (all lines when omitted) is emitted as a
+/// `log-line-matched` event. With `record_matches` set, matches are also pushed to the
+/// session as `snippet` notes. Returns the watch id to pass to `stop_log_watch`.
+#[tauri::command]
+pub(crate) fn start_log_watch(
+    app: tauri::AppHandle,
+    watchers: tauri::State<'_, LogWatchers>,
+    paths: Vec<String>,
+    pattern: Option<String>,
+    record_matches: Option<bool>,
+) -> Result<String, String> {
+    if paths.is_empty() {
+        return Err("No log files were given to watch.".to_string());
+    }
+
+    let regex = match pattern.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(p) => Some(Regex::new(p).map_err(|e| format!("Invalid log pattern: {}", e))?),
+        None => None,
+    };
+
+    let mut files = Vec::with_capacity(paths.len());
+    for path in &paths {
+        let path = PathBuf::from(path);
+        let state = TailState::at_end_of(&path)
+            .map_err(|e| format!("Cannot watch {}: {}", path.display(), e))?;
+        files.push((path, state));
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let watch_id = {
+        let mut running = watchers.0.lock().map_err(|e| e.to_string())?;
+
+        let millis = Local::now().timestamp_millis();
+        let mut watch_id = format!("log-watch-{}", millis);
+        let mut counter = 1u32;
+        while running.contains_key(&watch_id) {
+            watch_id = format!("log-watch-{}-{}", millis, counter);
+            counter += 1;
+        }
+
+        running.insert(watch_id.clone(), stop.clone());
+        watch_id
+    };
+
+    let record = record_matches.unwrap_or(false);
+    let id = watch_id.clone();
+
+    std::thread::spawn(move || {
+        while !stop.load(Ordering::Relaxed) {
+            for (path, state) in files.iter_mut() {
+                // Files can disappear briefly during rotation; keep polling.
+                let Ok(lines) = read_new_lines(path, state) else {
+                    continue;
+                };
+
+                for (line_number, text) in lines {
+                    if regex.as_ref().is_some_and(|r| !r.is_match(&text)) {
+                        continue;
+                    }
+
+                    let note_text = snippet_note_text(path, line_number, &text);
+
+                    if record {
+                        let _ = emit_to_main(
+                            &app,
+                            NOTE_CAPTURED_EVENT,
+                            Note {
+                                note_type: "snippet".to_string(),
+                                text: note_text.clone(),
+                                timestamp: Some(Local::now().timestamp_millis()),
+                                timestamp_iso: None,
+                            },
+                        );
+                    }
+
+                    let _ = emit_to_main(
+                        &app,
+                        LOG_LINE_EVENT,
+                        LogLineMatch {
+                            watch_id: id.clone(),
+                            source: path.to_string_lossy().to_string(),
+                            line_number,
+                            text,
+                            note_text,
+                        },
+                    );
+                }
+            }
+
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+
+    Ok(watch_id)
+}
+
+/// Stops a running log watch. Unknown ids are ignored.
+#[tauri::command]
+pub(crate) fn stop_log_watch(
+    watchers: tauri::State<'_, LogWatchers>,
+    watch_id: String,
+) -> Result<(), String> {
+    if let Some(stop) = watchers
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&watch_id)
+    {
+        stop.store(true, Ordering::Relaxed);
+    }
+
+    Ok(())
+}
+
+/// Returns the last `lines` lines of a log file (default 50), ready to record as a snippet.
+#[tauri::command]
+pub(crate) fn tail_log_file(path: String, lines: Option<usize>) -> Result<LogExcerpt, String> {
+    let count = lines.unwrap_or(DEFAULT_TAIL_LINES).clamp(1, MAX_TAIL_LINES);
+    let source = PathBuf::from(&path);
+
+    let (first_line, tail) = last_lines(&source, count)?;
+    if tail.is_empty() {
+        return Err(format!("Log file is empty: {}", path));
+    }
+
+    let text = tail.join("\n");
+    let last_line = first_line + tail.len() as u64 - 1;
+
+    Ok(LogExcerpt {
+        note_text: snippet_note_text(&source, first_line, &text),
+        source: path,
+        first_line,
+        last_line,
+        text,
+    })
+}
+
+/// Reads the last `count` lines of a file, returning the 1-based number of the first one.
+pub(crate) fn last_lines(path: &Path, count: usize) -> Result<(u64, Vec<String>), String> {
+    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut reader = std::io::BufReader::new(file);
+
+    let mut window: VecDeque<String> = VecDeque::with_capacity(count);
+    let mut total = 0u64;
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        if reader
+            .read_until(b'\n', &mut buf)
+            .map_err(|e| e.to_string())?
+            == 0
+        {
+            break;
+        }
+
+        total += 1;
+        if window.len() == count {
+            window.pop_front();
+        }
+        window.push_back(
+            String::from_utf8_lossy(&buf)
+                .trim_end_matches(['\n', '\r'])
+                .to_string(),
+        );
+    }
+
+    let first_line = total + 1 - window.len() as u64;
+    Ok((first_line, window.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::test_support::TempDir;
+    use std::io::Write;
+
+    /// A log file with `contents` in its own test folder, removed with the returned [`TempDir`].
+    fn temp_log(name: &str, contents: &str) -> (TempDir, PathBuf) {
+        let dir = TempDir::new("logs");
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        (dir, path)
+    }
+
+    fn append(path: &Path, contents: &str) {
+        let mut file = std::fs::OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn only_lines_written_after_start_are_read() {
+        let (_dir, path) = temp_log("app.log", "old 1\nold 2\n");
+        let mut state = TailState::at_end_of(&path).unwrap();
+        assert!(read_new_lines(&path, &mut state).unwrap().is_empty());
+
+        append(&path, "INFO ok\r\nERROR boom\n");
+
+        assert_eq!(
+            read_new_lines(&path, &mut state).unwrap(),
+            vec![(3, "INFO ok".to_string()), (4, "ERROR boom".to_string())]
+        );
+    }
+
+    #[test]
+    fn partial_lines_wait_for_their_newline() {
+        let (_dir, path) = temp_log("partial.log", "");
+        let mut state = TailState::at_end_of(&path).unwrap();
+
+        append(&path, "Exception in ");
+        assert!(read_new_lines(&path, &mut state).unwrap().is_empty());
+
+        append(&path, "thread main\nnext");
+        assert_eq!(
+            read_new_lines(&path, &mut state).unwrap(),
+            vec![(1, "Exception in thread main".to_string())]
+        );
+    }
+
+    #[test]
+    fn characters_split_between_reads_are_decoded_whole() {
+        let (_dir, path) = temp_log("utf8.log", "");
+        let mut state = TailState::at_end_of(&path).unwrap();
+
+        let line = "Zahlung fehlgeschlagen: Gebühr ✗\n".as_bytes();
+        let split = line.len() - 3; // inside the three bytes of "✗"
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(&line[..split]).unwrap();
+        assert!(read_new_lines(&path, &mut state).unwrap().is_empty());
+
+        file.write_all(&line[split..]).unwrap();
+        assert_eq!(
+            read_new_lines(&path, &mut state).unwrap(),
+            vec![(1, "Zahlung fehlgeschlagen: Gebühr ✗".to_string())]
+        );
+    }
+
+    #[test]
+    fn truncated_file_is_read_from_the_top() {
+        let (_dir, path) = temp_log("rotated.log", "a\nb\nc\n");
+        let mut state = TailState::at_end_of(&path).unwrap();
+
+        std::fs::write(&path, "new\n").unwrap();
+
+        assert_eq!(
+            read_new_lines(&path, &mut state).unwrap(),
+            vec![(1, "new".to_string())]
+        );
+    }
+
+    #[test]
+    fn last_lines_reports_first_line_number() {
+        let (_dir, path) = temp_log("tail.log", "one\ntwo\nthree\nfour\n");
+
+        let (first, lines) = last_lines(&path, 2).unwrap();
+        assert_eq!(first, 3);
+        assert_eq!(lines, vec!["three", "four"]);
+
+        let (first, lines) = last_lines(&path, 10).unwrap();
+        assert_eq!(first, 1);
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn snippet_note_text_names_file_and_line() {
+        assert_eq!(
+            snippet_note_text(Path::new("/var/log/app/server.log"), 42, "ERROR boom"),
+            "server.log:42\nERROR boom"
+        );
+    }
+}
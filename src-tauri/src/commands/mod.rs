@@ -1,3 +1,37 @@
+pub(crate) mod activity;
 pub(crate) mod app;
+pub(crate) mod attachments;
 pub(crate) mod capture;
+pub(crate) mod charters;
+pub(crate) mod diff;
 pub(crate) mod export;
+pub(crate) mod forge;
+pub(crate) mod history;
+pub(crate) mod http;
+pub(crate) mod jira;
+pub(crate) mod links;
+pub(crate) mod logs;
+pub(crate) mod recording;
+pub(crate) mod secrets;
+pub(crate) mod tags;
+pub(crate) mod timer;
+
+/// Event carrying a backend-generated `Note` the main window should add to the session.
+pub(crate) const NOTE_CAPTURED_EVENT: &str = "session-note-captured";
+
+/// Emits an event to the main window, falling back to a global emit when it is missing.
+pub(crate) fn emit_to_main<S: serde::Serialize + Clone>(
+    app: &tauri::AppHandle,
+    event: &str,
+    payload: S,
+) -> Result<(), String> {
+    use tauri::{Emitter, Manager};
+
+    if let Some(main) = app.get_webview_window("main") {
+        main.emit(event, payload)
+            .map_err(|e: tauri::Error| e.to_string())
+    } else {
+        app.emit(event, payload)
+            .map_err(|e: tauri::Error| e.to_string())
+    }
+}
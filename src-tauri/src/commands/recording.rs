@@ -0,0 +1,365 @@
+use crate::models::{BurstPlan, BurstStatus};
+use chrono::Local;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_FPS: u32 = 5;
+const MAX_FPS: u32 = 15;
+const DEFAULT_MAX_SECONDS: u32 = 10;
+const MAX_SECONDS: u32 = 30;
+
+/// Frames wider than this are downscaled before encoding so recordings stay shareable.
+const MAX_FRAME_WIDTH: u32 = 1280;
+
+/// Browsers clamp very short GIF delays (< 20ms) to 100ms, so never go below this.
+const MIN_FRAME_DELAY_MS: u32 = 20;
+
+/// Output container for an encoded burst recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AnimationFormat {
+    Gif,
+    Apng,
+}
+
+impl AnimationFormat {
+    fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value.map(|v| v.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("gif") => Ok(Self::Gif),
+            Some("apng") | Some("png") => Ok(Self::Apng),
+            Some(other) => Err(format!("Unsupported recording format: {}", other)),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Gif => "gif",
+            Self::Apng => "png",
+        }
+    }
+}
+
+struct BurstFrame {
+    path: PathBuf,
+    offset: Duration,
+}
+
+struct Burst {
+    fps: u32,
+    max_frames: usize,
+    limit: Duration,
+    started: Instant,
+    staging_dir: PathBuf,
+    frames: Vec<BurstFrame>,
+}
+
+impl Burst {
+    fn is_done(&self) -> bool {
+        self.frames.len() >= self.max_frames || self.started.elapsed() >= self.limit
+    }
+
+    fn status(&self) -> BurstStatus {
+        BurstStatus {
+            frames: self.frames.len(),
+            done: self.is_done(),
+        }
+    }
+}
+
+/// Managed state holding the burst recording currently in progress (if any).
+#[derive(Default)]
+pub(crate) struct BurstRecorder(Mutex<Option<Burst>>);
+
+/// Starts a short burst recording and returns the capture cadence the frontend should follow.
+///
+/// Frames are grabbed by the frontend through the screenshot plugin and handed over with
+/// `add_burst_frame`, so capture stays on the same permission-checked path as screenshots.
+#[tauri::command]
+pub(crate) fn start_burst_recording(
+    recorder: tauri::State<'_, BurstRecorder>,
+    fps: Option<u32>,
+    max_seconds: Option<u32>,
+) -> Result<BurstPlan, String> {
+    let fps = fps.unwrap_or(DEFAULT_FPS).clamp(1, MAX_FPS);
+    let max_seconds = max_seconds
+        .unwrap_or(DEFAULT_MAX_SECONDS)
+        .clamp(1, MAX_SECONDS);
+    let max_frames = (fps * max_seconds) as usize;
+
+    let mut guard = recorder.0.lock().map_err(|e| e.to_string())?;
+    if guard.is_some() {
+        return Err("A burst recording is already in progress.".to_string());
+    }
+
+    let millis = Local::now().timestamp_millis();
+    let staging_dir = std::env::temp_dir()
+        .join("rapid-reporter")
+        .join(format!("burst-{}", millis));
+    std::fs::create_dir_all(&staging_dir).map_err(|e| e.to_string())?;
+
+    *guard = Some(Burst {
+        fps,
+        max_frames,
+        limit: Duration::from_secs(max_seconds as u64),
+        started: Instant::now(),
+        staging_dir,
+        frames: Vec::new(),
+    });
+
+    Ok(BurstPlan {
+        fps,
+        interval_ms: 1000 / fps as u64,
+        max_seconds,
+        max_frames,
+    })
+}
+
+/// Adds a captured frame to the running burst.
+///
+/// The screenshot plugin reuses file names, so each frame is copied into the burst staging
+/// folder straight away. Frames arriving after the limit is reached are ignored.
+#[tauri::command]
+pub(crate) fn add_burst_frame(
+    recorder: tauri::State<'_, BurstRecorder>,
+    path: String,
+) -> Result<BurstStatus, String> {
+    let mut guard = recorder.0.lock().map_err(|e| e.to_string())?;
+    let burst = guard
+        .as_mut()
+        .ok_or_else(|| "No burst recording is in progress.".to_string())?;
+
+    if burst.is_done() {
+        return Ok(burst.status());
+    }
+
+    let offset = burst.started.elapsed();
+    let dst = burst
+        .staging_dir
+        .join(format!("frame-{:04}.png", burst.frames.len() + 1));
+    std::fs::copy(&path, &dst).map_err(|e| e.to_string())?;
+
+    burst.frames.push(BurstFrame { path: dst, offset });
+
+    Ok(burst.status())
+}
+
+/// Stops the running burst and encodes its frames into an animated GIF (default) or APNG.
+///
+/// Returns the absolute path of the animation, which the frontend records as a
+/// `recording` note so the exporters embed it like a screenshot.
+#[tauri::command]
+pub(crate) async fn finish_burst_recording(
+    recorder: tauri::State<'_, BurstRecorder>,
+    format: Option<String>,
+) -> Result<String, String> {
+    let format = AnimationFormat::parse(format.as_deref())?;
+
+    let burst = recorder
+        .0
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .ok_or_else(|| "No burst recording is in progress.".to_string())?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let result = encode_burst(&burst, format);
+        let _ = std::fs::remove_dir_all(&burst.staging_dir);
+        result
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Discards the running burst (if any) and its staged frames.
+#[tauri::command]
+pub(crate) fn cancel_burst_recording(
+    recorder: tauri::State<'_, BurstRecorder>,
+) -> Result<(), String> {
+    if let Some(burst) = recorder.0.lock().map_err(|e| e.to_string())?.take() {
+        let _ = std::fs::remove_dir_all(&burst.staging_dir);
+    }
+
+    Ok(())
+}
+
+fn encode_burst(burst: &Burst, format: AnimationFormat) -> Result<String, String> {
+    if burst.frames.is_empty() {
+        return Err("The burst recording has no frames.".to_string());
+    }
+
+    let nominal_delay_ms = 1000 / burst.fps;
+    let mut frames = Vec::with_capacity(burst.frames.len());
+
+    for (i, frame) in burst.frames.iter().enumerate() {
+        // Use the real gap to the next frame so playback speed matches what was captured.
+        let delay_ms = match burst.frames.get(i + 1) {
+            Some(next) => next.offset.saturating_sub(frame.offset).as_millis() as u32,
+            None => nominal_delay_ms,
+        };
+
+        let img = image::open(&frame.path)
+            .map_err(|e| e.to_string())?
+            .to_rgba8();
+        frames.push((img, delay_ms));
+    }
+
+    let out_dir = std::env::temp_dir().join("rapid-reporter");
+    std::fs::create_dir_all(&out_dir).map_err(|e| e.to_string())?;
+
+    let millis = Local::now().timestamp_millis();
+    let out_path = out_dir.join(format!("recording-{}.{}", millis, format.extension()));
+
+    encode_animation(frames, format, &out_path)?;
+
+    Ok(out_path.to_string_lossy().to_string())
+}
+
+/// Encodes RGBA frames (with per-frame delays in milliseconds) into an animation file.
+///
+/// All frames are scaled to the size of the first one (capped at `MAX_FRAME_WIDTH`).
+pub(crate) fn encode_animation(
+    frames: Vec<(image::RgbaImage, u32)>,
+    format: AnimationFormat,
+    out_path: &Path,
+) -> Result<(), String> {
+    use image::imageops::FilterType;
+
+    let (first_w, first_h) = frames
+        .first()
+        .map(|(img, _)| img.dimensions())
+        .ok_or_else(|| "Cannot encode an animation without frames.".to_string())?;
+
+    let (width, height) = if first_w > MAX_FRAME_WIDTH {
+        let scaled_h = (first_h as f64 * MAX_FRAME_WIDTH as f64 / first_w as f64).round();
+        (MAX_FRAME_WIDTH, (scaled_h as u32).max(1))
+    } else {
+        (first_w, first_h)
+    };
+
+    let frames: Vec<(image::RgbaImage, u32)> = frames
+        .into_iter()
+        .map(|(img, delay_ms)| {
+            let img = if img.dimensions() == (width, height) {
+                img
+            } else {
+                image::imageops::resize(&img, width, height, FilterType::Triangle)
+            };
+            (img, delay_ms.max(MIN_FRAME_DELAY_MS))
+        })
+        .collect();
+
+    let file = std::fs::File::create(out_path).map_err(|e| e.to_string())?;
+    let writer = std::io::BufWriter::new(file);
+
+    match format {
+        AnimationFormat::Gif => {
+            use image::codecs::gif::{GifEncoder, Repeat};
+            use image::{Delay, Frame};
+
+            let mut encoder = GifEncoder::new_with_speed(writer, 10);
+            encoder
+                .set_repeat(Repeat::Infinite)
+                .map_err(|e| e.to_string())?;
+
+            let gif_frames = frames.into_iter().map(|(img, delay_ms)| {
+                Frame::from_parts(img, 0, 0, Delay::from_numer_denom_ms(delay_ms, 1))
+            });
+            encoder
+                .encode_frames(gif_frames)
+                .map_err(|e| e.to_string())?;
+        }
+        AnimationFormat::Apng => {
+            // `image` only decodes APNG, so frames are written with its `png` backend directly.
+            let mut encoder = png::Encoder::new(writer, width, height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder
+                .set_animated(frames.len() as u32, 0)
+                .map_err(|e| e.to_string())?;
+
+            let mut png_writer = encoder.write_header().map_err(|e| e.to_string())?;
+            for (img, delay_ms) in &frames {
+                png_writer
+                    .set_frame_delay((*delay_ms).min(u16::MAX as u32) as u16, 1000)
+                    .map_err(|e| e.to_string())?;
+                png_writer
+                    .write_image_data(img.as_raw())
+                    .map_err(|e| e.to_string())?;
+            }
+            png_writer.finish().map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::export::test_support::TempDir;
+    use image::{AnimationDecoder, Rgba, RgbaImage};
+
+    fn solid(width: u32, height: u32, colour: [u8; 4]) -> RgbaImage {
+        RgbaImage::from_pixel(width, height, Rgba(colour))
+    }
+
+    #[test]
+    fn format_defaults_to_gif_and_rejects_unknown() {
+        assert_eq!(AnimationFormat::parse(None).unwrap(), AnimationFormat::Gif);
+        assert_eq!(
+            AnimationFormat::parse(Some("APNG")).unwrap(),
+            AnimationFormat::Apng
+        );
+        assert!(AnimationFormat::parse(Some("mp4")).is_err());
+    }
+
+    #[test]
+    fn gif_keeps_every_frame_and_normalises_size() {
+        let dir = TempDir::new("burst-gif");
+        let out = dir.join("burst.gif");
+        let frames = vec![
+            (solid(40, 20, [255, 0, 0, 255]), 200),
+            (solid(80, 40, [0, 255, 0, 255]), 5),
+            (solid(40, 20, [0, 0, 255, 255]), 200),
+        ];
+
+        encode_animation(frames, AnimationFormat::Gif, &out).unwrap();
+
+        let file = std::io::BufReader::new(std::fs::File::open(&out).unwrap());
+        let decoded = image::codecs::gif::GifDecoder::new(file)
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+
+        assert_eq!(decoded.len(), 3);
+        assert!(decoded.iter().all(|f| f.buffer().dimensions() == (40, 20)));
+
+        let (numer, denom) = decoded[1].delay().numer_denom_ms();
+        assert!(numer / denom >= MIN_FRAME_DELAY_MS);
+    }
+
+    #[test]
+    fn apng_is_animated_png() {
+        let dir = TempDir::new("burst-apng");
+        let out = dir.join("burst.png");
+        let frames = vec![
+            (solid(16, 16, [255, 0, 0, 255]), 100),
+            (solid(16, 16, [0, 255, 0, 255]), 100),
+        ];
+
+        encode_animation(frames, AnimationFormat::Apng, &out).unwrap();
+
+        let file = std::io::BufReader::new(std::fs::File::open(&out).unwrap());
+        let decoder = image::codecs::png::PngDecoder::new(file).unwrap();
+        assert!(decoder.is_apng().unwrap());
+
+        let decoded = decoder
+            .apng()
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(decoded.len(), 2);
+    }
+}
@@ -0,0 +1,56 @@
+/// Shown in place of a saved token; saving it back keeps the saved token.
+pub(crate) const REDACTED_TOKEN: &str = "********";
+
+/// Keychain service the integration tokens are stored under.
+const KEYCHAIN_SERVICE: &str = "rapid-reporter";
+
+/// `token` as returned to the frontend: [`REDACTED_TOKEN`] when one is set.
+pub(crate) fn redact(token: &str) -> String {
+    if token.trim().is_empty() {
+        String::new()
+    } else {
+        REDACTED_TOKEN.to_string()
+    }
+}
+
+/// The token to save: `token`, or the `saved` one when the settings come back with an empty
+/// or redacted token.
+pub(crate) fn keep_saved(token: &str, saved: Option<&str>) -> String {
+    let token = token.trim();
+    if token.is_empty() || token == REDACTED_TOKEN {
+        saved.unwrap_or_default().to_string()
+    } else {
+        token.to_string()
+    }
+}
+
+/// Stores `token` in the OS keychain under `account`. Returns `false` when no keychain is
+/// available, in which case the caller keeps the token in its settings file.
+pub(crate) fn store_token(account: &str, token: &str) -> bool {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account)
+        .and_then(|entry| entry.set_password(token))
+        .is_ok()
+}
+
+/// The token stored in the OS keychain under `account`, if any.
+pub(crate) fn load_token(account: &str) -> Option<String> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account)
+        .and_then(|entry| entry.get_password())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacted_or_empty_tokens_keep_the_saved_one() {
+        assert_eq!(redact("s3cret"), REDACTED_TOKEN);
+        assert_eq!(redact(""), "");
+
+        assert_eq!(keep_saved(REDACTED_TOKEN, Some("s3cret")), "s3cret");
+        assert_eq!(keep_saved(" ", Some("s3cret")), "s3cret");
+        assert_eq!(keep_saved("n3w ", Some("s3cret")), "n3w");
+        assert_eq!(keep_saved(REDACTED_TOKEN, None), "");
+    }
+}
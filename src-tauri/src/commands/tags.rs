@@ -0,0 +1,141 @@
+use crate::models::{Note, TagEntry};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// `#tag` / `@person` tokens: must start with a letter and follow whitespace or punctuation,
+/// so ticket numbers (`#456`), e-mail addresses and URL fragments are not picked up.
+fn token_regex() -> &'static Regex {
+    static TOKEN: OnceLock<Regex> = OnceLock::new();
+    TOKEN.get_or_init(|| {
+        Regex::new(r"(?:^|[^\w/&#@])([#@])(\p{L}[\p{L}\p{N}_-]*)").expect("valid token regex")
+    })
+}
+
+/// Tags (lowercased, without `#`) and mentions (without `@`) found in a note, each listed once.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct NoteTokens {
+    pub(crate) tags: Vec<String>,
+    pub(crate) mentions: Vec<String>,
+}
+
+pub(crate) fn parse_tokens(text: &str) -> NoteTokens {
+    let mut tokens = NoteTokens::default();
+
+    for caps in token_regex().captures_iter(text) {
+        let name = caps[2].trim_end_matches(['-', '_']);
+        let (list, name) = if &caps[1] == "#" {
+            (&mut tokens.tags, name.to_lowercase())
+        } else {
+            (&mut tokens.mentions, name.to_string())
+        };
+
+        if !list.iter().any(|n| n.eq_ignore_ascii_case(&name)) {
+            list.push(name);
+        }
+    }
+
+    tokens
+}
+
+/// Tags and mentions across `notes` (chronological), each with the indices of the notes
+/// carrying it, sorted by name. Only notes for which `include` returns true are scanned.
+pub(crate) fn index_notes(
+    notes: &[&Note],
+    include: impl Fn(&Note) -> bool,
+) -> (Vec<TagEntry>, Vec<TagEntry>) {
+    let mut tags: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut mentions: BTreeMap<String, (String, Vec<usize>)> = BTreeMap::new();
+
+    for (index, note) in notes.iter().enumerate() {
+        if !include(note) {
+            continue;
+        }
+
+        let tokens = parse_tokens(&note.text);
+        for tag in tokens.tags {
+            tags.entry(tag).or_default().push(index);
+        }
+        // Mentions are grouped case-insensitively but keep the first spelling.
+        for mention in tokens.mentions {
+            mentions
+                .entry(mention.to_lowercase())
+                .or_insert_with(|| (mention, Vec::new()))
+                .1
+                .push(index);
+        }
+    }
+
+    let tags = tags
+        .into_iter()
+        .map(|(name, notes)| TagEntry { name, notes })
+        .collect();
+    let mentions = mentions
+        .into_values()
+        .map(|(name, notes)| TagEntry { name, notes })
+        .collect();
+
+    (tags, mentions)
+}
+
+/// Normalises a tag filter such as `#Checkout` or ` checkout ` to `checkout`.
+pub(crate) fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').trim();
+    (!tag.is_empty()).then(|| tag.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_and_mentions_need_a_leading_letter_and_a_boundary() {
+        let tokens = parse_tokens(
+            "#Checkout fails for @maria (see #456, mail bob@example.com, \
+             https://shop.test/#/cart, #payments-, #checkout again) #über",
+        );
+
+        assert_eq!(tokens.tags, vec!["checkout", "payments", "über"]);
+        assert_eq!(tokens.mentions, vec!["maria"]);
+    }
+
+    #[test]
+    fn index_groups_notes_per_tag_and_mention() {
+        let note = |text: &str| Note {
+            note_type: "test".to_string(),
+            text: text.to_string(),
+            timestamp: None,
+            timestamp_iso: None,
+        };
+        let notes = [
+            note("Basket #cart with @Maria"),
+            note("Snippet #include <cart>"),
+            note("Pay #checkout @maria"),
+            note("Empty #Cart"),
+        ];
+        let refs: Vec<&Note> = notes.iter().collect();
+
+        let (tags, mentions) = index_notes(&refs, |n| !n.text.starts_with("Snippet"));
+
+        assert_eq!(
+            tags,
+            vec![
+                TagEntry {
+                    name: "cart".to_string(),
+                    notes: vec![0, 3],
+                },
+                TagEntry {
+                    name: "checkout".to_string(),
+                    notes: vec![2],
+                },
+            ]
+        );
+        assert_eq!(
+            mentions,
+            vec![TagEntry {
+                name: "Maria".to_string(),
+                notes: vec![0, 2],
+            }]
+        );
+    }
+}
@@ -0,0 +1,425 @@
+use crate::commands::emit_to_main;
+use crate::models::{ClockReminder, ClockStatus, PausedInterval, ReminderSpec, SessionTiming};
+use chrono::Local;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Event emitted once per second with the current `ClockStatus`.
+pub(crate) const CLOCK_TICK_EVENT: &str = "session-clock-tick";
+
+/// Event emitted when a configured reminder point is reached.
+pub(crate) const CLOCK_REMINDER_EVENT: &str = "session-clock-reminder";
+
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Reminders used when the frontend does not configure its own.
+fn default_reminders() -> Vec<ReminderSpec> {
+    vec![
+        ReminderSpec::Percent { percent: 50 },
+        ReminderSpec::MinutesLeft { minutes: 10 },
+        ReminderSpec::Overrun,
+    ]
+}
+
+/// Monotonic session clock. Wall-clock times are only kept for reporting pauses.
+pub(crate) struct ClockState {
+    session_started_at: i64,
+    planned: Option<Duration>,
+    reminders: Vec<(ReminderSpec, bool)>,
+    started: Instant,
+    paused_since: Option<(Instant, i64)>,
+    paused_total: Duration,
+    paused_intervals: Vec<PausedInterval>,
+    stop: Arc<AtomicBool>,
+}
+
+impl ClockState {
+    pub(crate) fn new(
+        session_started_at: i64,
+        planned_minutes: Option<i64>,
+        reminders: Vec<ReminderSpec>,
+        now: Instant,
+    ) -> Self {
+        let planned = planned_minutes
+            .filter(|m| *m > 0)
+            .map(|m| Duration::from_secs(m as u64 * 60));
+
+        ClockState {
+            session_started_at,
+            planned,
+            reminders: reminders.into_iter().map(|r| (r, false)).collect(),
+            started: now,
+            paused_since: None,
+            paused_total: Duration::ZERO,
+            paused_intervals: Vec::new(),
+            stop: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Active session time at `now`, excluding paused periods.
+    pub(crate) fn elapsed(&self, now: Instant) -> Duration {
+        let paused_now = self
+            .paused_since
+            .map(|(since, _)| now.saturating_duration_since(since))
+            .unwrap_or_default();
+
+        now.saturating_duration_since(self.started)
+            .saturating_sub(self.paused_total + paused_now)
+    }
+
+    pub(crate) fn pause(&mut self, now: Instant, wall_millis: i64) -> bool {
+        if self.paused_since.is_some() {
+            return false;
+        }
+        self.paused_since = Some((now, wall_millis));
+        true
+    }
+
+    pub(crate) fn resume(&mut self, now: Instant, wall_millis: i64) -> bool {
+        let Some((since, since_wall)) = self.paused_since.take() else {
+            return false;
+        };
+
+        self.paused_total += now.saturating_duration_since(since);
+        self.paused_intervals.push(PausedInterval {
+            started_at: since_wall,
+            ended_at: wall_millis,
+            started_at_iso: None,
+            ended_at_iso: None,
+        });
+        true
+    }
+
+    pub(crate) fn status(&self, now: Instant) -> ClockStatus {
+        let elapsed = self.elapsed(now).as_secs();
+        let planned = self.planned.map(|p| p.as_secs());
+        let remaining = planned.map(|p| p as i64 - elapsed as i64);
+
+        ClockStatus {
+            started_at: self.session_started_at,
+            elapsed_seconds: elapsed,
+            planned_seconds: planned,
+            remaining_seconds: remaining,
+            paused: self.paused_since.is_some(),
+            overrun: remaining.is_some_and(|r| r < 0),
+        }
+    }
+
+    /// Returns reminders that became due by `now`; each reminder fires at most once.
+    ///
+    /// Sessions without a planned duration have nothing to remind about.
+    pub(crate) fn due_reminders(&mut self, now: Instant) -> Vec<ClockReminder> {
+        let Some(planned) = self.planned else {
+            return Vec::new();
+        };
+        let elapsed = self.elapsed(now);
+
+        let mut due = Vec::new();
+        for (spec, fired) in self.reminders.iter_mut() {
+            if *fired {
+                continue;
+            }
+
+            let (kind, message, reached) = match spec {
+                ReminderSpec::Percent { percent } => (
+                    "percent",
+                    format!("{}% of the session time has been used", percent),
+                    elapsed >= planned.mul_f64(*percent as f64 / 100.0),
+                ),
+                ReminderSpec::MinutesLeft { minutes } => {
+                    let left = Duration::from_secs(*minutes as u64 * 60);
+                    (
+                        "minutesLeft",
+                        match minutes {
+                            1 => "1 minute left".to_string(),
+                            n => format!("{} minutes left", n),
+                        },
+                        // Skip "N minutes left" when the session is shorter than N minutes.
+                        left < planned && elapsed >= planned - left,
+                    )
+                }
+                ReminderSpec::Overrun => (
+                    "overrun",
+                    "Planned session time is up".to_string(),
+                    elapsed > planned,
+                ),
+            };
+
+            if reached {
+                *fired = true;
+                due.push(ClockReminder {
+                    kind: kind.to_string(),
+                    message,
+                    elapsed_seconds: elapsed.as_secs(),
+                });
+            }
+        }
+
+        due
+    }
+
+    /// Planned vs actual timing for the report. An open pause is closed at `now`.
+    pub(crate) fn timing(&self, now: Instant, wall_millis: i64) -> SessionTiming {
+        let mut paused_intervals = self.paused_intervals.clone();
+        if let Some((_, since_wall)) = self.paused_since {
+            paused_intervals.push(PausedInterval {
+                started_at: since_wall,
+                ended_at: wall_millis,
+                started_at_iso: None,
+                ended_at_iso: None,
+            });
+        }
+
+        SessionTiming {
+            planned_minutes: self.planned.map(|p| (p.as_secs() / 60) as i64),
+            actual_seconds: self.elapsed(now).as_secs(),
+            paused_intervals,
+        }
+    }
+}
+
+/// Managed state holding the clock of the running session (if any), plus the final timing of
+/// the last stopped one so a session can still be exported after its clock was stopped.
+#[derive(Default)]
+pub(crate) struct SessionClock {
+    running: Mutex<Option<ClockState>>,
+    /// `started_at` of the stopped session and its final timing.
+    stopped: Mutex<Option<(i64, SessionTiming)>>,
+}
+
+impl SessionClock {
+    /// Timing for the session that started at `started_at`, if the running clock or the last
+    /// stopped one belongs to it.
+    pub(crate) fn timing_for(&self, started_at: i64) -> Option<SessionTiming> {
+        let running = self.running.lock().ok()?;
+        if let Some(clock) = running
+            .as_ref()
+            .filter(|clock| clock.session_started_at == started_at)
+        {
+            return Some(clock.timing(Instant::now(), Local::now().timestamp_millis()));
+        }
+
+        let stopped = self.stopped.lock().ok()?;
+        stopped
+            .as_ref()
+            .filter(|(stopped_at, _)| *stopped_at == started_at)
+            .map(|(_, timing)| timing.clone())
+    }
+
+    /// Stops the running clock and keeps its final timing for the export.
+    fn stop(&self, now: Instant, wall_millis: i64) -> Result<Option<SessionTiming>, String> {
+        let Some(state) = self.running.lock().map_err(|e| e.to_string())?.take() else {
+            return Ok(None);
+        };
+        state.stop.store(true, Ordering::Relaxed);
+
+        let timing = state.timing(now, wall_millis);
+        *self.stopped.lock().map_err(|e| e.to_string())? =
+            Some((state.session_started_at, timing.clone()));
+        Ok(Some(timing))
+    }
+}
+
+/// Starts the backend session clock, replacing any previous one.
+///
+/// The clock emits `session-clock-tick` every second and `session-clock-reminder` at the
+/// configured points (default: 50% used, 10 minutes left, overrun). `started_at` is the
+/// session's epoch-ms start and identifies the session in later calls.
+#[tauri::command]
+pub(crate) fn start_session_clock(
+    app: tauri::AppHandle,
+    clock: tauri::State<'_, SessionClock>,
+    started_at: i64,
+    planned_minutes: Option<i64>,
+    reminders: Option<Vec<ReminderSpec>>,
+) -> Result<ClockStatus, String> {
+    let state = ClockState::new(
+        started_at,
+        planned_minutes,
+        reminders.unwrap_or_else(default_reminders),
+        Instant::now(),
+    );
+    let status = state.status(Instant::now());
+    let stop = state.stop.clone();
+
+    if let Some(previous) = clock
+        .running
+        .lock()
+        .map_err(|e| e.to_string())?
+        .replace(state)
+    {
+        previous.stop.store(true, Ordering::Relaxed);
+    }
+
+    std::thread::spawn(move || {
+        use tauri::Manager;
+
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(TICK_INTERVAL);
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+
+            let clock = app.state::<SessionClock>();
+            let (status, reminders) = {
+                let Ok(mut guard) = clock.running.lock() else {
+                    break;
+                };
+                let Some(state) = guard.as_mut() else {
+                    break;
+                };
+                let now = Instant::now();
+                (state.status(now), state.due_reminders(now))
+            };
+
+            let _ = emit_to_main(&app, CLOCK_TICK_EVENT, status);
+            for reminder in reminders {
+                let _ = emit_to_main(&app, CLOCK_REMINDER_EVENT, reminder);
+            }
+        }
+    });
+
+    Ok(status)
+}
+
+/// Pauses the session clock. Pausing twice is a no-op.
+#[tauri::command]
+pub(crate) fn pause_session_clock(
+    clock: tauri::State<'_, SessionClock>,
+) -> Result<ClockStatus, String> {
+    with_clock(&clock, |state, now| {
+        state.pause(now, Local::now().timestamp_millis());
+    })
+}
+
+/// Resumes a paused session clock and records the paused interval.
+#[tauri::command]
+pub(crate) fn resume_session_clock(
+    clock: tauri::State<'_, SessionClock>,
+) -> Result<ClockStatus, String> {
+    with_clock(&clock, |state, now| {
+        state.resume(now, Local::now().timestamp_millis());
+    })
+}
+
+/// Returns the current clock status, or `None` when no session clock is running.
+#[tauri::command]
+pub(crate) fn session_clock_status(
+    clock: tauri::State<'_, SessionClock>,
+) -> Result<Option<ClockStatus>, String> {
+    let guard = clock.running.lock().map_err(|e| e.to_string())?;
+    Ok(guard.as_ref().map(|state| state.status(Instant::now())))
+}
+
+/// Stops the session clock and returns the final planned vs actual timing, which the export
+/// of the same session still picks up.
+#[tauri::command]
+pub(crate) fn stop_session_clock(
+    clock: tauri::State<'_, SessionClock>,
+) -> Result<Option<SessionTiming>, String> {
+    clock.stop(Instant::now(), Local::now().timestamp_millis())
+}
+
+fn with_clock(
+    clock: &SessionClock,
+    f: impl FnOnce(&mut ClockState, Instant),
+) -> Result<ClockStatus, String> {
+    let mut guard = clock.running.lock().map_err(|e| e.to_string())?;
+    let state = guard
+        .as_mut()
+        .ok_or_else(|| "The session clock is not running.".to_string())?;
+
+    let now = Instant::now();
+    f(state, now);
+    Ok(state.status(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIN: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn paused_time_is_excluded_and_recorded() {
+        let t0 = Instant::now();
+        let mut clock = ClockState::new(1, Some(60), Vec::new(), t0);
+
+        assert!(clock.pause(t0 + 10 * MIN, 1_000));
+        assert!(!clock.pause(t0 + 11 * MIN, 2_000));
+        assert_eq!(clock.elapsed(t0 + 15 * MIN), 10 * MIN);
+
+        assert!(clock.resume(t0 + 15 * MIN, 3_000));
+        assert_eq!(clock.elapsed(t0 + 20 * MIN), 15 * MIN);
+
+        let timing = clock.timing(t0 + 20 * MIN, 4_000);
+        assert_eq!(timing.planned_minutes, Some(60));
+        assert_eq!(timing.actual_seconds, 15 * 60);
+        assert_eq!(
+            timing.paused_intervals,
+            vec![PausedInterval {
+                started_at: 1_000,
+                ended_at: 3_000,
+                started_at_iso: None,
+                ended_at_iso: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn stopped_sessions_keep_their_timing_for_the_export() {
+        let t0 = Instant::now();
+        let clock = SessionClock::default();
+        *clock.running.lock().unwrap() = Some(ClockState::new(7, Some(30), Vec::new(), t0));
+
+        let stopped = clock.stop(t0 + 20 * MIN, 2_000).unwrap().unwrap();
+        assert_eq!(stopped.actual_seconds, 20 * 60);
+        assert!(clock.running.lock().unwrap().is_none());
+
+        assert_eq!(clock.timing_for(7), Some(stopped));
+        assert_eq!(clock.timing_for(8), None);
+        assert_eq!(clock.stop(t0 + 21 * MIN, 3_000).unwrap(), None);
+    }
+
+    #[test]
+    fn open_pause_is_closed_in_timing() {
+        let t0 = Instant::now();
+        let mut clock = ClockState::new(1, None, Vec::new(), t0);
+        clock.pause(t0 + MIN, 100);
+
+        let timing = clock.timing(t0 + 5 * MIN, 500);
+        assert_eq!(timing.actual_seconds, 60);
+        assert_eq!(timing.paused_intervals[0].ended_at, 500);
+    }
+
+    #[test]
+    fn default_reminders_fire_once_in_order() {
+        let t0 = Instant::now();
+        let mut clock = ClockState::new(1, Some(30), default_reminders(), t0);
+
+        assert!(clock.due_reminders(t0 + 14 * MIN).is_empty());
+
+        let half = clock.due_reminders(t0 + 15 * MIN);
+        assert_eq!(half.len(), 1);
+        assert_eq!(half[0].kind, "percent");
+        assert!(clock.due_reminders(t0 + 16 * MIN).is_empty());
+
+        let left = clock.due_reminders(t0 + 20 * MIN);
+        assert_eq!(left[0].message, "10 minutes left");
+
+        assert!(clock.due_reminders(t0 + 30 * MIN).is_empty());
+        let over = clock.due_reminders(t0 + 30 * MIN + Duration::from_secs(1));
+        assert_eq!(over[0].kind, "overrun");
+        assert!(clock.status(t0 + 31 * MIN).overrun);
+    }
+
+    #[test]
+    fn unlimited_sessions_have_no_reminders() {
+        let t0 = Instant::now();
+        let mut clock = ClockState::new(1, None, default_reminders(), t0);
+
+        assert!(clock.due_reminders(t0 + 600 * MIN).is_empty());
+        assert_eq!(clock.status(t0).remaining_seconds, None);
+    }
+}
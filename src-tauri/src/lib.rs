@@ -4,21 +4,62 @@ mod models;
 /// Starts the Tauri application and registers all frontend-invokable commands.
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    commands::attachments::clear_staging();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_macos_permissions::init())
         .plugin(tauri_plugin_screenshots::init())
+        .manage(commands::recording::BurstRecorder::default())
+        .manage(commands::logs::LogWatchers::default())
+        .manage(commands::timer::SessionClock::default())
+        .manage(commands::activity::ActivityTracker::default())
         .invoke_handler(tauri::generate_handler![
             commands::app::greet,
             commands::app::app_version,
             commands::app::platform_os,
+            commands::app::collect_environment,
             commands::app::unique_screenshot_copy,
+            commands::attachments::attach_file,
+            commands::charters::list_charters,
+            commands::charters::create_charter,
+            commands::charters::update_charter,
+            commands::charters::delete_charter,
+            commands::charters::use_charter,
             commands::export::export_session_markdown,
+            commands::export::debrief::export_debrief_markdown,
+            commands::history::list_sessions,
+            commands::history::forget_session,
+            commands::jira::jira_settings,
+            commands::jira::save_jira_settings,
+            commands::jira::create_jira_issues,
+            commands::forge::forge_settings,
+            commands::forge::save_forge_settings,
+            commands::forge::create_forge_issues,
+            commands::links::list_link_rules,
+            commands::links::save_link_rules,
+            commands::export::template::list_report_templates,
+            commands::export::template::validate_report_template,
+            commands::logs::start_log_watch,
+            commands::logs::stop_log_watch,
+            commands::logs::tail_log_file,
             commands::capture::open_region_overlay,
             commands::capture::close_region_overlay,
             commands::capture::submit_region_selection,
             commands::capture::crop_screenshot,
-            commands::capture::capture_windows_snip_to_file
+            commands::capture::capture_windows_snip_to_file,
+            commands::diff::diff_screenshots,
+            commands::recording::start_burst_recording,
+            commands::recording::add_burst_frame,
+            commands::recording::finish_burst_recording,
+            commands::recording::cancel_burst_recording,
+            commands::timer::start_session_clock,
+            commands::timer::pause_session_clock,
+            commands::timer::resume_session_clock,
+            commands::timer::session_clock_status,
+            commands::timer::stop_session_clock,
+            commands::activity::set_session_activity,
+            commands::activity::session_activity
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
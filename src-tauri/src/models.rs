@@ -1,17 +1,28 @@
 use serde::{Deserialize, Serialize};
 
 /// A single session note sent from the frontend for export/report generation.
-#[derive(Deserialize)]
+///
+/// Backend-generated notes are sent the other way with the same shape.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct Note {
     pub(crate) text: String,
 
     #[serde(rename = "type")]
     pub(crate) note_type: String,
+
+    /// When the note was taken (epoch ms), if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) timestamp: Option<i64>,
+
+    /// `timestamp` as ISO 8601 with the UTC offset of the report's time zone; filled in for
+    /// `session.json` on export.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) timestamp_iso: Option<String>,
 }
 
 /// Session payload sent by the frontend when exporting a report.
-#[derive(Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct Session {
     #[serde(default)]
@@ -20,6 +31,111 @@ pub(crate) struct Session {
     pub(crate) duration_minutes: Option<i64>,
     pub(crate) started_at: i64,
     pub(crate) notes: Vec<Note>,
+
+    #[serde(default)]
+    pub(crate) environment: Option<Environment>,
+
+    /// The charter library entry the session was started from, if any.
+    #[serde(default)]
+    pub(crate) library_charter: Option<CharterRef>,
+}
+
+/// Reference to a charter library entry, recorded with the session.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CharterRef {
+    pub(crate) id: String,
+    pub(crate) title: String,
+}
+
+/// A reusable charter in the persisted charter library.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LibraryCharter {
+    pub(crate) id: String,
+    pub(crate) title: String,
+    /// "Explore …" — the target area.
+    pub(crate) explore: String,
+    /// "With …" — resources, tools or techniques.
+    #[serde(default)]
+    pub(crate) with: String,
+    /// "To discover …" — the information sought.
+    #[serde(default)]
+    pub(crate) to_discover: String,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+    #[serde(default)]
+    pub(crate) usage_count: u32,
+    pub(crate) created_at: i64,
+    pub(crate) updated_at: i64,
+    #[serde(default)]
+    pub(crate) last_used_at: Option<i64>,
+}
+
+/// Editable fields of a charter library entry, used for create and update.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CharterDraft {
+    pub(crate) title: String,
+    pub(crate) explore: String,
+    #[serde(default)]
+    pub(crate) with: String,
+    #[serde(default)]
+    pub(crate) to_discover: String,
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+}
+
+/// A library charter picked for a new session, with its rendered charter text.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CharterSelection {
+    #[serde(flatten)]
+    pub(crate) charter: CharterRef,
+    pub(crate) text: String,
+}
+
+/// Environment / system-under-test details rendered in the report header.
+///
+/// Collected by `collect_environment` and sent back with any user-supplied key/values.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Environment {
+    #[serde(default)]
+    pub(crate) os: Option<String>,
+    #[serde(default)]
+    pub(crate) os_version: Option<String>,
+    #[serde(default)]
+    pub(crate) arch: Option<String>,
+    #[serde(default)]
+    pub(crate) screens: Vec<ScreenInfo>,
+    #[serde(default)]
+    pub(crate) locale: Option<String>,
+    #[serde(default)]
+    pub(crate) app_version: Option<String>,
+
+    /// User-supplied details such as build number, URL, device or browser, in display order.
+    #[serde(default)]
+    pub(crate) custom: Vec<EnvironmentEntry>,
+}
+
+/// A connected display, in physical pixels.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ScreenInfo {
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) scale_factor: f64,
+}
+
+/// A free-form environment detail entered by the tester.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EnvironmentEntry {
+    pub(crate) key: String,
+    pub(crate) value: String,
 }
 
 /// A selected screen region used for cropping screenshots and overlay events.
@@ -36,3 +152,443 @@ pub(crate) struct RegionSelection {
     #[serde(default)]
     pub(crate) monitor_id: Option<i32>,
 }
+
+/// Burst recording parameters returned to the frontend capture loop.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BurstPlan {
+    pub(crate) fps: u32,
+    pub(crate) interval_ms: u64,
+    pub(crate) max_seconds: u32,
+    pub(crate) max_frames: usize,
+}
+
+/// Progress of an in-flight burst recording, reported after each captured frame.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BurstStatus {
+    pub(crate) frames: usize,
+    pub(crate) done: bool,
+}
+
+/// Bounding box (in image pixels) of an area that changed between two screenshots.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DiffRegion {
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+/// Result of comparing a "before" and "after" screenshot.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ScreenshotDiff {
+    /// Absolute path of the generated diff image.
+    pub(crate) path: String,
+    /// Share of unchanged pixels, from `0.0` (entirely different) to `1.0` (identical).
+    pub(crate) similarity: f64,
+    pub(crate) changed_pixels: u64,
+    pub(crate) size_mismatch: bool,
+    pub(crate) regions: Vec<DiffRegion>,
+}
+
+/// Details of a file snapshotted into the session staging area as evidence.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AttachmentInfo {
+    /// Absolute path of the staged copy; this is what the `attachment` note should hold.
+    pub(crate) path: String,
+    pub(crate) file_name: String,
+    pub(crate) size_bytes: u64,
+    pub(crate) sha256: String,
+}
+
+/// A new log line that matched a watch pattern, pushed to the frontend as it is written.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogLineMatch {
+    pub(crate) watch_id: String,
+    pub(crate) source: String,
+    pub(crate) line_number: u64,
+    pub(crate) text: String,
+    /// Ready-to-commit text for a `snippet` note (source, line number and the line itself).
+    pub(crate) note_text: String,
+}
+
+/// The last lines of a log file, as returned by "grab last N lines".
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LogExcerpt {
+    pub(crate) source: String,
+    pub(crate) first_line: u64,
+    pub(crate) last_line: u64,
+    pub(crate) text: String,
+    pub(crate) note_text: String,
+}
+
+/// A point during the session at which the backend clock emits a reminder event.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub(crate) enum ReminderSpec {
+    /// Fires once this percentage of the planned duration has elapsed.
+    #[serde(rename_all = "camelCase")]
+    Percent { percent: u8 },
+    /// Fires when this many minutes of the planned duration remain.
+    #[serde(rename_all = "camelCase")]
+    MinutesLeft { minutes: u32 },
+    /// Fires when the planned duration is exceeded.
+    Overrun,
+}
+
+/// Snapshot of the backend session clock, emitted every second while a session runs.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ClockStatus {
+    pub(crate) started_at: i64,
+    /// Active (unpaused) time so far.
+    pub(crate) elapsed_seconds: u64,
+    pub(crate) planned_seconds: Option<u64>,
+    /// Negative once the session overruns its planned duration.
+    pub(crate) remaining_seconds: Option<i64>,
+    pub(crate) paused: bool,
+    pub(crate) overrun: bool,
+}
+
+/// A reminder emitted by the backend session clock.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ClockReminder {
+    pub(crate) kind: String,
+    pub(crate) message: String,
+    pub(crate) elapsed_seconds: u64,
+}
+
+/// A period during which the session clock was paused (epoch ms).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PausedInterval {
+    pub(crate) started_at: i64,
+    pub(crate) ended_at: i64,
+    /// `started_at` and `ended_at` as ISO 8601 with the UTC offset of the report's time zone;
+    /// filled in for `session.json` on export.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) started_at_iso: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) ended_at_iso: Option<String>,
+}
+
+/// Planned vs actual timing of a session, as measured by the backend clock.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionTiming {
+    pub(crate) planned_minutes: Option<i64>,
+    /// Active session time, excluding pauses.
+    pub(crate) actual_seconds: u64,
+    #[serde(default)]
+    pub(crate) paused_intervals: Vec<PausedInterval>,
+}
+
+/// SBTM task category the tester is currently spending time on.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum SessionActivity {
+    /// Session setup.
+    Setup,
+    /// Test design and execution.
+    Test,
+    /// Bug investigation and reporting.
+    Bug,
+}
+
+/// The activity currently being tracked by the backend.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ActivityStatus {
+    pub(crate) activity: SessionActivity,
+    pub(crate) on_charter: bool,
+    /// Epoch ms when this activity started.
+    pub(crate) since: i64,
+}
+
+/// Time spent per SBTM task category and on charter vs opportunity work.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TaskBreakdown {
+    pub(crate) setup_seconds: u64,
+    pub(crate) test_seconds: u64,
+    pub(crate) bug_seconds: u64,
+    pub(crate) charter_seconds: u64,
+    pub(crate) opportunity_seconds: u64,
+}
+
+/// Session data saved as `session.json` in every export folder, so exported sessions can be
+/// rolled up into a debrief report later. A bare [`Session`] JSON file also parses as a record.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionRecord {
+    #[serde(flatten)]
+    pub(crate) session: Session,
+
+    #[serde(default)]
+    pub(crate) timing: Option<SessionTiming>,
+
+    #[serde(default)]
+    pub(crate) task_breakdown: Option<TaskBreakdown>,
+
+    /// Tracker issues filed for notes of this session.
+    #[serde(default)]
+    pub(crate) issues: Vec<IssueLink>,
+
+    /// `#tag` tokens found in the notes.
+    #[serde(default)]
+    pub(crate) tags: Vec<TagEntry>,
+
+    /// `@person` tokens found in the notes.
+    #[serde(default)]
+    pub(crate) mentions: Vec<TagEntry>,
+
+    /// Session start as an ISO 8601 timestamp with the UTC offset of the report's time zone,
+    /// e.g. `2025-03-30T03:30:00+02:00`.
+    #[serde(default)]
+    pub(crate) started_at_iso: Option<String>,
+
+    /// Time zone the report was written in: an IANA name such as `Europe/Berlin`, or a UTC
+    /// offset such as `UTC+05:30`.
+    #[serde(default)]
+    pub(crate) timezone: Option<String>,
+}
+
+/// A tag or mention and the notes carrying it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TagEntry {
+    /// Tag (lowercase) or mention name, without the leading `#` / `@`.
+    pub(crate) name: String,
+    /// Indices of the notes in chronological (oldest-first) order.
+    pub(crate) notes: Vec<usize>,
+}
+
+/// An issue filed in an external tracker for one of the session's notes.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct IssueLink {
+    /// Tracker id: `jira`, `github` or `gitlab`.
+    pub(crate) tracker: String,
+    /// Index of the note in chronological (oldest-first) order.
+    pub(crate) note_index: usize,
+    /// Issue key as shown by the tracker, e.g. `QA-123`.
+    pub(crate) key: String,
+    pub(crate) url: String,
+}
+
+/// Order of the notes in an exported report.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum NoteOrder {
+    /// Oldest first.
+    #[default]
+    Chronological,
+    /// Newest first.
+    Reverse,
+    /// Oldest first, followed by a section per note type (bugs, ideas, observations,
+    /// questions, warnings) linked from the Summary.
+    Grouped,
+}
+
+/// Optional extras for `export_session_markdown`. Extra report formats are written next to the
+/// markdown file; one that fails is reported as `<format>Error` without failing the export.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ExportOptions {
+    /// File a Jira issue for every bug note once the report is written. Tracker failures are
+    /// reported as `jiraError` and never fail the export.
+    #[serde(default)]
+    pub(crate) create_jira_issues: bool,
+    /// Report template to render: the built-in `default` layout when unset, the built-in
+    /// `obsidian` flavour (YAML front matter, no raw HTML), or a template from the user's
+    /// templates folder.
+    #[serde(default)]
+    pub(crate) template: Option<String>,
+    /// Language of the report, its copies and the bug drafts (`en`, `de` or `fr`, or a tag
+    /// such as `de-AT`); English when unset.
+    #[serde(default)]
+    pub(crate) locale: Option<String>,
+    /// Time zone of the times in the report, which are shown with their UTC offset: an IANA
+    /// name (`Europe/Berlin`), `UTC` or a UTC offset (`+05:30`); the system zone when unset.
+    #[serde(default)]
+    pub(crate) timezone: Option<String>,
+    /// Order of the notes in the report.
+    #[serde(default)]
+    pub(crate) order: NoteOrder,
+    /// Note types to export (case-insensitive); all types when empty. The type filters only
+    /// shape the report: `session.json` keeps every note.
+    #[serde(default)]
+    pub(crate) include_types: Vec<String>,
+    /// Note types to leave out of the report, e.g. `["test", "snippet"]`.
+    #[serde(default)]
+    pub(crate) exclude_types: Vec<String>,
+    /// Also lay the report out as a PDF, fully offline.
+    #[serde(default)]
+    pub(crate) pdf: bool,
+    /// Also save the report as a Word document (`.docx`).
+    #[serde(default)]
+    pub(crate) docx: bool,
+    /// Also save the report as an OpenDocument text file (`.odt`).
+    #[serde(default)]
+    pub(crate) odt: bool,
+    /// Also save the report in Confluence storage format (`.confluence.xhtml`), with images and
+    /// attachments referenced by file name.
+    #[serde(default)]
+    pub(crate) confluence: bool,
+    /// Also save the report as Jira wiki markup (`.jira.txt`).
+    #[serde(default)]
+    pub(crate) jira_wiki: bool,
+    /// Also save the notes as a CSV table, one row per note.
+    #[serde(default)]
+    pub(crate) csv: bool,
+    /// Also save the notes as an Excel workbook (`.xlsx`).
+    #[serde(default)]
+    pub(crate) xlsx: bool,
+}
+
+/// The report templates available to the exporter, as returned by `list_report_templates`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReportTemplates {
+    /// Folder holding custom `<name>.md.hbs` templates.
+    pub(crate) dir: String,
+    /// Template names, starting with the built-in `default` and `obsidian`.
+    pub(crate) names: Vec<String>,
+}
+
+/// Connection details for the Jira integration, stored in the app config directory.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct JiraSettings {
+    /// Site URL, e.g. `https://example.atlassian.net`.
+    pub(crate) base_url: String,
+    pub(crate) project_key: String,
+    #[serde(default = "default_jira_issue_type")]
+    pub(crate) issue_type: String,
+    /// Account email for Jira Cloud basic auth. Without it the token is sent as a bearer
+    /// token (Jira Server / Data Center personal access tokens).
+    #[serde(default)]
+    pub(crate) email: Option<String>,
+    /// Kept in the OS keychain when there is one, and only ever returned redacted.
+    #[serde(default)]
+    pub(crate) api_token: String,
+}
+
+fn default_jira_issue_type() -> String {
+    "Bug".to_string()
+}
+
+/// Code hosting service for issue creation from session findings.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ForgeKind {
+    Github,
+    Gitlab,
+}
+
+/// Connection details for the GitHub / GitLab integration, stored in the app config directory.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeSettings {
+    pub(crate) kind: ForgeKind,
+    /// API root for self-hosted instances, e.g. `https://github.example.com/api/v3` or
+    /// `https://gitlab.example.com`. Defaults to github.com / gitlab.com.
+    #[serde(default)]
+    pub(crate) api_url: Option<String>,
+    /// `owner/repo` on GitHub, the full project path (`group/subgroup/project`) on GitLab.
+    pub(crate) repo: String,
+    /// Kept in the OS keychain when there is one, and only ever returned redacted.
+    #[serde(default)]
+    pub(crate) token: String,
+    /// Labels added to every created issue.
+    #[serde(default)]
+    pub(crate) labels: Vec<String>,
+}
+
+/// An issue created from a bug or question note, or in a dry run the issue that would be.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ForgeIssue {
+    /// Index of the note in chronological (oldest-first) order.
+    pub(crate) note_index: usize,
+    pub(crate) note_type: String,
+    pub(crate) title: String,
+    pub(crate) body: String,
+    pub(crate) labels: Vec<String>,
+    /// Issue reference such as `#42`; `None` in a dry run.
+    pub(crate) key: Option<String>,
+    pub(crate) url: Option<String>,
+}
+
+/// Turns ticket references in note text into links, e.g. `PROJ-\d+` →
+/// `https://jira.example.com/browse/$0`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LinkRule {
+    /// Optional label used in validation errors.
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    /// Regular expression matched against note text.
+    pub(crate) pattern: String,
+    /// URL template; `$0` is the whole match, `$1`... (or `$name`) capture groups.
+    pub(crate) url: String,
+}
+
+/// Filters for searching the local session history. All fields are optional and combined.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionQuery {
+    /// Case-insensitive substring of the tester name.
+    #[serde(default)]
+    pub(crate) tester: Option<String>,
+    /// Earliest session start (epoch ms, inclusive).
+    #[serde(default)]
+    pub(crate) from: Option<i64>,
+    /// Latest session start (epoch ms, inclusive).
+    #[serde(default)]
+    pub(crate) to: Option<i64>,
+    /// Only sessions containing at least one note of this type.
+    #[serde(default)]
+    pub(crate) note_type: Option<String>,
+    /// Free text; every word must appear in the charter or a single note.
+    #[serde(default)]
+    pub(crate) text: Option<String>,
+    /// Only notes carrying this `#tag` (the `#` is optional).
+    #[serde(default)]
+    pub(crate) tag: Option<String>,
+}
+
+/// A note that matched a history search.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NoteMatch {
+    pub(crate) index: usize,
+    pub(crate) note_type: String,
+    pub(crate) text: String,
+    pub(crate) tags: Vec<String>,
+}
+
+/// An exported session in the history index, as returned by `list_sessions`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionSummary {
+    pub(crate) id: String,
+    pub(crate) export_dir: String,
+    pub(crate) markdown_path: String,
+    pub(crate) tester: Option<String>,
+    pub(crate) charter: String,
+    pub(crate) started_at: i64,
+    /// `started_at` as ISO 8601 with the UTC offset of the report's time zone, when indexed.
+    pub(crate) started_at_iso: Option<String>,
+    pub(crate) duration_minutes: Option<i64>,
+    pub(crate) note_count: usize,
+    /// Notes matching the text / type filters (empty when neither is set).
+    pub(crate) matches: Vec<NoteMatch>,
+}
@@ -1,6 +1,6 @@
 // src/App.test.tsx
 import { describe, it, expect, vi, beforeEach } from "vitest";
-import { render, screen, waitFor, within } from "@testing-library/react";
+import { act, render, screen, waitFor, within } from "@testing-library/react";
 import userEvent from "@testing-library/user-event";
 import App from "./App";
 
@@ -196,4 +196,58 @@ describe("App", () => {
     // Still in main capture UI
     expect(screen.getByPlaceholderText(/Type note/i)).toBeInTheDocument();
   });
+
+  it("notes captured by the backend (diffs, log snippets) are added to the session", async () => {
+    const user = userEvent.setup();
+    renderApp();
+
+    await startSession(user, { testerName: "Del", charter: "My charter" });
+
+    const calls = listenMock.mock.calls as unknown as [string, (event: any) => void][];
+    const onCaptured = calls.find(([name]) => name === "session-note-captured")?.[1];
+    expect(onCaptured).toBeDefined();
+
+    act(() => {
+      onCaptured!({
+        payload: {
+          type: "observation",
+          text: "Screenshot diff (a.png → b.png): 97.0% similar, 1 changed area",
+          timestamp: 1_700_000_000_000,
+        },
+      });
+      onCaptured!({
+        payload: {
+          type: "snippet",
+          text: "app.log:42\nERROR payment declined",
+          timestamp: 1_700_000_005_000,
+        },
+      });
+    });
+
+    await user.click(screen.getByRole("button", { name: /End session/i }));
+    const dialogText = screen.getByText(/End this session and export/i);
+    const dialogRoot = (dialogText.closest("div") as HTMLElement).parentElement as HTMLElement;
+    await user.click(within(dialogRoot).getByRole("button", { name: /End & Export/i }));
+
+    await waitFor(() => {
+      expect(invokeMock).toHaveBeenCalledWith(
+        "export_session_markdown",
+        expect.objectContaining({
+          session: expect.objectContaining({
+            notes: [
+              expect.objectContaining({
+                type: "snippet",
+                text: "app.log:42\nERROR payment declined",
+              }),
+              expect.objectContaining({
+                type: "observation",
+                text: "Screenshot diff (a.png → b.png): 97.0% similar, 1 changed area",
+                timestamp: 1_700_000_000_000,
+              }),
+            ],
+          }),
+        })
+      );
+    });
+  });
 });
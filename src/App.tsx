@@ -11,8 +11,8 @@ import {
   getScreenshotableMonitors,
 } from "tauri-plugin-screenshots-api";
 
-import InstrumentPanel, { DurationMinutes, Note } from "./components/InstrumentPanel";
-import StartSessionModal from "./components/StartSessionModal";
+import InstrumentPanel, { DurationMinutes, Note, NoteType } from "./components/InstrumentPanel";
+import StartSessionModal, { CharterRef } from "./components/StartSessionModal";
 
 type Session = {
   testerName: string;
@@ -20,6 +20,14 @@ type Session = {
   durationMinutes: DurationMinutes;
   startedAt: number; // epoch ms
   notes: Note[];
+  libraryCharter?: CharterRef;
+};
+
+/** A note generated by the backend (screenshot diffs, log snippets). */
+type CapturedNote = {
+  type: NoteType;
+  text: string;
+  timestamp?: number; // epoch ms
 };
 
 type RegionSelection = {
@@ -187,9 +195,26 @@ function App() {
           }
         );
 
+        const fnCaptured = await win.listen<CapturedNote>(
+          "session-note-captured",
+          (event: Event<CapturedNote>) => {
+            if (!sessionRef.current) return;
+
+            const { type, text, timestamp } = event.payload;
+            handleCommit({
+              id: crypto.randomUUID(),
+              timestamp: timestamp ?? Date.now(),
+              type,
+              text,
+            });
+          }
+        );
+
         // If we were unmounted before `listen` resolved, immediately clean up.
         if (disposed) {
           fn();
+          fnClosed();
+          fnCaptured();
           return;
         }
 
@@ -200,6 +225,9 @@ function App() {
           try {
             fnClosed();
           } catch {}
+          try {
+            fnCaptured();
+          } catch {}
         };
       } catch (err) {
         console.error("Failed to register region-selected listener:", err);
@@ -1,10 +1,31 @@
 import { describe, expect, test, vi, beforeEach, afterEach } from "vitest";
-import { render, screen } from "@testing-library/react";
+import { render, screen, waitFor } from "@testing-library/react";
 import userEvent from "@testing-library/user-event";
 
 import InstrumentPanel from "./InstrumentPanel";
 import type { Note } from "./InstrumentPanel";
 
+const invokeMock = vi.fn();
+vi.mock("@tauri-apps/api/core", () => {
+  return {
+    invoke: (cmd: string, args?: any) => invokeMock(cmd, args),
+  };
+});
+
+vi.mock("tauri-plugin-screenshots-api", () => {
+  return {
+    getMonitorScreenshot: vi.fn(async () => "/tmp/fake-frame.png"),
+    getScreenshotableMonitors: vi.fn(async () => [{ id: 1 }]),
+  };
+});
+
+vi.mock("tauri-plugin-macos-permissions-api", () => {
+  return {
+    checkScreenRecordingPermission: vi.fn(async () => true),
+    requestScreenRecordingPermission: vi.fn(async () => {}),
+  };
+});
+
 function renderPanel(overrides?: Partial<React.ComponentProps<typeof InstrumentPanel>>) {
   const onCommit = vi.fn<(note: Note) => void>();
   const onToggleRecap = vi.fn();
@@ -43,6 +64,7 @@ describe("InstrumentPanel", () => {
 
   afterEach(() => {
     vi.restoreAllMocks();
+    invokeMock.mockReset();
   });
 
   test("renders default type label and duration indicator", () => {
@@ -132,4 +154,32 @@ describe("InstrumentPanel", () => {
     renderPanel({ durationMinutes: null });
     expect(screen.getByText("∞")).toBeInTheDocument();
   });
-});
\ No newline at end of file
+
+  test("Record captures a burst and commits it as a recording note", async () => {
+    const user = userEvent.setup();
+
+    invokeMock.mockImplementation(async (cmd: string) => {
+      switch (cmd) {
+        case "start_burst_recording":
+          return { fps: 5, intervalMs: 200, maxSeconds: 10, maxFrames: 50 };
+        case "add_burst_frame":
+          return { frames: 1, done: true };
+        case "finish_burst_recording":
+          return "/tmp/recording-1.gif";
+        default:
+          return null;
+      }
+    });
+
+    const { onCommit } = renderPanel();
+
+    await user.click(screen.getByRole("button", { name: /record/i }));
+
+    await waitFor(() => expect(onCommit).toHaveBeenCalledTimes(1));
+    expect(invokeMock).toHaveBeenCalledWith("add_burst_frame", { path: "/tmp/fake-frame.png" });
+    expect(onCommit.mock.calls[0][0]).toMatchObject({
+      type: "recording",
+      text: "/tmp/recording-1.gif",
+    });
+  });
+});
@@ -18,7 +18,9 @@ export type NoteType =
   | "warning"
   | "question"
   | "snippet"
-  | "screenshot";
+  | "screenshot"
+  | "recording" // animated GIF/APNG from a burst recording
+  | "attachment"; // evidence file snapshotted by `attach_file`
 
 export type DurationMinutes = 30 | 60 | 90 | 120 | null; // null = no limit
 
@@ -62,6 +64,22 @@ const NOTE_TYPE_LABEL: Record<NoteType, string> = {
   question: "Question",
   snippet: "Snippet",
   screenshot: "Screenshot",
+  recording: "Recording",
+  attachment: "Attachment",
+};
+
+/** Capture cadence returned by `start_burst_recording`. */
+type BurstPlan = {
+  fps: number;
+  intervalMs: number;
+  maxSeconds: number;
+  maxFrames: number;
+};
+
+/** Progress returned by `add_burst_frame` after each frame. */
+type BurstStatus = {
+  frames: number;
+  done: boolean;
 };
 
 // Progress fill gradient (easy to tweak colour and opacity in one place)
@@ -86,6 +104,8 @@ export default function InstrumentPanel({
   const [text, setText] = useState("");
 
   const [isCapturing, setIsCapturing] = useState(false);
+  const [isRecording, setIsRecording] = useState(false);
+  const stopRecordingRef = useRef(false);
 
   const textareaRef = useRef<HTMLTextAreaElement | null>(null);
 
@@ -150,44 +170,53 @@ export default function InstrumentPanel({
     setNoteType(NOTE_TYPE_ORDER[n]);
   };
 
-  const captureScreenshot = async () => {
-    if (isCapturing) return;
-
+  // Shared by screenshots and burst recordings. Returns null (after telling the user) when
+  // permission is missing or there is nothing to capture.
+  const firstCapturableMonitor = async (): Promise<number | null> => {
+    // On macOS this requires Screen Recording permission.
+    // On other OSes these calls may no-op or throw, so we guard with try/catch.
     try {
-      setIsCapturing(true);
-
-      // On macOS this requires Screen Recording permission.
-      // On other OSes these calls may no-op or throw, so we guard with try/catch.
-      try {
-        const authorized = await checkScreenRecordingPermission();
-        if (!authorized) {
-          await requestScreenRecordingPermission();
-        }
-      } catch {
-        // Ignore permission helper errors on non-macOS.
+      const authorized = await checkScreenRecordingPermission();
+      if (!authorized) {
+        await requestScreenRecordingPermission();
       }
+    } catch {
+      // Ignore permission helper errors on non-macOS.
+    }
 
-      // Re-check (macOS) and fail fast with a helpful message.
-      try {
-        const authorized = await checkScreenRecordingPermission();
-        if (!authorized) {
-          window.alert(
-            "Rapid Reporter needs Screen Recording permission to capture screenshots.\n\nGo to System Settings → Privacy & Security → Screen Recording, enable Rapid Reporter, then restart the app."
-          );
-          return;
-        }
-      } catch {
-        // Non-macOS: carry on.
+    // Re-check (macOS) and fail fast with a helpful message.
+    try {
+      const authorized = await checkScreenRecordingPermission();
+      if (!authorized) {
+        window.alert(
+          "Rapid Reporter needs Screen Recording permission to capture screenshots.\n\nGo to System Settings → Privacy & Security → Screen Recording, enable Rapid Reporter, then restart the app."
+        );
+        return null;
       }
+    } catch {
+      // Non-macOS: carry on.
+    }
 
-      const monitors = await getScreenshotableMonitors();
-      const first = (monitors as any[])?.[0];
-      if (!first || typeof first.id !== "number") {
-        window.alert("No monitors available for screenshot capture.");
-        return;
-      }
+    const monitors = await getScreenshotableMonitors();
+    const first = (monitors as any[])?.[0];
+    if (!first || typeof first.id !== "number") {
+      window.alert("No monitors available for screenshot capture.");
+      return null;
+    }
+
+    return first.id;
+  };
 
-      const path = await getMonitorScreenshot(first.id);
+  const captureScreenshot = async () => {
+    if (isCapturing || isRecording) return;
+
+    try {
+      setIsCapturing(true);
+
+      const monitorId = await firstCapturableMonitor();
+      if (monitorId === null) return;
+
+      const path = await getMonitorScreenshot(monitorId);
 
       let finalPath = path;
       try {
@@ -215,6 +244,59 @@ export default function InstrumentPanel({
     }
   };
 
+  // Grabs frames at the backend's cadence until the burst is full or the tester clicks Stop,
+  // then commits the encoded animation as a `recording` note.
+  const recordBurst = async () => {
+    if (isRecording) {
+      stopRecordingRef.current = true;
+      return;
+    }
+    if (isCapturing) return;
+
+    try {
+      setIsRecording(true);
+      stopRecordingRef.current = false;
+
+      const monitorId = await firstCapturableMonitor();
+      if (monitorId === null) return;
+
+      const plan = await invoke<BurstPlan>("start_burst_recording", {});
+
+      try {
+        let frames = 0;
+        while (!stopRecordingRef.current) {
+          const framePath = await getMonitorScreenshot(monitorId);
+          const status = await invoke<BurstStatus>("add_burst_frame", { path: framePath });
+          frames = status.frames;
+          if (status.done) break;
+          await new Promise((resolve) => window.setTimeout(resolve, plan.intervalMs));
+        }
+
+        if (frames === 0) {
+          await invoke("cancel_burst_recording");
+          return;
+        }
+
+        const path = await invoke<string>("finish_burst_recording", {});
+        onCommit({
+          id: crypto.randomUUID(),
+          timestamp: Date.now(),
+          type: "recording",
+          text: path,
+        });
+      } catch (err) {
+        await invoke("cancel_burst_recording").catch(() => {});
+        throw err;
+      }
+    } catch (err) {
+      console.error("Burst recording failed:", err);
+      window.alert("Recording failed. Check permissions and try again.");
+    } finally {
+      setIsRecording(false);
+      requestAnimationFrame(() => textareaRef.current?.focus());
+    }
+  };
+
   const openRegionCapture = async () => {
     // Extra guard (in addition to the button's disabled state)
     if (isCapturing || isRegionCapturing) return;
@@ -377,10 +459,10 @@ export default function InstrumentPanel({
           <button
             type="button"
             onClick={captureScreenshot}
-            disabled={isCapturing}
+            disabled={isCapturing || isRecording}
             className={[
               "rounded border border-black/20 bg-white/40 px-2 py-1 text-black/70 hover:bg-white/60",
-              isCapturing ? "opacity-50 cursor-not-allowed" : "",
+              isCapturing || isRecording ? "opacity-50 cursor-not-allowed" : "",
             ].join(" ")}
           >
             {isCapturing ? "Please wait…" : "📷 Screenshot"}
@@ -396,6 +478,18 @@ export default function InstrumentPanel({
           >
             {isRegionCapturing ? "Please wait…" : "▭ Region"}
           </button>
+          <button
+            type="button"
+            onClick={recordBurst}
+            disabled={isCapturing}
+            className={[
+              "rounded border border-black/20 bg-white/40 px-2 py-1 text-black/70 hover:bg-white/60",
+              isCapturing ? "opacity-50 cursor-not-allowed" : "",
+            ].join(" ")}
+            title="Record a short animated capture"
+          >
+            {isRecording ? "■ Stop" : "⏺ Record"}
+          </button>
           <button
             type="button"
             onClick={onToggleRecap}
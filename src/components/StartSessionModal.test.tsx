@@ -1,5 +1,5 @@
 import { describe, test, expect, vi } from "vitest";
-import { render, screen } from "@testing-library/react";
+import { render, screen, waitFor } from "@testing-library/react";
 import userEvent from "@testing-library/user-event";
 import StartSessionModal from "./StartSessionModal";
 
@@ -9,6 +9,12 @@ vi.mock("@tauri-apps/api/webviewWindow", () => ({
   }),
 }));
 
+// No charter library by default, so the picker stays hidden.
+const invokeMock = vi.fn(async (_cmd: string, _args?: any): Promise<any> => []);
+vi.mock("@tauri-apps/api/core", () => ({
+  invoke: (cmd: string, args?: any) => invokeMock(cmd, args),
+}));
+
 function getCharterField(): HTMLElement {
   // Prefer an accessible label if present.
   const byLabel = screen.queryByLabelText(/charter/i);
@@ -88,4 +94,37 @@ describe("StartSessionModal", () => {
     expect(onStart).toHaveBeenCalledTimes(1);
     expect(onStart.mock.calls[0][0].charter).toBe("My charter");
   });
-});
\ No newline at end of file
+
+  test("Picking a library charter fills the charter and is passed to onStart", async () => {
+    const user = userEvent.setup();
+    const onStart = vi.fn();
+
+    invokeMock.mockImplementation(async (cmd: string) => {
+      if (cmd === "list_charters") {
+        return [{ id: "c1", title: "Checkout", usageCount: 2 }];
+      }
+      if (cmd === "use_charter") {
+        return { id: "c1", title: "Checkout", text: "Explore checkout\nWith expired cards" };
+      }
+      return undefined;
+    });
+
+    render(<StartSessionModal onStart={onStart} />);
+
+    await fillTesterIfPresent(user);
+
+    const picker = await screen.findByRole("combobox", { name: /from library/i });
+    await user.selectOptions(picker, "c1");
+
+    await waitFor(() =>
+      expect((getCharterField() as HTMLTextAreaElement).value).toBe(
+        "Explore checkout\nWith expired cards"
+      )
+    );
+    expect(invokeMock).toHaveBeenCalledWith("use_charter", { id: "c1" });
+
+    await user.click(screen.getByRole("button", { name: /start/i }));
+
+    expect(onStart.mock.calls[0][0].libraryCharter).toEqual({ id: "c1", title: "Checkout" });
+  });
+});
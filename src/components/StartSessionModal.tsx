@@ -1,12 +1,25 @@
 import React, { useEffect, useMemo, useState } from "react";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import { invoke } from "@tauri-apps/api/core";
 import type { DurationMinutes } from "./InstrumentPanel";
 
+/** The library charter a session was started from; the export records it. */
+export type CharterRef = {
+  id: string;
+  title: string;
+};
+
+/** A charter in the backend charter library (`list_charters`). */
+type LibraryCharter = CharterRef & {
+  usageCount: number;
+};
+
 export type StartSessionConfig = {
   testerName: string;
   charter: string;
   durationMinutes: DurationMinutes;
   startedAt: number;
+  libraryCharter?: CharterRef;
 };
 
 export default function StartSessionModal({
@@ -31,6 +44,26 @@ export default function StartSessionModal({
   }, []);
 
   const [charter, setCharter] = useState("");
+  const [libraryCharter, setLibraryCharter] = useState<CharterRef | undefined>(undefined);
+  const [library, setLibrary] = useState<LibraryCharter[]>([]);
+
+  // The library is optional: without one (or without a backend) the picker stays hidden.
+  useEffect(() => {
+    invoke<LibraryCharter[]>("list_charters", {})
+      .then((charters) => setLibrary(Array.isArray(charters) ? charters : []))
+      .catch(() => setLibrary([]));
+  }, []);
+
+  const pickLibraryCharter = async (id: string) => {
+    if (!id) return;
+    try {
+      const picked = await invoke<CharterRef & { text: string }>("use_charter", { id });
+      setCharter(picked.text);
+      setLibraryCharter({ id: picked.id, title: picked.title });
+    } catch (err) {
+      console.error("Could not use library charter:", err);
+    }
+  };
   const [durationMinutes, setDurationMinutes] = useState<DurationMinutes>(60);
 
   const canStart = useMemo(
@@ -82,10 +115,31 @@ export default function StartSessionModal({
           autoFocus={testerName.trim().length === 0}
         />
 
-        <label className="block text-sm text-black/70 mb-1">Charter</label>
+        <div className="mb-1 flex items-baseline justify-between gap-2">
+          <label className="block text-sm text-black/70">Charter</label>
+          {library.length > 0 && (
+            <select
+              aria-label="From library"
+              value={libraryCharter?.id ?? ""}
+              onChange={(e) => pickLibraryCharter(e.target.value)}
+              className="max-w-[60%] truncate rounded border border-black/20 bg-white/80 px-2 py-0.5 text-xs text-black/80 outline-none"
+            >
+              <option value="">From library…</option>
+              {library.map((c) => (
+                <option key={c.id} value={c.id}>
+                  {c.title}
+                </option>
+              ))}
+            </select>
+          )}
+        </div>
         <textarea
           value={charter}
-          onChange={(e) => setCharter(e.target.value)}
+          onChange={(e) => {
+            setCharter(e.target.value);
+            // Clearing the text means the session no longer follows the library charter.
+            if (e.target.value.trim().length === 0) setLibraryCharter(undefined);
+          }}
           placeholder="What are you testing in this session?"
           className="w-full h-20 resize-none rounded border border-black/20 bg-white/80 px-3 py-2 outline-none focus:border-black/40 focus:ring-2 focus:ring-black/10"
           autoFocus={testerName.trim().length > 0}
@@ -136,6 +190,7 @@ export default function StartSessionModal({
             onClick={() => {
               setTesterName("");
               setCharter("");
+              setLibraryCharter(undefined);
               setDurationMinutes(60);
               try {
                 window.localStorage.removeItem("rapidReporter.testerName");
@@ -162,6 +217,7 @@ export default function StartSessionModal({
                 charter: charter.trim(),
                 durationMinutes,
                 startedAt: Date.now(),
+                libraryCharter,
               });
             }}
           >